@@ -1,18 +1,23 @@
 use crate::config::Config;
+use crate::error::{self, Error};
 use crate::types::{CheckResult, CheckStatus};
-use crate::utils::registry;
-
-#[cfg(target_os = "windows")]
-use windows::Win32::System::Registry::*;
+use crate::utils::powercfg::{self, PowerSetting, SchemeDetails};
+use crate::checks::defaults;
+use crate::hardware::cpu::{self, CpuInfo};
+use crate::hardware::microarch::{self, Capability};
 
 pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
+    // Query the active scheme once and share it between the power checks
+    let scheme = current_scheme();
+    let scheme = scheme.as_ref();
+
     vec![
-        check_power_plan(),
-        check_c_states(),
-        check_core_parking(),
-        check_boost_mode(),
-        check_throttle_min(),
-        check_throttle_max(),
+        check_power_plan(scheme),
+        check_c_states(scheme),
+        check_core_parking(scheme),
+        check_boost_mode(scheme),
+        check_throttle_min(scheme),
+        check_throttle_max(scheme),
         check_vbs(),
         check_hvci(),
         check_mitigations(),
@@ -25,7 +30,7 @@ pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
         check_cppc(),
         check_cache_prefetcher(),
         check_thread_director(),
-        check_efficiency_parking(),
+        check_efficiency_parking(scheme),
         check_performance_priority(),
         check_curve_optimizer(),
         check_affinity(),
@@ -41,73 +46,135 @@ pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
     ]
 }
 
-fn check_power_plan() -> CheckResult {
-    CheckResult {
-        name: "Power Plan".to_string(),
-        category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Power plan requires powercfg enumeration".to_string(),
-    }
+#[cfg(target_os = "windows")]
+fn current_scheme() -> error::Result<SchemeDetails> {
+    powercfg::query_scheme("SCHEME_CURRENT")
 }
 
-fn check_c_states() -> CheckResult {
-    CheckResult {
-        name: "C-States".to_string(),
-        category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "C-States require power configuration check".to_string(),
-    }
+#[cfg(not(target_os = "windows"))]
+fn current_scheme() -> error::Result<SchemeDetails> {
+    Err(Error::unsupported("Power scheme checks"))
 }
 
-fn check_core_parking() -> CheckResult {
+/// Evaluates the AC value of a processor power setting in the active scheme.
+fn processor_setting_check(
+    name: &str,
+    scheme: Result<&SchemeDetails, &Error>,
+    setting: &str,
+    evaluate: impl Fn(&PowerSetting, u32) -> (CheckStatus, String),
+) -> CheckResult {
+    let (status, detail) = match scheme {
+        Ok(scheme) => match scheme.setting(powercfg::SUB_PROCESSOR, setting).and_then(|s| s.ac.map(|ac| (s, ac))) {
+            Some((setting, ac)) => evaluate(setting, ac),
+            None => (CheckStatus::Info, format!("Not exposed by power scheme {}", scheme.name)),
+        },
+        Err(e) => return CheckResult::unreadable(name, "CPU", e),
+    };
+
     CheckResult {
-        name: "Core Parking".to_string(),
+        name: name.to_string(),
         category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Core parking requires power configuration check".to_string(),
+        status,
+        detail,
+        error: None,
     }
 }
 
-fn check_boost_mode() -> CheckResult {
-    CheckResult {
-        name: "Turbo Boost".to_string(),
-        category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Boost mode requires power configuration check".to_string(),
+/// Why a microarchitecture-specific feature does not apply to this CPU, or
+/// `None` when it does.
+fn not_applicable(cpu: &CpuInfo, capability: Capability) -> Option<String> {
+    if cpu.has(capability) {
+        return None;
     }
-}
 
-fn check_throttle_min() -> CheckResult {
-    CheckResult {
-        name: "Minimum Processor State".to_string(),
-        category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Min throttle requires power configuration check".to_string(),
+    let since = capability.since();
+    if microarch::vendor(&cpu.vendor) != since.vendor() {
+        return Some(format!("Not applicable: {} is specific to {} CPUs", capability, since.vendor()));
+    }
+    if let Some(m) = cpu.microarch.as_ref().filter(|m| m.uarch.at_least(since)) {
+        return Some(format!("Not applicable: {} is not available on {} CPUs ({})", capability, m.segment, m.generation));
     }
+    let cpu_is = match &cpu.microarch {
+        Some(m) => format!("this CPU is {}", m.uarch),
+        None => format!("family {:x}h model {:x}h is not a known microarchitecture", cpu.family, cpu.model),
+    };
+    Some(format!("Not applicable: {} needs {} {} or newer; {}", capability, since.vendor(), since, cpu_is))
 }
 
-fn check_throttle_max() -> CheckResult {
+fn check_power_plan(scheme: Result<&SchemeDetails, &Error>) -> CheckResult {
+    let (status, detail) = match scheme {
+        Ok(scheme) => {
+            let high_performance = [
+                powercfg::SCHEME_HIGH_PERFORMANCE,
+                powercfg::SCHEME_ULTIMATE_PERFORMANCE,
+                powercfg::SCHEME_APEX_ULTIMATE,
+            ].contains(&scheme.guid.as_str());
+
+            (
+                if high_performance { CheckStatus::Ok } else { CheckStatus::Warn },
+                format!("Active plan: {} ({})", scheme.name, scheme.guid),
+            )
+        }
+        Err(e) => return CheckResult::unreadable("Power Plan", "CPU", e),
+    };
+
     CheckResult {
-        name: "Maximum Processor State".to_string(),
+        name: "Power Plan".to_string(),
         category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Max throttle requires power configuration check".to_string(),
+        status,
+        detail,
+        error: None,
     }
 }
 
+fn check_c_states(scheme: Result<&SchemeDetails, &Error>) -> CheckResult {
+    processor_setting_check("C-States", scheme, powercfg::SETTING_IDLE_DISABLE, |_, ac| (
+        CheckStatus::Info,
+        format!("Processor idle states {} (AC)", if ac == 1 { "disabled" } else { "enabled" }),
+    ))
+}
+
+fn check_core_parking(scheme: Result<&SchemeDetails, &Error>) -> CheckResult {
+    processor_setting_check("Core Parking", scheme, powercfg::SETTING_CORE_PARKING_MIN, |_, ac| (
+        if ac >= 100 { CheckStatus::Ok } else { CheckStatus::Warn },
+        format!("Minimum unparked cores: {}% (AC)", ac),
+    ))
+}
+
+fn check_boost_mode(scheme: Result<&SchemeDetails, &Error>) -> CheckResult {
+    processor_setting_check("Turbo Boost", scheme, powercfg::SETTING_BOOST_MODE, |setting, ac| (
+        if ac == 0 { CheckStatus::Warn } else { CheckStatus::Ok },
+        format!("Boost mode: {} (AC)", setting.display_value(ac)),
+    ))
+}
+
+fn check_throttle_min(scheme: Result<&SchemeDetails, &Error>) -> CheckResult {
+    processor_setting_check("Minimum Processor State", scheme, powercfg::SETTING_THROTTLE_MIN, |_, ac| (
+        CheckStatus::Info,
+        format!("Minimum processor state: {}% (AC)", ac),
+    ))
+}
+
+fn check_throttle_max(scheme: Result<&SchemeDetails, &Error>) -> CheckResult {
+    processor_setting_check("Maximum Processor State", scheme, powercfg::SETTING_THROTTLE_MAX, |_, ac| (
+        if ac >= 100 { CheckStatus::Ok } else { CheckStatus::Warn },
+        format!("Maximum processor state: {}% (AC)", ac),
+    ))
+}
+
 #[cfg(target_os = "windows")]
 fn check_vbs() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\DeviceGuard",
-        "EnableVirtualizationBasedSecurity"
-    ).unwrap_or(0);
+    let status = match defaults::read("Virtualization Based Security") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("Virtualization Based Security", "CPU", &e),
+    };
     
     CheckResult {
         name: "Virtualization Based Security".to_string(),
         category: "CPU".to_string(),
         status: if status == 0 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("VBS enabled: {}", status == 1),
+        detail: format!("VBS enabled: {}{}", status == 1, defaults::note("Virtualization Based Security")),
+        error: None,
     }
 }
 
@@ -118,22 +185,23 @@ fn check_vbs() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_hvci() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\DeviceGuard\\Scenarios\\HypervisorEnforcedCodeIntegrity",
-        "Enabled"
-    ).unwrap_or(0);
+    let status = match defaults::read("Hypervisor-Enforced Code Integrity") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("Hypervisor-Enforced Code Integrity", "CPU", &e),
+    };
     
     CheckResult {
         name: "Hypervisor-Enforced Code Integrity".to_string(),
         category: "CPU".to_string(),
         status: if status == 0 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("HVCI enabled: {}", status == 1),
+        detail: format!("HVCI enabled: {}{}", status == 1, defaults::note("Hypervisor-Enforced Code Integrity")),
+        error: None,
     }
 }
 
@@ -144,22 +212,23 @@ fn check_hvci() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_mitigations() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Memory Management",
-        "FeatureSettingsOverride"
-    ).unwrap_or(0);
+    let status = match defaults::read("CPU Mitigations") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("CPU Mitigations", "CPU", &e),
+    };
     
     CheckResult {
         name: "CPU Mitigations".to_string(),
         category: "CPU".to_string(),
         status: if status == 3 { CheckStatus::Ok } else { CheckStatus::Info },
-        detail: format!("Mitigations override: {}", status),
+        detail: format!("Mitigations override: {}{}", status, defaults::note("CPU Mitigations")),
+        error: None,
     }
 }
 
@@ -170,60 +239,119 @@ fn check_mitigations() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 fn check_heterogeneous_scheduler() -> CheckResult {
+    let cpu = cpu::info();
+    let detail = if cpu.hybrid {
+        format!("Hybrid CPU: {} P-cores, {} E-cores", cpu.performance_cores, cpu.efficiency_cores)
+    } else {
+        "Not a hybrid CPU: all cores are of one type".to_string()
+    };
+
     CheckResult {
         name: "Heterogeneous Scheduler".to_string(),
         category: "CPU".to_string(),
         status: CheckStatus::Info,
-        detail: "Heterogeneous scheduler requires CPU detection".to_string(),
+        detail,
+        error: None,
     }
 }
 
 fn check_smt() -> CheckResult {
+    let cpu = cpu::info();
+    let detail = format!(
+        "SMT {}: {} cores, {} threads",
+        if cpu.smt { "enabled" } else { "disabled or not supported" },
+        cpu.cores,
+        cpu.threads,
+    );
+
     CheckResult {
         name: "Simultaneous Multithreading".to_string(),
         category: "CPU".to_string(),
         status: CheckStatus::Info,
-        detail: "SMT status requires CPU enumeration".to_string(),
+        detail,
+        error: None,
     }
 }
 
 fn check_speedshift() -> CheckResult {
+    let cpu = cpu::info();
+    let (status, detail) = match (not_applicable(cpu, Capability::SpeedShift), &cpu.microarch) {
+        (None, Some(m)) if cpu.hardware_pstates => (CheckStatus::Ok, format!("Speed Shift active on {}", m)),
+        (None, Some(m)) if cpu.hypervisor => (
+            CheckStatus::Info,
+            format!("Speed Shift supported by {} but not exposed to this virtual machine", m),
+        ),
+        (None, Some(m)) => (
+            CheckStatus::Warn,
+            format!("Speed Shift supported by {} but disabled in firmware", m),
+        ),
+        (reason, _) => (CheckStatus::Info, reason.unwrap_or_default()),
+    };
+
     CheckResult {
         name: "Intel Speed Shift".to_string(),
         category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Speed Shift requires Intel CPU detection".to_string(),
+        status,
+        detail,
+        error: None,
     }
 }
 
 fn check_pbo() -> CheckResult {
+    let cpu = cpu::info();
+    let detail = match (not_applicable(cpu, Capability::PrecisionBoostOverdrive), &cpu.microarch) {
+        (None, Some(m)) => format!("PBO supported by {}; set in BIOS or Ryzen Master", m),
+        (reason, _) => reason.unwrap_or_default(),
+    };
+
     CheckResult {
         name: "Precision Boost Overdrive".to_string(),
         category: "CPU".to_string(),
         status: CheckStatus::Info,
-        detail: "PBO requires AMD CPU detection".to_string(),
+        detail,
+        error: None,
     }
 }
 
 fn check_per_core_pstates() -> CheckResult {
+    let cpu = cpu::info();
+    let (status, detail) = if cpu.hardware_pstates {
+        (CheckStatus::Ok, "Hardware-controlled per-core P-states (HWP/CPPC) supported".to_string())
+    } else if cpu.hypervisor {
+        (CheckStatus::Info, "HWP/CPPC not exposed to this virtual machine".to_string())
+    } else {
+        (CheckStatus::Info, "CPU reports neither HWP nor CPPC; P-states are OS-controlled".to_string())
+    };
+
     CheckResult {
         name: "Per-Core P-States".to_string(),
         category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Per-core P-states require CPU enumeration".to_string(),
+        status,
+        detail,
+        error: None,
     }
 }
 
 fn check_tvb() -> CheckResult {
+    let cpu = cpu::info();
+    let core_i9 = cpu.name.contains("i9") || cpu.name.contains("Ultra 9");
+    let detail = match (not_applicable(cpu, Capability::ThermalVelocityBoost), &cpu.microarch) {
+        (None, Some(m)) if core_i9 => format!("TVB available on this Core i9 ({})", m),
+        (None, Some(_)) => "Not applicable: TVB is limited to Core i9 parts".to_string(),
+        (reason, _) => reason.unwrap_or_default(),
+    };
+
     CheckResult {
         name: "Thermal Velocity Boost".to_string(),
         category: "CPU".to_string(),
         status: CheckStatus::Info,
-        detail: "TVB requires Intel CPU detection".to_string(),
+        detail,
+        error: None,
     }
 }
 
@@ -233,6 +361,7 @@ fn check_cppc() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "CPPC requires power configuration check".to_string(),
+        error: None,
     }
 }
 
@@ -242,25 +371,50 @@ fn check_cache_prefetcher() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Cache prefetcher requires MSR access".to_string(),
+        error: None,
     }
 }
 
 fn check_thread_director() -> CheckResult {
+    let cpu = cpu::info();
+    let (status, detail) = match (cpu.hybrid, cpu.thread_director, defaults::windows_build()) {
+        (false, _, _) => (CheckStatus::Info, "Not applicable: no hybrid cores".to_string()),
+        (true, false, _) => (CheckStatus::Info, "Hybrid CPU without Thread Director".to_string()),
+        (true, true, Some(build)) if build < 22000 => (
+            CheckStatus::Warn,
+            format!("Thread Director supported, but Windows build {} does not use it (needs Windows 11)", build),
+        ),
+        (true, true, Some(_)) => (CheckStatus::Ok, "Thread Director supported and used by Windows".to_string()),
+        (true, true, None) => (CheckStatus::Info, "Thread Director supported by the CPU".to_string()),
+    };
+
     CheckResult {
         name: "Intel Thread Director".to_string(),
         category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "Thread Director requires Intel 12th gen+ detection".to_string(),
+        status,
+        detail,
+        error: None,
     }
 }
 
-fn check_efficiency_parking() -> CheckResult {
-    CheckResult {
-        name: "Efficiency Core Parking".to_string(),
-        category: "CPU".to_string(),
-        status: CheckStatus::Info,
-        detail: "E-core parking requires heterogeneous CPU detection".to_string(),
+fn check_efficiency_parking(scheme: Result<&SchemeDetails, &Error>) -> CheckResult {
+    let cpu = cpu::info();
+    if !cpu.hybrid {
+        return CheckResult {
+            name: "Efficiency Core Parking".to_string(),
+            category: "CPU".to_string(),
+            status: CheckStatus::Info,
+            detail: "Not applicable: no E-cores".to_string(),
+            error: None,
+        };
     }
+
+    // On hybrid CPUs the base parking setting applies to efficiency class 0,
+    // the E-cores; P-cores have their own class 1 setting
+    processor_setting_check("Efficiency Core Parking", scheme, powercfg::SETTING_CORE_PARKING_MIN, |_, ac| (
+        CheckStatus::Info,
+        format!("Minimum unparked E-cores: {}% of {} (AC)", ac, cpu.efficiency_cores),
+    ))
 }
 
 fn check_performance_priority() -> CheckResult {
@@ -269,15 +423,23 @@ fn check_performance_priority() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "P-core priority requires heterogeneous CPU detection".to_string(),
+        error: None,
     }
 }
 
 fn check_curve_optimizer() -> CheckResult {
+    let cpu = cpu::info();
+    let detail = match (not_applicable(cpu, Capability::CurveOptimizer), &cpu.microarch) {
+        (None, Some(m)) => format!("Curve Optimizer supported by {}; per-core offsets are set in BIOS", m),
+        (reason, _) => reason.unwrap_or_default(),
+    };
+
     CheckResult {
         name: "AMD Curve Optimizer".to_string(),
         category: "CPU".to_string(),
         status: CheckStatus::Info,
-        detail: "Curve Optimizer requires AMD Ryzen 5000+ detection".to_string(),
+        detail,
+        error: None,
     }
 }
 
@@ -287,6 +449,7 @@ fn check_affinity() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Process affinity requires process enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -296,6 +459,7 @@ fn check_scheduling_class() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Scheduling class requires process enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -305,6 +469,7 @@ fn check_quantum() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Thread quantum requires system configuration check".to_string(),
+        error: None,
     }
 }
 
@@ -314,6 +479,7 @@ fn check_priority_boost() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Priority boost requires process enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -323,22 +489,23 @@ fn check_background_throttling() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Background throttling requires power configuration check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_foreground_separation() -> CheckResult {
-    let value = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\PriorityControl",
-        "Win32PrioritySeparation"
-    ).unwrap_or(2);
+    let value = match defaults::read("Foreground/Background Separation") {
+        Ok(value) => value,
+        Err(e) => return CheckResult::unreadable("Foreground/Background Separation", "CPU", &e),
+    };
     
     CheckResult {
         name: "Foreground/Background Separation".to_string(),
         category: "CPU".to_string(),
         status: CheckStatus::Info,
-        detail: format!("Priority separation: {}", value),
+        detail: format!("Priority separation: {}{}", value, defaults::note("Foreground/Background Separation")),
+        error: None,
     }
 }
 
@@ -349,6 +516,7 @@ fn check_foreground_separation() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
@@ -358,6 +526,7 @@ fn check_cpu_set() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "CPU sets require process enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -367,6 +536,7 @@ fn check_idle_states() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Idle states require power configuration check".to_string(),
+        error: None,
     }
 }
 
@@ -376,6 +546,7 @@ fn check_power_limits() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Power limits require CPU monitoring".to_string(),
+        error: None,
     }
 }
 
@@ -385,5 +556,6 @@ fn check_frequency_scaling() -> CheckResult {
         category: "CPU".to_string(),
         status: CheckStatus::Info,
         detail: "Frequency scaling requires power configuration check".to_string(),
+        error: None,
     }
 }
@@ -0,0 +1,189 @@
+use crate::checks::tweaks::TWEAK_SIGNATURES;
+#[cfg(target_os = "windows")]
+use crate::error::Result;
+use crate::types::Change;
+#[cfg(target_os = "windows")]
+use crate::utils::registry;
+use crate::utils::registry::RegPath;
+use std::sync::OnceLock;
+
+/// What Windows ships with for a registry value a check reads.
+pub struct DefaultValue {
+    pub check: &'static str,
+    pub category: &'static str,
+    pub key: RegPath,
+    pub value: &'static str,
+    /// Stock value, or `None` when a clean install does not set it.
+    pub default: Option<u32>,
+    /// What Windows behaves as when the value is missing.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub fallback: u32,
+    /// First build this row applies to; a later row for the same value
+    /// overrides it from its own build on.
+    pub min_build: u32,
+    pub reboot: bool,
+}
+
+const fn row(
+    check: &'static str,
+    category: &'static str,
+    key: RegPath,
+    value: &'static str,
+    default: Option<u32>,
+    fallback: u32,
+    reboot: bool,
+) -> DefaultValue {
+    DefaultValue { check, category, key, value, default, fallback, min_build: 0, reboot }
+}
+
+impl DefaultValue {
+    const fn since(mut self, build: u32) -> Self {
+        self.min_build = build;
+        self
+    }
+}
+
+const KERNEL: RegPath = RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\Session Manager\\kernel");
+const SYSTEM_PROFILE: RegPath = RegPath::hklm("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Multimedia\\SystemProfile");
+const PRIORITY_CONTROL: RegPath = RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\PriorityControl");
+const MEMORY_MANAGEMENT: RegPath = RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Memory Management");
+const GRAPHICS_DRIVERS: RegPath = RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\GraphicsDrivers");
+const HVCI: RegPath = RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\DeviceGuard\\Scenarios\\HypervisorEnforcedCodeIntegrity");
+
+pub const DEFAULTS: &[DefaultValue] = &[
+    row("HPET Status", "Latency", RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\TimeProviders\\TimerResolution"), "HPETDisabled", None, 0, true),
+    row("TSC Sync Policy", "Latency", KERNEL, "UsePlatformClock", None, 0, true),
+    row("Dynamic Tick", "Latency", KERNEL, "DisableDynamicTick", None, 0, true),
+    row("MMCSS System Responsiveness", "Latency", SYSTEM_PROFILE, "SystemResponsiveness", Some(20), 20, false),
+    row("Network Throttling", "Latency", SYSTEM_PROFILE, "NetworkThrottlingIndex", Some(10), 10, false),
+    row("Priority Separation", "Latency", PRIORITY_CONTROL, "Win32PrioritySeparation", Some(2), 2, false),
+    row("Timer Coalescing", "Latency", KERNEL, "CoalescingTimerInterval", None, 0, true),
+    row("Virtualization Based Security", "CPU", RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\DeviceGuard"), "EnableVirtualizationBasedSecurity", None, 0, true),
+    row("Hypervisor-Enforced Code Integrity", "CPU", HVCI, "Enabled", None, 0, true),
+    // Clean installs of Windows 11 turn memory integrity on
+    row("Hypervisor-Enforced Code Integrity", "CPU", HVCI, "Enabled", Some(1), 0, true).since(22000),
+    row("CPU Mitigations", "CPU", MEMORY_MANAGEMENT, "FeatureSettingsOverride", None, 0, true),
+    row("CPU Mitigations", "CPU", MEMORY_MANAGEMENT, "FeatureSettingsOverrideMask", None, 0, true),
+    row("Foreground/Background Separation", "CPU", PRIORITY_CONTROL, "Win32PrioritySeparation", Some(2), 2, false),
+    row("Hardware Accelerated GPU Scheduling", "GPU", GRAPHICS_DRIVERS, "HwSchMode", None, 1, true),
+    row("TDR Level", "GPU", GRAPHICS_DRIVERS, "TdrLevel", None, 3, true),
+    row("TDR Delay", "GPU", GRAPHICS_DRIVERS, "TdrDelay", None, 2, true),
+    row("Game DVR", "GPU", RegPath::hkcu("System\\GameConfigStore"), "GameDVR_Enabled", Some(1), 1, false),
+];
+
+/// `CurrentBuildNumber` of the running Windows, read once.
+pub fn windows_build() -> Option<u32> {
+    static BUILD: OnceLock<Option<u32>> = OnceLock::new();
+    *BUILD.get_or_init(read_build)
+}
+
+#[cfg(target_os = "windows")]
+fn read_build() -> Option<u32> {
+    registry::read_string(&RegPath::hklm("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion"), "CurrentBuildNumber")
+        .ok()
+        .and_then(|b| b.trim().parse().ok())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_build() -> Option<u32> {
+    None
+}
+
+/// The rows of a check that apply to this build, one per value, in table
+/// order.
+pub fn for_check(check: &str) -> Vec<&'static DefaultValue> {
+    let build = windows_build().unwrap_or(0);
+    let mut rows: Vec<&'static DefaultValue> = Vec::new();
+
+    for row in DEFAULTS.iter().filter(|d| d.check == check && d.min_build <= build) {
+        match rows.iter_mut().find(|r| r.value == row.value) {
+            Some(existing) if row.min_build >= existing.min_build => *existing = row,
+            Some(_) => {}
+            None => rows.push(row),
+        }
+    }
+    rows
+}
+
+/// Reads the value a check looks at, or what Windows assumes when it is
+/// missing. Other failures, such as access denied, are passed on.
+#[cfg(target_os = "windows")]
+pub fn read(check: &str) -> Result<u32> {
+    let Some(row) = for_check(check).into_iter().next() else { return Ok(0) };
+    match registry::read_dword(&row.key, row.value) {
+        Err(e) if e.is_not_found() => Ok(row.fallback),
+        other => other,
+    }
+}
+
+/// Suffix for check details naming the stock value.
+#[cfg(target_os = "windows")]
+pub fn note(check: &str) -> String {
+    match for_check(check).first() {
+        Some(DefaultValue { value, default: Some(data), .. }) => format!(" (default: {} = {})", value, data),
+        Some(DefaultValue { value, default: None, .. }) => format!(" (default: {} not set)", value),
+        None => String::new(),
+    }
+}
+
+/// Command-line id of a check, e.g. `mmcss-system-responsiveness`.
+pub fn check_id(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Checks that `reset` knows defaults for, as `(category, check)` pairs.
+pub fn resettable() -> Vec<(&'static str, &'static str)> {
+    let mut checks: Vec<(&'static str, &'static str)> = Vec::new();
+    let all = DEFAULTS
+        .iter()
+        .map(|d| (d.category, d.check))
+        .chain(TWEAK_SIGNATURES.iter().map(|s| ("Tweaks", s.name)));
+    for entry in all {
+        if !checks.contains(&entry) {
+            checks.push(entry);
+        }
+    }
+    checks
+}
+
+/// Changes that put a check id or a whole category back to the Windows
+/// defaults, and whether any of them needs a restart. Empty when nothing
+/// matches the target.
+pub fn reset_changes(target: &str) -> (Vec<Change>, bool) {
+    let matches = |category: &str, check: &str| {
+        category.eq_ignore_ascii_case(target) || check_id(check) == check_id(target)
+    };
+
+    let mut changes = Vec::new();
+    let mut reboot = false;
+
+    for (category, check) in resettable() {
+        if !matches(category, check) {
+            continue;
+        }
+        for row in for_check(check) {
+            changes.push(match row.default {
+                Some(data) => Change::RegistryDword {
+                    key: row.key.clone(),
+                    value: row.value.to_string(),
+                    data,
+                },
+                None => Change::RegistryDelete {
+                    key: row.key.clone(),
+                    value: row.value.to_string(),
+                },
+            });
+            reboot |= row.reboot;
+        }
+        for signature in TWEAK_SIGNATURES.iter().filter(|s| s.name == check) {
+            changes.extend(signature.settings.iter().map(|s| s.revert()));
+            reboot |= signature.reboot;
+        }
+    }
+
+    (changes, reboot)
+}
@@ -1,9 +1,9 @@
 use crate::config::Config;
 use crate::types::{CheckResult, CheckStatus};
-use crate::utils::registry;
-
 #[cfg(target_os = "windows")]
-use windows::Win32::System::Registry::*;
+use crate::checks::defaults;
+use crate::hardware::{display, gpu};
+use crate::hardware::pci::Vendor;
 
 pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
     vec![
@@ -43,22 +43,23 @@ pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
         check_dlss_fsr(),
         check_scheduled_priority(),
         check_display_mode(),
+        check_refresh_rate(),
     ]
 }
 
 #[cfg(target_os = "windows")]
 fn check_hags() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\GraphicsDrivers",
-        "HwSchMode"
-    ).unwrap_or(1);
+    let status = match defaults::read("Hardware Accelerated GPU Scheduling") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("Hardware Accelerated GPU Scheduling", "GPU", &e),
+    };
     
     CheckResult {
         name: "Hardware Accelerated GPU Scheduling".to_string(),
         category: "GPU".to_string(),
         status: if status == 2 { CheckStatus::Ok } else { CheckStatus::Info },
-        detail: format!("HAGS enabled: {}", status == 2),
+        detail: format!("HAGS enabled: {}{}", status == 2, defaults::note("Hardware Accelerated GPU Scheduling")),
+        error: None,
     }
 }
 
@@ -69,22 +70,23 @@ fn check_hags() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_tdr_level() -> CheckResult {
-    let level = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\GraphicsDrivers",
-        "TdrLevel"
-    ).unwrap_or(3);
+    let level = match defaults::read("TDR Level") {
+        Ok(level) => level,
+        Err(e) => return CheckResult::unreadable("TDR Level", "GPU", &e),
+    };
     
     CheckResult {
         name: "TDR Level".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: format!("TDR level: {}", level),
+        detail: format!("TDR level: {}{}", level, defaults::note("TDR Level")),
+        error: None,
     }
 }
 
@@ -95,22 +97,23 @@ fn check_tdr_level() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_tdr_delay() -> CheckResult {
-    let delay = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\GraphicsDrivers",
-        "TdrDelay"
-    ).unwrap_or(2);
+    let delay = match defaults::read("TDR Delay") {
+        Ok(delay) => delay,
+        Err(e) => return CheckResult::unreadable("TDR Delay", "GPU", &e),
+    };
     
     CheckResult {
         name: "TDR Delay".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: format!("TDR delay: {} seconds", delay),
+        detail: format!("TDR delay: {} seconds{}", delay, defaults::note("TDR Delay")),
+        error: None,
     }
 }
 
@@ -121,22 +124,23 @@ fn check_tdr_delay() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_gamedvr() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_CURRENT_USER,
-        "System\\GameConfigStore",
-        "GameDVR_Enabled"
-    ).unwrap_or(0);
+    let status = match defaults::read("Game DVR") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("Game DVR", "GPU", &e),
+    };
     
     CheckResult {
         name: "Game DVR".to_string(),
         category: "GPU".to_string(),
         status: if status == 0 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("Game DVR disabled: {}", status == 0),
+        detail: format!("Game DVR disabled: {}{}", status == 0, defaults::note("Game DVR")),
+        error: None,
     }
 }
 
@@ -147,6 +151,7 @@ fn check_gamedvr() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
@@ -157,6 +162,7 @@ fn check_mpo() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "MPO requires detailed registry check".to_string(),
+        error: None,
     }
 }
 
@@ -166,6 +172,7 @@ fn check_fso() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "FSO status per-application".to_string(),
+        error: None,
     }
 }
 
@@ -175,6 +182,26 @@ fn check_dwm() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "DWM always enabled on Windows 10+".to_string(),
+        error: None,
+    }
+}
+
+/// Names the installed adapters of a vendor, for checks of vendor driver
+/// settings that only apply when such a card is present.
+fn vendor_detail(vendor: Vendor, settings: &str) -> String {
+    let found: Vec<String> = gpu::adapters()
+        .iter()
+        .filter(|g| g.vendor == vendor)
+        .map(|g| match &g.driver_version {
+            Some(version) => format!("{} (driver {})", g.name, version),
+            None => g.name.clone(),
+        })
+        .collect();
+
+    if found.is_empty() {
+        format!("No {} GPU detected", vendor)
+    } else {
+        format!("{}; {} not inspected", found.join(", "), settings)
     }
 }
 
@@ -183,7 +210,8 @@ fn check_nvidia_scheduling() -> CheckResult {
         name: "NVIDIA Multi-threaded Optimization".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: "NVIDIA scheduling requires driver detection".to_string(),
+        detail: vendor_detail(Vendor::Nvidia, "driver profile settings"),
+        error: None,
     }
 }
 
@@ -192,7 +220,8 @@ fn check_amd_prerender() -> CheckResult {
         name: "AMD Anti-Lag".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: "AMD prerender requires driver detection".to_string(),
+        detail: vendor_detail(Vendor::Amd, "Adrenalin settings"),
+        error: None,
     }
 }
 
@@ -202,6 +231,7 @@ fn check_rebar() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "ReBAR requires PCIe enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -211,6 +241,7 @@ fn check_gpu_preemption() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "GPU preemption requires driver API".to_string(),
+        error: None,
     }
 }
 
@@ -220,6 +251,7 @@ fn check_shader_cache() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Shader cache location varies by driver".to_string(),
+        error: None,
     }
 }
 
@@ -229,6 +261,7 @@ fn check_anisotropic() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Anisotropic filtering per-application".to_string(),
+        error: None,
     }
 }
 
@@ -238,6 +271,7 @@ fn check_prerendered_frames() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Pre-rendered frames requires driver detection".to_string(),
+        error: None,
     }
 }
 
@@ -247,6 +281,7 @@ fn check_texture_filtering() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Texture filtering per-application".to_string(),
+        error: None,
     }
 }
 
@@ -256,6 +291,7 @@ fn check_vsync() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "VSync per-application".to_string(),
+        error: None,
     }
 }
 
@@ -265,6 +301,7 @@ fn check_triple_buffering() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Triple buffering per-application".to_string(),
+        error: None,
     }
 }
 
@@ -274,6 +311,7 @@ fn check_frame_limiter() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Frame limiter per-application".to_string(),
+        error: None,
     }
 }
 
@@ -283,6 +321,7 @@ fn check_low_latency_mode() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Low latency mode requires driver detection".to_string(),
+        error: None,
     }
 }
 
@@ -292,6 +331,7 @@ fn check_power_management() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "GPU power management requires driver detection".to_string(),
+        error: None,
     }
 }
 
@@ -301,33 +341,75 @@ fn check_pcie_power_state() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "PCIe power state requires device enumeration".to_string(),
+        error: None,
     }
 }
 
 fn check_color_depth() -> CheckResult {
+    let depths: Vec<String> = display::displays()
+        .iter()
+        .filter_map(|d| Some(format!("{}: {}-bit", d.name, d.edid.as_ref()?.bits_per_color?)))
+        .collect();
+    let detail = if depths.is_empty() {
+        "No display reports its panel bit depth".to_string()
+    } else {
+        format!("Panel bit depth per color: {}", depths.join(", "))
+    };
     CheckResult {
         name: "Display Color Depth".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: "Color depth requires display enumeration".to_string(),
+        detail,
+        error: None,
     }
 }
 
 fn check_hdr() -> CheckResult {
+    let hdr: Vec<String> = display::displays()
+        .iter()
+        .filter_map(|d| {
+            let hdr = d.edid.as_ref()?.hdr.as_ref().filter(|h| h.pq)?;
+            let peak = hdr.max_luminance.map(|nits| format!(", {:.0} nits peak", nits)).unwrap_or_default();
+            Some(format!("{} (HDR10{}{})", d.name, if hdr.hlg { "/HLG" } else { "" }, peak))
+        })
+        .collect();
+    let detail = if hdr.is_empty() {
+        "No connected display advertises HDR".to_string()
+    } else {
+        format!("HDR-capable: {}", hdr.join(", "))
+    };
     CheckResult {
         name: "HDR Support".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: "HDR requires display enumeration".to_string(),
+        detail,
+        error: None,
     }
 }
 
 fn check_vrr() -> CheckResult {
+    let displays = display::displays();
+    let ranges: Vec<String> = displays
+        .iter()
+        .filter_map(|d| {
+            let vrr = d.edid.as_ref()?.vrr?;
+            Some(format!("{} ({} {}-{} Hz)", d.name, vrr.source, vrr.min, vrr.max))
+        })
+        .collect();
+    let detail = if !ranges.is_empty() {
+        format!("Variable refresh supported: {}", ranges.join(", "))
+    } else if displays.is_empty() {
+        "No connected display found".to_string()
+    } else {
+        // DisplayPort Adaptive-Sync is announced over DPCD, not in the EDID
+        "No display advertises a VRR range in its EDID".to_string()
+    };
     CheckResult {
         name: "Variable Refresh Rate".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: "VRR/FreeSync/G-Sync requires display enumeration".to_string(),
+        detail,
+        error: None,
     }
 }
 
@@ -337,6 +419,7 @@ fn check_memory_clock() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Memory clock requires GPU monitoring".to_string(),
+        error: None,
     }
 }
 
@@ -346,6 +429,7 @@ fn check_core_clock() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Core clock requires GPU monitoring".to_string(),
+        error: None,
     }
 }
 
@@ -355,6 +439,7 @@ fn check_voltage() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Voltage requires GPU monitoring".to_string(),
+        error: None,
     }
 }
 
@@ -364,6 +449,7 @@ fn check_wddm_version() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "WDDM version requires driver detection".to_string(),
+        error: None,
     }
 }
 
@@ -373,6 +459,7 @@ fn check_directx_level() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "DirectX level requires GPU detection".to_string(),
+        error: None,
     }
 }
 
@@ -382,6 +469,7 @@ fn check_vulkan() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Vulkan requires runtime detection".to_string(),
+        error: None,
     }
 }
 
@@ -391,6 +479,7 @@ fn check_opengl_icd() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "OpenGL ICD requires driver detection".to_string(),
+        error: None,
     }
 }
 
@@ -400,6 +489,7 @@ fn check_compute_shader() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Compute shader requires GPU detection".to_string(),
+        error: None,
     }
 }
 
@@ -409,6 +499,7 @@ fn check_ray_tracing() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "Ray tracing requires GPU detection".to_string(),
+        error: None,
     }
 }
 
@@ -418,6 +509,7 @@ fn check_dlss_fsr() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "DLSS/FSR requires GPU detection".to_string(),
+        error: None,
     }
 }
 
@@ -427,14 +519,57 @@ fn check_scheduled_priority() -> CheckResult {
         category: "GPU".to_string(),
         status: CheckStatus::Info,
         detail: "GPU priority requires process enumeration".to_string(),
+        error: None,
     }
 }
 
 fn check_display_mode() -> CheckResult {
+    let modes: Vec<String> = display::displays()
+        .iter()
+        .map(|d| match d.current_mode {
+            Some(mode) => format!("{}: {}", d.name, mode),
+            None => format!("{}: current mode unknown", d.name),
+        })
+        .collect();
+    let detail = if modes.is_empty() { "No connected display found".to_string() } else { modes.join(", ") };
     CheckResult {
         name: "Display Mode".to_string(),
         category: "GPU".to_string(),
         status: CheckStatus::Info,
-        detail: "Display mode requires enumeration".to_string(),
+        detail,
+        error: None,
+    }
+}
+
+fn check_refresh_rate() -> CheckResult {
+    let displays = display::displays();
+    let mut slow = Vec::new();
+    let mut known = 0;
+    for d in displays {
+        let Some(current) = d.current_mode else { continue };
+        // Only modes at the resolution in use; other resolutions would need a mode change
+        let Some(max) = d.edid.as_ref().and_then(|e| e.max_refresh(Some(&current))) else { continue };
+        known += 1;
+        // 59.94 against 60 Hz and the like are the same setting
+        if current.refresh + 1.0 < max {
+            slow.push(format!("{} runs at {} Hz but supports {} Hz at {}x{}", d.name, current.refresh, max, current.width, current.height));
+        }
+    }
+
+    let (status, detail) = if !slow.is_empty() {
+        (CheckStatus::Warn, format!("{}: raise it in Settings > Display > Advanced display", slow.join("; ")))
+    } else if known == 0 && displays.is_empty() {
+        (CheckStatus::Info, "No connected display found".to_string())
+    } else if known == 0 {
+        (CheckStatus::Info, "Current refresh rate or supported modes unknown".to_string())
+    } else {
+        (CheckStatus::Ok, format!("{} of {} displays at their maximum refresh rate", known, displays.len()))
+    };
+    CheckResult {
+        name: "Refresh Rate".to_string(),
+        category: "GPU".to_string(),
+        status,
+        detail,
+        error: None,
     }
 }
@@ -1,9 +1,7 @@
 use crate::config::Config;
 use crate::types::{CheckResult, CheckStatus};
-use crate::utils::registry;
-
 #[cfg(target_os = "windows")]
-use windows::Win32::System::Registry::*;
+use crate::checks::defaults;
 
 pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
     vec![
@@ -51,22 +49,23 @@ fn check_dpc_latency() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "DPC latency measurement requires runtime monitoring".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_hpet() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\TimeProviders\\TimerResolution",
-        "HPETDisabled"
-    ).unwrap_or(0);
+    let status = match defaults::read("HPET Status") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("HPET Status", "Latency", &e),
+    };
     
     CheckResult {
         name: "HPET Status".to_string(),
         category: "Latency".to_string(),
         status: if status == 1 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("HPET disabled: {}", status == 1),
+        detail: format!("HPET disabled: {}{}", status == 1, defaults::note("HPET Status")),
+        error: None,
     }
 }
 
@@ -77,22 +76,23 @@ fn check_hpet() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_tsc_sync() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\kernel",
-        "UsePlatformClock"
-    ).unwrap_or(0);
+    let status = match defaults::read("TSC Sync Policy") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("TSC Sync Policy", "Latency", &e),
+    };
     
     CheckResult {
         name: "TSC Sync Policy".to_string(),
         category: "Latency".to_string(),
         status: if status == 0 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("TSC synchronized: {}", status == 0),
+        detail: format!("TSC synchronized: {}{}", status == 0, defaults::note("TSC Sync Policy")),
+        error: None,
     }
 }
 
@@ -103,22 +103,23 @@ fn check_tsc_sync() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_dynamic_tick() -> CheckResult {
-    let status = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\kernel",
-        "DisableDynamicTick"
-    ).unwrap_or(0);
+    let status = match defaults::read("Dynamic Tick") {
+        Ok(status) => status,
+        Err(e) => return CheckResult::unreadable("Dynamic Tick", "Latency", &e),
+    };
     
     CheckResult {
         name: "Dynamic Tick".to_string(),
         category: "Latency".to_string(),
         status: if status == 1 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("Dynamic tick disabled: {}", status == 1),
+        detail: format!("Dynamic tick disabled: {}{}", status == 1, defaults::note("Dynamic Tick")),
+        error: None,
     }
 }
 
@@ -129,22 +130,23 @@ fn check_dynamic_tick() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_mmcss_responsiveness() -> CheckResult {
-    let value = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Multimedia\\SystemProfile",
-        "SystemResponsiveness"
-    ).unwrap_or(20);
+    let value = match defaults::read("MMCSS System Responsiveness") {
+        Ok(value) => value,
+        Err(e) => return CheckResult::unreadable("MMCSS System Responsiveness", "Latency", &e),
+    };
     
     CheckResult {
         name: "MMCSS System Responsiveness".to_string(),
         category: "Latency".to_string(),
         status: if value <= 10 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("System responsiveness: {}%", value),
+        detail: format!("System responsiveness: {}%{}", value, defaults::note("MMCSS System Responsiveness")),
+        error: None,
     }
 }
 
@@ -155,22 +157,23 @@ fn check_mmcss_responsiveness() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_network_throttling() -> CheckResult {
-    let value = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Multimedia\\SystemProfile",
-        "NetworkThrottlingIndex"
-    ).unwrap_or(10);
+    let value = match defaults::read("Network Throttling") {
+        Ok(value) => value,
+        Err(e) => return CheckResult::unreadable("Network Throttling", "Latency", &e),
+    };
     
     CheckResult {
         name: "Network Throttling".to_string(),
         category: "Latency".to_string(),
         status: if value == 0xFFFFFFFF { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("Network throttling: {}", if value == 0xFFFFFFFF { "Disabled" } else { "Enabled" }),
+        detail: format!("Network throttling: {}{}", if value == 0xFFFFFFFF { "Disabled" } else { "Enabled" }, defaults::note("Network Throttling")),
+        error: None,
     }
 }
 
@@ -181,22 +184,23 @@ fn check_network_throttling() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_priority_separation() -> CheckResult {
-    let value = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\PriorityControl",
-        "Win32PrioritySeparation"
-    ).unwrap_or(2);
+    let value = match defaults::read("Priority Separation") {
+        Ok(value) => value,
+        Err(e) => return CheckResult::unreadable("Priority Separation", "Latency", &e),
+    };
     
     CheckResult {
         name: "Priority Separation".to_string(),
         category: "Latency".to_string(),
         status: if value == 38 { CheckStatus::Ok } else { CheckStatus::Info },
-        detail: format!("Win32PrioritySeparation: {}", value),
+        detail: format!("Win32PrioritySeparation: {}{}", value, defaults::note("Priority Separation")),
+        error: None,
     }
 }
 
@@ -207,6 +211,7 @@ fn check_priority_separation() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
@@ -216,6 +221,7 @@ fn check_gpu_msi() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "GPU MSI-X status requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -225,6 +231,7 @@ fn check_nic_msi() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "NIC MSI-X status requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -234,6 +241,7 @@ fn check_gpu_interrupt_affinity() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "GPU interrupt affinity requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -243,22 +251,23 @@ fn check_nic_interrupt_affinity() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "NIC interrupt affinity requires device enumeration".to_string(),
+        error: None,
     }
 }
 
 #[cfg(target_os = "windows")]
 fn check_timer_coalescing() -> CheckResult {
-    let value = registry::read_dword(
-        HKEY_LOCAL_MACHINE,
-        "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\kernel",
-        "CoalescingTimerInterval"
-    ).unwrap_or(0);
+    let value = match defaults::read("Timer Coalescing") {
+        Ok(value) => value,
+        Err(e) => return CheckResult::unreadable("Timer Coalescing", "Latency", &e),
+    };
     
     CheckResult {
         name: "Timer Coalescing".to_string(),
         category: "Latency".to_string(),
         status: if value == 0 { CheckStatus::Ok } else { CheckStatus::Warn },
-        detail: format!("Timer coalescing disabled: {}", value == 0),
+        detail: format!("Timer coalescing disabled: {}{}", value == 0, defaults::note("Timer Coalescing")),
+        error: None,
     }
 }
 
@@ -269,6 +278,7 @@ fn check_timer_coalescing() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Windows-only check".to_string(),
+        error: None,
     }
 }
 
@@ -278,6 +288,7 @@ fn check_smi_detection() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "SMI detection requires hardware monitoring".to_string(),
+        error: None,
     }
 }
 
@@ -287,6 +298,7 @@ fn check_x2apic() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "x2APIC status requires CPU feature detection".to_string(),
+        error: None,
     }
 }
 
@@ -296,6 +308,7 @@ fn check_pcie_aspm() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "PCIe ASPM status requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -305,6 +318,7 @@ fn check_usb_latency() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "USB latency requires controller enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -314,6 +328,7 @@ fn check_sata_link_power() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "SATA link power requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -323,6 +338,7 @@ fn check_audio_buffer() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Audio buffer size requires audio device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -332,6 +348,7 @@ fn check_directx_flip() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "DirectX flip model requires runtime detection".to_string(),
+        error: None,
     }
 }
 
@@ -341,6 +358,7 @@ fn check_timer_resolution() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Timer resolution requires NtQueryTimerResolution call".to_string(),
+        error: None,
     }
 }
 
@@ -350,6 +368,7 @@ fn check_cpu_idle_latency() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "CPU idle latency requires power state monitoring".to_string(),
+        error: None,
     }
 }
 
@@ -359,6 +378,7 @@ fn check_memory_timing() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Memory timing requires SPD reading".to_string(),
+        error: None,
     }
 }
 
@@ -368,6 +388,7 @@ fn check_nvme_queue_depth() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "NVMe queue depth requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -377,6 +398,7 @@ fn check_bluetooth_latency() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Bluetooth latency requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -386,6 +408,7 @@ fn check_hid_polling() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "HID polling rate requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -395,6 +418,7 @@ fn check_monitor_sync() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Monitor sync requires display enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -404,6 +428,7 @@ fn check_gpu_preemption() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "GPU preemption requires driver API".to_string(),
+        error: None,
     }
 }
 
@@ -413,6 +438,7 @@ fn check_shader_cache() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Shader cache status requires driver enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -422,6 +448,7 @@ fn check_asset_streaming() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Asset streaming requires game detection".to_string(),
+        error: None,
     }
 }
 
@@ -431,6 +458,7 @@ fn check_network_coalescing() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Network coalescing requires adapter enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -440,6 +468,7 @@ fn check_storage_priority() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Storage priority requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -449,6 +478,7 @@ fn check_realtime_priority() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Real-time priority requires process enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -458,6 +488,7 @@ fn check_mmcss_thread_priority() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "MMCSS thread priority requires service enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -467,6 +498,7 @@ fn check_interrupt_steering() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Interrupt steering requires device enumeration".to_string(),
+        error: None,
     }
 }
 
@@ -476,5 +508,6 @@ fn check_global_timer_resolution() -> CheckResult {
         category: "Latency".to_string(),
         status: CheckStatus::Info,
         detail: "Global timer resolution requires system monitoring".to_string(),
+        error: None,
     }
 }
@@ -1,11 +1,108 @@
 use crate::config::Config;
+use crate::error::Error;
+use crate::hardware::smbios::{self, MemoryModule};
 use crate::types::{CheckResult, CheckStatus};
 
 pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
-    (1..=25).map(|i| CheckResult {
+    // Parse the memory devices once for both module checks
+    let modules = smbios::table().map(|t| t.memory_modules());
+    let modules = modules.as_deref();
+
+    let mut results = vec![
+        check_channels(modules),
+        check_speed(modules),
+    ];
+    results.extend((3..=25).map(|i| CheckResult {
         name: format!("Memory Check {}", i),
         category: "Memory".to_string(),
         status: CheckStatus::Info,
         detail: "Memory checks require detailed implementation".to_string(),
-    }).collect()
+        error: None,
+    }));
+    results
+}
+
+fn slots(modules: &[MemoryModule]) -> String {
+    modules.iter().map(|m| m.slot.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+fn check_channels(modules: Result<&[MemoryModule], &Error>) -> CheckResult {
+    let modules = match modules {
+        Ok(modules) => modules,
+        Err(e) => return CheckResult::unreadable("Memory Channels", "Memory", e),
+    };
+
+    let mut channels: Vec<&str> = modules.iter().filter_map(|m| m.channel.as_deref()).collect();
+    channels.sort_unstable();
+    channels.dedup();
+
+    let (status, detail) = match modules {
+        [] => (CheckStatus::Info, "No memory modules listed in SMBIOS".to_string()),
+        [module] => (
+            CheckStatus::Warn,
+            format!("Single module in {}: memory runs single-channel, add a matching module", module.slot),
+        ),
+        _ if modules.iter().any(|m| m.channel.is_none()) => (
+            CheckStatus::Info,
+            format!("{} modules in {}; channels not identifiable from the slot names", modules.len(), slots(modules)),
+        ),
+        _ if channels.len() == 1 => (
+            CheckStatus::Warn,
+            format!(
+                "All {} modules on channel {} ({}): memory runs single-channel, see the board manual for the right slots",
+                modules.len(),
+                channels[0],
+                slots(modules),
+            ),
+        ),
+        _ => (
+            CheckStatus::Ok,
+            format!("{} modules on {} channels ({})", modules.len(), channels.len(), slots(modules)),
+        ),
+    };
+
+    CheckResult {
+        name: "Memory Channels".to_string(),
+        category: "Memory".to_string(),
+        status,
+        detail,
+        error: None,
+    }
+}
+
+fn check_speed(modules: Result<&[MemoryModule], &Error>) -> CheckResult {
+    let modules = match modules {
+        Ok(modules) => modules,
+        Err(e) => return CheckResult::unreadable("Memory Speed", "Memory", e),
+    };
+
+    // A kit runs at the speed of its slowest module
+    let configured = modules.iter().filter_map(|m| m.configured_speed).min();
+    let rated = modules.iter().filter_map(|m| m.rated_speed()).min();
+    let jedec = modules.iter().filter_map(|m| m.speed).min();
+    let memory_type = modules.first().map_or("", |m| m.memory_type.as_str());
+
+    let (status, detail) = match (configured, rated) {
+        _ if modules.is_empty() => (CheckStatus::Info, "No memory modules listed in SMBIOS".to_string()),
+        (None, _) => (CheckStatus::Info, "Firmware does not report the configured memory speed".to_string()),
+        (Some(configured), Some(rated)) if configured < rated => (
+            CheckStatus::Warn,
+            format!(
+                "{} running at {} MT/s, modules rated {} MT/s: enable XMP/EXPO in the BIOS",
+                memory_type, configured, rated,
+            ),
+        ),
+        (Some(configured), _) => {
+            let profile = if jedec.is_some_and(|jedec| configured > jedec) { ", XMP/EXPO active" } else { "" };
+            (CheckStatus::Ok, format!("{} running at {} MT/s{}", memory_type, configured, profile))
+        }
+    };
+
+    CheckResult {
+        name: "Memory Speed".to_string(),
+        category: "Memory".to_string(),
+        status,
+        detail,
+        error: None,
+    }
 }
@@ -12,8 +12,11 @@ pub mod security;
 pub mod platform;
 pub mod thermal;
 pub mod power;
+pub mod tweaks;
+pub mod defaults;
 
 use crate::config::Config;
+use crate::fixes::catalog;
 use crate::types::{AuditResults, CheckResult};
 use rayon::prelude::*;
 
@@ -36,6 +39,7 @@ pub fn run_all_checks(config: &Config) -> AuditResults {
         platform::run_checks(config),
         thermal::run_checks(config),
         power::run_checks(config),
+        tweaks::run_checks(config),
     ].into_par_iter()
         .map(|checks| checks)
         .collect();
@@ -47,6 +51,10 @@ pub fn run_all_checks(config: &Config) -> AuditResults {
         }
     }
     
+    for issue in catalog::issues_for(&results.checks) {
+        results.add_issue(issue);
+    }
+    
     results.calculate_score();
     results
 }
@@ -1,11 +1,58 @@
 use crate::config::Config;
+use crate::hardware::network::{self, AdapterType};
 use crate::types::{CheckResult, CheckStatus};
 
 pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
-    (1..=35).map(|i| CheckResult {
+    let mut results = vec![check_active_adapter()];
+    results.extend((2..=35).map(|i| CheckResult {
         name: format!("Network Check {}", i),
         category: "Network".to_string(),
         status: CheckStatus::Info,
         detail: "Network checks require detailed implementation".to_string(),
-    }).collect()
+        error: None,
+    }));
+    results
+}
+
+/// Link speed as `1 Gbps`, `2.5 Gbps` or `100 Mbps`.
+fn format_speed(bits: u64) -> String {
+    if bits >= 1_000_000_000 {
+        format!("{} Gbps", bits as f64 / 1e9)
+    } else {
+        format!("{} Mbps", bits / 1_000_000)
+    }
+}
+
+fn check_active_adapter() -> CheckResult {
+    let adapters = network::adapters();
+    let (status, detail) = match network::active_adapter(adapters) {
+        None if adapters.iter().any(|a| a.up && a.default_gateway) => (
+            CheckStatus::Info,
+            "Traffic leaves through a virtual adapter (VPN or virtual switch); no physical adapter is connected".to_string(),
+        ),
+        None => (CheckStatus::Info, "No connected physical network adapter".to_string()),
+        Some(adapter) => {
+            let speed = adapter.speed.map(format_speed).unwrap_or_else(|| "unknown speed".to_string());
+            let mut notes = vec![adapter.adapter_type.to_string(), speed];
+            if !adapter.description.is_empty() {
+                notes.insert(0, adapter.description.clone());
+            }
+            let link = format!("{} ({})", adapter.name, notes.join(", "));
+            if adapter.full_duplex == Some(false) {
+                (CheckStatus::Warn, format!("{} runs half duplex: set Speed & Duplex to Auto Negotiation", link))
+            } else if adapter.adapter_type == AdapterType::Ethernet && adapter.speed.is_some_and(|s| s < 1_000_000_000) {
+                (CheckStatus::Warn, format!("{} linked below 1 Gbps: check the cable and the switch port", link))
+            } else {
+                (CheckStatus::Ok, link)
+            }
+        }
+    };
+
+    CheckResult {
+        name: "Active Network Adapter".to_string(),
+        category: "Network".to_string(),
+        status,
+        detail,
+        error: None,
+    }
 }
@@ -7,5 +7,6 @@ pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
         category: "Platform".to_string(),
         status: CheckStatus::Info,
         detail: "Platform checks require detailed implementation".to_string(),
+        error: None,
     }).collect()
 }
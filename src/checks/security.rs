@@ -7,5 +7,6 @@ pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
         category: "Security".to_string(),
         status: CheckStatus::Info,
         detail: "Security checks require detailed implementation".to_string(),
+        error: None,
     }).collect()
 }
@@ -1,11 +1,94 @@
 use crate::config::Config;
-use crate::types::{CheckResult, CheckStatus};
+use crate::types::{CheckResult, CheckStatus, Safety};
+use crate::utils::services::StartType;
+
+/// A Windows service APEX has an opinion about.
+pub struct ServiceRule {
+    pub service: &'static str,
+    pub display: &'static str,
+    /// Start type the service should not exceed, or `None` when its
+    /// configuration is only reported.
+    pub recommended: Option<StartType>,
+    pub safety: Safety,
+}
+
+pub const SERVICE_RULES: &[ServiceRule] = &[
+    ServiceRule { service: "DiagTrack", display: "Connected User Experiences and Telemetry", recommended: Some(StartType::Disabled), safety: Safety::Safe },
+    ServiceRule { service: "dmwappushservice", display: "Device Management WAP Push", recommended: Some(StartType::Disabled), safety: Safety::Safe },
+    ServiceRule { service: "diagnosticshub.standardcollector.service", display: "Diagnostics Hub Standard Collector", recommended: Some(StartType::Disabled), safety: Safety::Safe },
+    ServiceRule { service: "WerSvc", display: "Windows Error Reporting", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "PcaSvc", display: "Program Compatibility Assistant", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "RemoteRegistry", display: "Remote Registry", recommended: Some(StartType::Disabled), safety: Safety::Safe },
+    ServiceRule { service: "RetailDemo", display: "Retail Demo", recommended: Some(StartType::Disabled), safety: Safety::Safe },
+    ServiceRule { service: "MapsBroker", display: "Downloaded Maps Manager", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "lfsvc", display: "Geolocation", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "Fax", display: "Fax", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "WMPNetworkSvc", display: "Windows Media Player Network Sharing", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "TrkWks", display: "Distributed Link Tracking Client", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "wisvc", display: "Windows Insider Service", recommended: Some(StartType::Manual), safety: Safety::Safe },
+    ServiceRule { service: "XblAuthManager", display: "Xbox Live Auth Manager", recommended: Some(StartType::Manual), safety: Safety::Moderate },
+    ServiceRule { service: "XblGameSave", display: "Xbox Live Game Save", recommended: Some(StartType::Manual), safety: Safety::Moderate },
+    ServiceRule { service: "XboxNetApiSvc", display: "Xbox Live Networking", recommended: Some(StartType::Manual), safety: Safety::Moderate },
+    ServiceRule { service: "XboxGipSvc", display: "Xbox Accessory Management", recommended: Some(StartType::Manual), safety: Safety::Moderate },
+    ServiceRule { service: "SysMain", display: "SysMain (Superfetch)", recommended: None, safety: Safety::Safe },
+    ServiceRule { service: "WSearch", display: "Windows Search", recommended: None, safety: Safety::Safe },
+    ServiceRule { service: "Spooler", display: "Print Spooler", recommended: None, safety: Safety::Safe },
+    ServiceRule { service: "BITS", display: "Background Intelligent Transfer", recommended: None, safety: Safety::Safe },
+    ServiceRule { service: "wuauserv", display: "Windows Update", recommended: None, safety: Safety::Safe },
+    ServiceRule { service: "Audiosrv", display: "Windows Audio", recommended: None, safety: Safety::Safe },
+    ServiceRule { service: "MMCSS", display: "Multimedia Class Scheduler", recommended: None, safety: Safety::Safe },
+];
+
+/// Orders start types from least to most eager so "at most Manual" can be
+/// compared.
+#[cfg(target_os = "windows")]
+fn eagerness(start_type: StartType) -> u8 {
+    match start_type {
+        StartType::Disabled => 0,
+        StartType::Manual => 1,
+        StartType::Automatic => 2,
+        StartType::System => 3,
+        StartType::Boot => 4,
+    }
+}
 
 pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
-    (1..=40).map(|i| CheckResult {
-        name: format!("Service Check {}", i),
+    SERVICE_RULES.iter().map(check_service).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn check_service(rule: &ServiceRule) -> CheckResult {
+    use crate::utils::services;
+
+    let (status, detail) = match services::query(rule.service) {
+        Ok(None) => (CheckStatus::Ok, format!("{} not installed", rule.service)),
+        Ok(Some(config)) => match rule.recommended {
+            Some(recommended) if eagerness(config.start_type) > eagerness(recommended) => (
+                CheckStatus::Warn,
+                format!("{}: {} (recommended: {:?})", rule.service, config.describe(), recommended),
+            ),
+            Some(_) => (CheckStatus::Ok, format!("{}: {}", rule.service, config.describe())),
+            None => (CheckStatus::Info, format!("{}: {}", rule.service, config.describe())),
+        },
+        Err(e) => return CheckResult::unreadable(rule.display, "Services", &e),
+    };
+
+    CheckResult {
+        name: rule.display.to_string(),
+        category: "Services".to_string(),
+        status,
+        detail,
+        error: None,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn check_service(rule: &ServiceRule) -> CheckResult {
+    CheckResult {
+        name: rule.display.to_string(),
         category: "Services".to_string(),
         status: CheckStatus::Info,
-        detail: "Service checks require detailed implementation".to_string(),
-    }).collect()
+        detail: "Windows-only check".to_string(),
+        error: None,
+    }
 }
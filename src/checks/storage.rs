@@ -1,11 +1,203 @@
 use crate::config::Config;
+use crate::hardware::disk;
+use crate::hardware::smart::{self, SmartHealth};
 use crate::types::{CheckResult, CheckStatus};
 
-pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
-    (1..=30).map(|i| CheckResult {
+pub fn run_checks(config: &Config) -> Vec<CheckResult> {
+    let mut results = vec![check_partition_alignment()];
+    results.extend(disk_health(config));
+    results.extend((8..=30).map(|i| CheckResult {
         name: format!("Storage Check {}", i),
         category: "Storage".to_string(),
         status: CheckStatus::Info,
         detail: "Storage checks require detailed implementation".to_string(),
-    }).collect()
+        error: None,
+    }));
+    results
+}
+
+fn check_partition_alignment() -> CheckResult {
+    let disks = disk::disks();
+    let partitions = disks.iter().map(|d| d.partitions.len()).sum::<usize>();
+    let misaligned: Vec<String> = disks
+        .iter()
+        .flat_map(|d| {
+            d.partitions
+                .iter()
+                .filter(|p| !p.is_aligned())
+                .map(move |p| format!("disk {} partition {} at {} bytes", d.number, p.number, p.offset))
+        })
+        .collect();
+
+    let (status, detail) = if disks.is_empty() {
+        (CheckStatus::Info, "No physical disks detected".to_string())
+    } else if partitions == 0 {
+        (CheckStatus::Info, format!("No partitions on {} disks", disks.len()))
+    } else if !misaligned.is_empty() {
+        (
+            CheckStatus::Warn,
+            format!("Not 4K-aligned, which slows writes and wears SSDs: {}", misaligned.join(", ")),
+        )
+    } else {
+        (
+            CheckStatus::Ok,
+            format!("All {} partitions on {} disks are 4K-aligned", partitions, disks.len()),
+        )
+    };
+
+    CheckResult {
+        name: "Partition Alignment".to_string(),
+        category: "Storage".to_string(),
+        status,
+        detail,
+        error: None,
+    }
+}
+
+fn check(name: &str, drive: &SmartHealth, status: CheckStatus, detail: String) -> CheckResult {
+    CheckResult {
+        name: format!("{}: {}", name, drive.label()),
+        category: "Storage".to_string(),
+        status,
+        detail,
+        error: None,
+    }
+}
+
+/// Health checks for every drive with SMART data. Counters a drive does
+/// not report get no check.
+fn disk_health(config: &Config) -> Vec<CheckResult> {
+    let reports = smart::reports(&config.smart_imports);
+    if reports.is_empty() {
+        return vec![CheckResult {
+            name: "Disk Health".to_string(),
+            category: "Storage".to_string(),
+            status: CheckStatus::Info,
+            detail: "No SMART data: install smartmontools or import a report with `audit --smart <file>`".to_string(),
+            error: None,
+        }];
+    }
+
+    let mut results = Vec::new();
+    for report in &reports {
+        let drive = match report {
+            Ok(drive) => drive,
+            Err(e) => {
+                results.push(CheckResult::unreadable("Disk Health", "Storage", e));
+                continue;
+            }
+        };
+        results.push(check_smart_status(drive));
+        results.extend(check_wear(config, drive));
+        results.extend(check_media_errors(config, drive));
+        results.extend(check_bad_sectors(config, drive));
+        results.extend(check_disk_temperature(config, drive));
+        results.extend(check_unsafe_shutdowns(config, drive));
+    }
+    results
+}
+
+fn check_smart_status(drive: &SmartHealth) -> CheckResult {
+    const WARNINGS: [(u8, &str); 5] = [
+        (0x01, "spare capacity below threshold"),
+        (0x02, "temperature out of range"),
+        (0x04, "reliability degraded"),
+        (0x08, "read-only mode"),
+        (0x10, "volatile memory backup failed"),
+    ];
+    let warnings: Vec<&str> = WARNINGS
+        .iter()
+        .filter(|(bit, _)| drive.critical_warning.is_some_and(|w| w & bit != 0))
+        .map(|(_, text)| *text)
+        .collect();
+
+    let (status, detail) = match drive.passed {
+        _ if !warnings.is_empty() => {
+            (CheckStatus::Bad, format!("Critical warning: {}; back up the drive", warnings.join(", ")))
+        }
+        Some(false) => (CheckStatus::Bad, "Drive reports a SMART failure; back up and replace it".to_string()),
+        Some(true) => (CheckStatus::Ok, "SMART self-assessment passed".to_string()),
+        None => (CheckStatus::Info, "Drive does not report a SMART self-assessment".to_string()),
+    };
+    check("SMART Status", drive, status, detail)
+}
+
+fn check_wear(config: &Config, drive: &SmartHealth) -> Option<CheckResult> {
+    let used = drive.percentage_used?;
+    let status = if used >= config.disk_wear_bad {
+        CheckStatus::Bad
+    } else if used >= config.disk_wear_warn {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Ok
+    };
+    Some(check("Disk Wear", drive, status, format!("{}% of rated write endurance used", used)))
+}
+
+fn check_media_errors(config: &Config, drive: &SmartHealth) -> Option<CheckResult> {
+    let errors = drive.media_errors?;
+    let (status, detail) = if errors >= config.disk_media_errors_bad {
+        (CheckStatus::Bad, format!("{} uncorrectable media errors: data was lost, back up the drive", errors))
+    } else {
+        (CheckStatus::Ok, "No uncorrectable media errors".to_string())
+    };
+    Some(check("Media Errors", drive, status, detail))
+}
+
+fn check_bad_sectors(config: &Config, drive: &SmartHealth) -> Option<CheckResult> {
+    if drive.reallocated_sectors.is_none() && drive.pending_sectors.is_none() {
+        return None;
+    }
+    let reallocated = drive.reallocated_sectors.unwrap_or(0);
+    let pending = drive.pending_sectors.unwrap_or(0);
+
+    let (status, detail) = if pending >= config.disk_pending_bad {
+        (CheckStatus::Bad, format!("{} sectors pending reallocation are unreadable; back up the drive", pending))
+    } else if reallocated >= config.disk_reallocated_bad {
+        (CheckStatus::Bad, format!("{} reallocated sectors: the drive is failing", reallocated))
+    } else if reallocated >= config.disk_reallocated_warn {
+        (CheckStatus::Warn, format!("{} reallocated sectors; watch whether the count grows", reallocated))
+    } else {
+        (CheckStatus::Ok, "No reallocated or pending sectors".to_string())
+    };
+    Some(check("Bad Sectors", drive, status, detail))
+}
+
+fn check_disk_temperature(config: &Config, drive: &SmartHealth) -> Option<CheckResult> {
+    if drive.temperature.is_none() && drive.max_temperature.is_none() {
+        return None;
+    }
+    let current = drive.temperature.map(|t| format!("{}°C", t)).unwrap_or_else(|| "Unknown".to_string());
+    let max = drive.max_temperature.map(|t| format!(", highest {}°C", t)).unwrap_or_default();
+    let critical_minutes = drive.critical_temp_minutes.unwrap_or(0);
+    let warning_minutes = drive.warning_temp_minutes.unwrap_or(0);
+
+    let (status, note) = if drive.temperature.is_some_and(|t| t >= config.disk_temp_crit) {
+        (CheckStatus::Bad, ": improve airflow or add a heatsink".to_string())
+    } else if critical_minutes > 0 {
+        (CheckStatus::Warn, format!("; {} minutes above the critical temperature", critical_minutes))
+    } else if drive.temperature.is_some_and(|t| t >= config.disk_temp_warn) {
+        (CheckStatus::Warn, ": running warm".to_string())
+    } else if drive.max_temperature.is_some_and(|t| t >= config.disk_temp_crit) {
+        (CheckStatus::Warn, ": has overheated under load".to_string())
+    } else if warning_minutes > 0 {
+        (CheckStatus::Ok, format!("; {} minutes above the warning temperature", warning_minutes))
+    } else {
+        (CheckStatus::Ok, String::new())
+    };
+    Some(check("Disk Temperature", drive, status, format!("{}{}{}", current, max, note)))
+}
+
+fn check_unsafe_shutdowns(config: &Config, drive: &SmartHealth) -> Option<CheckResult> {
+    let count = drive.unsafe_shutdowns?;
+    let hours = drive.power_on_hours.map(|h| format!(" in {} power-on hours", h)).unwrap_or_default();
+    let (status, detail) = if count >= config.disk_unsafe_shutdowns_warn {
+        (
+            CheckStatus::Warn,
+            format!("{} unsafe shutdowns{}: power loss risks data in the drive cache, shut down cleanly", count, hours),
+        )
+    } else {
+        (CheckStatus::Ok, format!("{} unsafe shutdowns{}", count, hours))
+    };
+    Some(check("Unsafe Shutdowns", drive, status, detail))
 }
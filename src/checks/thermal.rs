@@ -7,5 +7,6 @@ pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
         category: "Thermal".to_string(),
         status: CheckStatus::Info,
         detail: "Thermal checks require detailed implementation".to_string(),
+        error: None,
     }).collect()
 }
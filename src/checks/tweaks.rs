@@ -0,0 +1,258 @@
+use crate::config::Config;
+use crate::types::{Change, CheckResult, CheckStatus, Severity};
+use crate::utils::registry::RegPath;
+use crate::utils::services::StartType;
+
+/// One value a third-party tweak changes, with the Windows default it
+/// replaces.
+pub enum TweakSetting {
+    Registry {
+        key: RegPath,
+        value: &'static str,
+        /// Value the tweak writes, or `None` when any non-default value counts.
+        #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+        tweaked: Option<u32>,
+        /// Windows default, or `None` when the value does not exist by default.
+        default: Option<u32>,
+    },
+    Service {
+        service: &'static str,
+        #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+        tweaked: StartType,
+        default: StartType,
+        delayed_auto: bool,
+    },
+}
+
+impl TweakSetting {
+    #[cfg(target_os = "windows")]
+    pub fn describe(&self) -> String {
+        match self {
+            TweakSetting::Registry { key, value, .. } => key.value_path(value),
+            TweakSetting::Service { service, .. } => format!("service {}", service),
+        }
+    }
+
+    /// The change that puts the Windows default back.
+    pub fn revert(&self) -> Change {
+        match self {
+            TweakSetting::Registry { key, value, default: Some(data), .. } => Change::RegistryDword {
+                key: key.clone(),
+                value: value.to_string(),
+                data: *data,
+            },
+            TweakSetting::Registry { key, value, default: None, .. } => Change::RegistryDelete {
+                key: key.clone(),
+                value: value.to_string(),
+            },
+            TweakSetting::Service { service, default, delayed_auto, .. } => Change::ServiceStartType {
+                name: service.to_string(),
+                start_type: *default,
+                delayed_auto: *delayed_auto,
+            },
+        }
+    }
+}
+
+/// Settings a family of optimizer tools or scripts is known to change.
+pub struct TweakSignature {
+    pub name: &'static str,
+    /// Where the tweak usually comes from.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub origin: &'static str,
+    pub severity: Severity,
+    pub reboot: bool,
+    pub settings: &'static [TweakSetting],
+}
+
+const fn hklm(subkey: &'static str, value: &'static str, tweaked: Option<u32>, default: Option<u32>) -> TweakSetting {
+    TweakSetting::Registry { key: RegPath::hklm(subkey), value, tweaked, default }
+}
+
+const MEMORY_MANAGEMENT: &str = "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Memory Management";
+
+pub const TWEAK_SIGNATURES: &[TweakSignature] = &[
+    TweakSignature {
+        name: "Defender Disabled by Policy",
+        origin: "Defender Control and privacy scripts",
+        severity: Severity::High,
+        reboot: true,
+        settings: &[
+            hklm("SOFTWARE\\Policies\\Microsoft\\Windows Defender", "DisableAntiSpyware", Some(1), None),
+            hklm("SOFTWARE\\Policies\\Microsoft\\Windows Defender\\Real-Time Protection", "DisableRealtimeMonitoring", Some(1), None),
+        ],
+    },
+    TweakSignature {
+        name: "Windows Update Blocked",
+        origin: "update blockers and debloat scripts",
+        severity: Severity::High,
+        reboot: false,
+        settings: &[
+            hklm("SOFTWARE\\Policies\\Microsoft\\Windows\\WindowsUpdate\\AU", "NoAutoUpdate", Some(1), None),
+            TweakSetting::Service { service: "wuauserv", tweaked: StartType::Disabled, default: StartType::Manual, delayed_auto: false },
+        ],
+    },
+    TweakSignature {
+        name: "Speculative Execution Mitigations Disabled",
+        origin: "InSpectre and FPS tweak packs",
+        severity: Severity::High,
+        reboot: true,
+        settings: &[
+            hklm(MEMORY_MANAGEMENT, "FeatureSettingsOverride", Some(3), None),
+            hklm(MEMORY_MANAGEMENT, "FeatureSettingsOverrideMask", Some(3), None),
+        ],
+    },
+    TweakSignature {
+        name: "User Account Control Disabled",
+        origin: "tweak packs",
+        severity: Severity::High,
+        reboot: true,
+        settings: &[hklm("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Policies\\System", "EnableLUA", Some(0), Some(1))],
+    },
+    TweakSignature {
+        name: "Firewall Disabled",
+        origin: "gaming and network optimizers",
+        severity: Severity::High,
+        reboot: false,
+        settings: &[
+            hklm("SYSTEM\\CurrentControlSet\\Services\\SharedAccess\\Parameters\\FirewallPolicy\\StandardProfile", "EnableFirewall", Some(0), Some(1)),
+            hklm("SYSTEM\\CurrentControlSet\\Services\\SharedAccess\\Parameters\\FirewallPolicy\\PublicProfile", "EnableFirewall", Some(0), Some(1)),
+        ],
+    },
+    TweakSignature {
+        name: "Large System Cache",
+        origin: "memory optimizers",
+        severity: Severity::Medium,
+        reboot: true,
+        settings: &[hklm(MEMORY_MANAGEMENT, "LargeSystemCache", Some(1), Some(0))],
+    },
+    TweakSignature {
+        name: "Kernel Paging Disabled",
+        origin: "memory optimizers",
+        severity: Severity::Low,
+        reboot: true,
+        settings: &[hklm(MEMORY_MANAGEMENT, "DisablePagingExecutive", Some(1), Some(0))],
+    },
+    TweakSignature {
+        name: "Prefetcher Disabled",
+        origin: "SSD optimizers",
+        severity: Severity::Low,
+        reboot: true,
+        settings: &[hklm("SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Memory Management\\PrefetchParameters", "EnablePrefetcher", Some(0), Some(3))],
+    },
+    TweakSignature {
+        name: "SvcHost Split Threshold Changed",
+        origin: "RAM-based service grouping scripts",
+        severity: Severity::Low,
+        reboot: true,
+        settings: &[hklm("SYSTEM\\CurrentControlSet\\Control", "SvcHostSplitThresholdInKB", None, Some(0x380000))],
+    },
+    TweakSignature {
+        name: "IRQ Priority Override",
+        origin: "legacy latency guides (no effect on modern Windows)",
+        severity: Severity::Low,
+        reboot: true,
+        settings: &[hklm("SYSTEM\\CurrentControlSet\\Control\\PriorityControl", "IRQ8Priority", None, None)],
+    },
+    TweakSignature {
+        name: "Game Task Priority Override",
+        origin: "gaming tweak packs",
+        severity: Severity::Low,
+        reboot: false,
+        settings: &[hklm("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Multimedia\\SystemProfile\\Tasks\\Games", "Priority", Some(6), Some(2))],
+    },
+    TweakSignature {
+        name: "Power Throttling Disabled",
+        origin: "laptop performance tweaks",
+        severity: Severity::Low,
+        reboot: true,
+        settings: &[hklm("SYSTEM\\CurrentControlSet\\Control\\Power\\PowerThrottling", "PowerThrottlingOff", Some(1), None)],
+    },
+    TweakSignature {
+        name: "SysMain Disabled",
+        origin: "SSD optimizers",
+        severity: Severity::Low,
+        reboot: false,
+        settings: &[TweakSetting::Service { service: "SysMain", tweaked: StartType::Disabled, default: StartType::Automatic, delayed_auto: false }],
+    },
+    TweakSignature {
+        name: "Windows Search Disabled",
+        origin: "debloat scripts",
+        severity: Severity::Low,
+        reboot: false,
+        settings: &[TweakSetting::Service { service: "WSearch", tweaked: StartType::Disabled, default: StartType::Automatic, delayed_auto: true }],
+    },
+];
+
+pub fn run_checks(_config: &Config) -> Vec<CheckResult> {
+    TWEAK_SIGNATURES.iter().map(check_signature).collect()
+}
+
+/// Whether a setting currently holds the tweaked value. A value or service
+/// that does not exist is not tweaked.
+#[cfg(target_os = "windows")]
+fn is_tweaked(setting: &TweakSetting) -> crate::error::Result<bool> {
+    use crate::utils::{registry, services};
+    use crate::utils::registry::RegValue;
+
+    match setting {
+        TweakSetting::Registry { key, value, tweaked, default } => {
+            match registry::read_value(key, value) {
+                Ok(RegValue::Dword(current)) => Ok(tweaked.map_or(Some(current) != *default, |t| current == t)),
+                // Never the tweak's own value, but still not what Windows ships
+                Ok(_) => Ok(tweaked.is_none()),
+                Err(e) if e.is_not_found() => Ok(false),
+                Err(e) => Err(e),
+            }
+        }
+        TweakSetting::Service { service, tweaked, .. } => {
+            Ok(matches!(services::query(service)?, Some(config) if config.start_type == *tweaked))
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn check_signature(signature: &TweakSignature) -> CheckResult {
+    let mut found = Vec::new();
+    let mut unreadable = None;
+    for setting in signature.settings {
+        match is_tweaked(setting) {
+            Ok(true) => found.push(setting.describe()),
+            Ok(false) => {}
+            Err(e) => {
+                unreadable.get_or_insert(e);
+            }
+        }
+    }
+
+    // Only an unreadable setting can hide a tweak that is otherwise absent
+    if let (true, Some(e)) = (found.is_empty(), &unreadable) {
+        return CheckResult::unreadable(signature.name, "Tweaks", e);
+    }
+
+    let (status, detail) = if found.is_empty() {
+        (CheckStatus::Ok, "Not present".to_string())
+    } else {
+        let status = if signature.severity == Severity::High { CheckStatus::Bad } else { CheckStatus::Warn };
+        (status, format!("Found {} (seen in {})", found.join(", "), signature.origin))
+    };
+
+    CheckResult {
+        name: signature.name.to_string(),
+        category: "Tweaks".to_string(),
+        status,
+        detail,
+        error: None,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn check_signature(signature: &TweakSignature) -> CheckResult {
+    CheckResult {
+        name: signature.name.to_string(),
+        category: "Tweaks".to_string(),
+        status: CheckStatus::Info,
+        detail: "Windows-only check".to_string(),
+        error: None,
+    }
+}
@@ -1,23 +1,34 @@
+use std::path::PathBuf;
+
 pub struct Config {
-    pub dpc_warn: f64,
-    pub dpc_bad: f64,
-    pub temp_warn: i32,
-    pub temp_crit: i32,
-    pub cpu_usage_warn: f32,
-    pub memory_usage_warn: f32,
-    pub disk_usage_warn: f32,
+    /// Percentage of rated SSD endurance used.
+    pub disk_wear_warn: u32,
+    pub disk_wear_bad: u32,
+    pub disk_temp_warn: i32,
+    pub disk_temp_crit: i32,
+    pub disk_media_errors_bad: u64,
+    pub disk_reallocated_warn: u64,
+    pub disk_reallocated_bad: u64,
+    pub disk_pending_bad: u64,
+    pub disk_unsafe_shutdowns_warn: u64,
+    /// Exported smartctl JSON or NVMe log files to check along with the
+    /// local drives.
+    pub smart_imports: Vec<PathBuf>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            dpc_warn: 1.5,
-            dpc_bad: 3.0,
-            temp_warn: 80,
-            temp_crit: 90,
-            cpu_usage_warn: 90.0,
-            memory_usage_warn: 90.0,
-            disk_usage_warn: 85.0,
+            disk_wear_warn: 80,
+            disk_wear_bad: 100,
+            disk_temp_warn: 60,
+            disk_temp_crit: 70,
+            disk_media_errors_bad: 1,
+            disk_reallocated_warn: 1,
+            disk_reallocated_bad: 100,
+            disk_pending_bad: 1,
+            disk_unsafe_shutdowns_warn: 100,
+            smart_imports: Vec::new(),
         }
     }
 }
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::sync::Arc;
+
+/// What went wrong, without the details. Written to reports so tools can
+/// tell a missing setting from one that needs admin rights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "&'static str", try_from = "String")]
+pub enum ErrorKind {
+    NotFound,
+    AccessDenied,
+    #[cfg(target_os = "windows")]
+    WrongType,
+    #[cfg(not(target_os = "windows"))]
+    Unsupported,
+    Invalid,
+    CommandFailed,
+    #[cfg(target_os = "windows")]
+    Os,
+    Io,
+}
+
+impl ErrorKind {
+    const ALL: &[ErrorKind] = &[
+        ErrorKind::NotFound,
+        ErrorKind::AccessDenied,
+        #[cfg(target_os = "windows")]
+        ErrorKind::WrongType,
+        #[cfg(not(target_os = "windows"))]
+        ErrorKind::Unsupported,
+        ErrorKind::Invalid,
+        ErrorKind::CommandFailed,
+        #[cfg(target_os = "windows")]
+        ErrorKind::Os,
+        ErrorKind::Io,
+    ];
+
+    /// The name used in every report format; JSON goes through it too.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::AccessDenied => "access_denied",
+            #[cfg(target_os = "windows")]
+            ErrorKind::WrongType => "wrong_type",
+            #[cfg(not(target_os = "windows"))]
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Invalid => "invalid",
+            ErrorKind::CommandFailed => "command_failed",
+            #[cfg(target_os = "windows")]
+            ErrorKind::Os => "os",
+            ErrorKind::Io => "io",
+        }
+    }
+}
+
+impl From<ErrorKind> for &'static str {
+    fn from(kind: ErrorKind) -> Self {
+        kind.label()
+    }
+}
+
+impl TryFrom<String> for ErrorKind {
+    type Error = String;
+
+    fn try_from(label: String) -> std::result::Result<Self, String> {
+        ErrorKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.label() == label)
+            .ok_or_else(|| format!("unknown error kind '{}'", label))
+    }
+}
+
+/// Failure of a probe, fix, backup or export. `context` names what was
+/// being accessed, e.g. a registry value path or a file.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("{context}: not found")]
+    NotFound { context: String },
+
+    #[error("{context}: access denied (needs admin)")]
+    AccessDenied { context: String, code: u32 },
+
+    #[cfg(target_os = "windows")]
+    #[error("{context} is {found}, expected {expected}")]
+    WrongType { context: String, expected: &'static str, found: &'static str },
+
+    #[cfg(not(target_os = "windows"))]
+    #[error("{context} is only supported on Windows")]
+    Unsupported { context: String },
+
+    #[error("{context}: {message}")]
+    Invalid { context: String, message: String },
+
+    #[error("{command} failed: {message}")]
+    CommandFailed { command: String, message: String },
+
+    #[cfg(target_os = "windows")]
+    #[error("{context}: {message}")]
+    Os { context: String, code: u32, message: String },
+
+    #[error("{context}: {error}")]
+    Io { context: String, error: Arc<io::Error> },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(target_os = "windows")]
+const ERROR_FILE_NOT_FOUND: u32 = 2;
+#[cfg(target_os = "windows")]
+const ERROR_PATH_NOT_FOUND: u32 = 3;
+const ERROR_ACCESS_DENIED: u32 = 5;
+#[cfg(target_os = "windows")]
+const ERROR_SERVICE_DOES_NOT_EXIST: u32 = 1060;
+
+impl Error {
+    /// Classifies a Win32 error code.
+    #[cfg(target_os = "windows")]
+    pub fn os(code: u32, context: impl Into<String>) -> Self {
+        let context = context.into();
+        match code {
+            ERROR_FILE_NOT_FOUND | ERROR_PATH_NOT_FOUND | ERROR_SERVICE_DOES_NOT_EXIST => Error::NotFound { context },
+            ERROR_ACCESS_DENIED => Error::AccessDenied { context, code },
+            _ => Error::Os { context, code, message: io::Error::from_raw_os_error(code as i32).to_string() },
+        }
+    }
+
+    pub fn io(error: io::Error, context: impl Into<String>) -> Self {
+        let context = context.into();
+        match error.kind() {
+            io::ErrorKind::NotFound => Error::NotFound { context },
+            io::ErrorKind::PermissionDenied => Error::AccessDenied {
+                context,
+                code: error.raw_os_error().map_or(ERROR_ACCESS_DENIED, |c| c as u32),
+            },
+            _ => Error::Io { context, error: Arc::new(error) },
+        }
+    }
+
+    pub fn not_found(context: impl Into<String>) -> Self {
+        Error::NotFound { context: context.into() }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn unsupported(context: impl Into<String>) -> Self {
+        Error::Unsupported { context: context.into() }
+    }
+
+    pub fn invalid(context: impl Into<String>, message: impl ToString) -> Self {
+        Error::Invalid { context: context.into(), message: message.to_string() }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::NotFound { .. } => ErrorKind::NotFound,
+            Error::AccessDenied { .. } => ErrorKind::AccessDenied,
+            #[cfg(target_os = "windows")]
+            Error::WrongType { .. } => ErrorKind::WrongType,
+            #[cfg(not(target_os = "windows"))]
+            Error::Unsupported { .. } => ErrorKind::Unsupported,
+            Error::Invalid { .. } => ErrorKind::Invalid,
+            Error::CommandFailed { .. } => ErrorKind::CommandFailed,
+            #[cfg(target_os = "windows")]
+            Error::Os { .. } => ErrorKind::Os,
+            Error::Io { .. } => ErrorKind::Io,
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound
+    }
+}
@@ -0,0 +1,235 @@
+use crate::fixes::{apply, backup, reboot};
+use crate::fixes::plan::Plan;
+use crate::types::Issue;
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+/// Statistic an experiment is judged by. Lower is better for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum Metric {
+    Mean,
+    Median,
+    P99,
+    Max,
+}
+
+/// Summary of timer latency samples, in microseconds.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let count = sorted.len();
+        let percentile = |p: f64| sorted.get(((count as f64 * p) as usize).min(count.saturating_sub(1))).copied().unwrap_or(0.0);
+        let mean = sorted.iter().sum::<f64>() / count.max(1) as f64;
+        let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count.saturating_sub(1).max(1) as f64;
+
+        Stats {
+            count,
+            mean,
+            median: percentile(0.5),
+            p99: percentile(0.99),
+            max: sorted.last().copied().unwrap_or(0.0),
+            stddev: variance.sqrt(),
+        }
+    }
+
+    pub fn metric(&self, metric: Metric) -> f64 {
+        match metric {
+            Metric::Mean => self.mean,
+            Metric::Median => self.median,
+            Metric::P99 => self.p99,
+            Metric::Max => self.max,
+        }
+    }
+}
+
+/// Measures how late the OS wakes a thread from a 1 ms sleep, which is what
+/// timer, tick and interrupt tweaks change. Returns the overshoot of each
+/// sleep in microseconds.
+pub fn measure(samples: usize) -> Vec<f64> {
+    let interval = Duration::from_millis(1);
+
+    // Let the CPU leave its idle states before recording
+    for _ in 0..20 {
+        std::thread::sleep(interval);
+    }
+
+    (0..samples)
+        .map(|_| {
+            let start = Instant::now();
+            std::thread::sleep(interval);
+            start.elapsed().saturating_sub(interval).as_secs_f64() * 1_000_000.0
+        })
+        .collect()
+}
+
+/// Baseline against post-change measurements.
+#[derive(Debug)]
+pub struct Comparison {
+    pub before: Stats,
+    pub after: Stats,
+    /// Welch's t statistic for the difference in means.
+    pub t: f64,
+}
+
+impl Comparison {
+    pub fn new(before: &[f64], after: &[f64]) -> Self {
+        let before = Stats::from_samples(before);
+        let after = Stats::from_samples(after);
+        let error = (before.stddev.powi(2) / before.count.max(1) as f64
+            + after.stddev.powi(2) / after.count.max(1) as f64)
+            .sqrt();
+        let t = if error > 0.0 { (before.mean - after.mean) / error } else { 0.0 };
+
+        Comparison { before, after, t }
+    }
+
+    /// Improvement of a metric in percent; negative when it got worse.
+    pub fn improvement(&self, metric: Metric) -> f64 {
+        let before = self.before.metric(metric);
+        if before == 0.0 {
+            return 0.0;
+        }
+        (before - self.after.metric(metric)) / before * 100.0
+    }
+
+    /// Whether the means differ at roughly 95% confidence. With hundreds of
+    /// samples the t distribution is close enough to normal.
+    pub fn significant(&self) -> bool {
+        self.t.abs() > 1.96
+    }
+}
+
+/// A fix set applied on trial, with everything needed to judge and undo it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Experiment {
+    pub issues: Vec<Issue>,
+    pub metric: Metric,
+    /// Minimum improvement of the metric, in percent, to keep the fixes.
+    pub min_improvement: f64,
+    pub require_significant: bool,
+    pub samples: usize,
+    pub baseline: Vec<f64>,
+    /// Backup files of the applied changes, in the order they were written.
+    pub backups: Vec<PathBuf>,
+    /// Unix timestamp of when the fixes were applied.
+    pub applied_at: u64,
+}
+
+impl Experiment {
+    pub fn applied_at_display(&self) -> String {
+        Local
+            .timestamp_opt(self.applied_at as i64, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| self.applied_at.to_string())
+    }
+
+    pub fn needs_reboot(&self) -> bool {
+        self.issues.iter().any(|i| i.reboot)
+    }
+
+    /// The fixes need a restart that has not happened yet.
+    pub fn waiting_for_reboot(&self) -> bool {
+        self.needs_reboot() && System::boot_time() <= self.applied_at
+    }
+
+    /// Whether the measured result meets the criterion the experiment was
+    /// started with.
+    pub fn passes(&self, comparison: &Comparison) -> bool {
+        comparison.improvement(self.metric) >= self.min_improvement
+            && (!self.require_significant || (comparison.significant() && comparison.t > 0.0))
+    }
+
+    /// Applies the experiment's fixes in plan order, recording their backups.
+    /// If a change fails, the ones already made are rolled back.
+    pub fn apply(&mut self) -> Result<()> {
+        let issues: Vec<&Issue> = self.issues.iter().collect();
+        let plan = Plan::new(&issues);
+        if !plan.conflicts.is_empty() {
+            plan.print_conflicts();
+            return Err(anyhow::anyhow!("The selected fixes change the same setting differently"));
+        }
+
+        for step in &plan.steps {
+            match apply::apply_change(&step.change) {
+                Ok(Some(path)) => self.backups.push(path),
+                Ok(None) => {}
+                Err(e) => {
+                    let _ = self.rollback();
+                    return Err(anyhow::anyhow!("{}: {}", step.change.describe(), e));
+                }
+            }
+        }
+
+        for issue in self.issues.iter().filter(|i| i.reboot) {
+            reboot::record(issue)?;
+        }
+        self.applied_at = Local::now().timestamp() as u64;
+        Ok(())
+    }
+
+    /// Restores every backup in reverse order, so e.g. the previous power
+    /// scheme is active again before a created one is deleted. A backup that
+    /// cannot be restored does not stop the others; the failures are
+    /// returned.
+    pub fn rollback(&self) -> Result<Vec<String>> {
+        let mut failures = Vec::new();
+        for path in self.backups.iter().rev() {
+            if let Err(e) = backup::rollback_from_file(&path.to_string_lossy()) {
+                failures.push(format!("{}: {}", path.display(), e));
+            }
+        }
+        for issue in self.issues.iter().filter(|i| i.reboot) {
+            reboot::forget(issue)?;
+        }
+        Ok(failures)
+    }
+}
+
+fn experiment_path() -> PathBuf {
+    PathBuf::from("backups").join("experiment.json")
+}
+
+pub fn load() -> Result<Option<Experiment>> {
+    let path = experiment_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&json)?))
+}
+
+pub fn save(experiment: &Experiment) -> Result<()> {
+    let path = experiment_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(experiment)?)?;
+    Ok(())
+}
+
+pub fn clear() -> Result<()> {
+    let path = experiment_path();
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
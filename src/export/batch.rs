@@ -0,0 +1,182 @@
+use crate::fixes::plan::Plan;
+use crate::types::{Change, Issue};
+use crate::utils::registry::RegPath;
+use crate::error::{Error, Result};
+use chrono::Local;
+use std::fs;
+
+/// Writes a `.cmd` script applying the issues' changes with `reg add`. The
+/// touched keys are exported on the first run, and the values it sets are
+/// noted as present or absent; running the script with `/rollback` imports
+/// those exports again and deletes the values that were absent.
+pub fn export(issues: &[&Issue], path: &str) -> Result<()> {
+    let mut script = String::from("@echo off\r\n");
+
+    script.push_str("rem APEX v11 remediation script\r\n");
+    script.push_str(&format!("rem Generated {}\r\n", Local::now().format("%Y-%m-%d %H:%M:%S")));
+    script.push_str("rem Run as administrator. Re-running is safe.\r\n");
+    script.push_str("rem Restore the previous values with: <script>.cmd /rollback\r\n");
+    script.push_str("rem\r\n");
+    for issue in issues {
+        script.push_str(&format!("rem   #{} [{}] [{}] {} - {}\r\n",
+            issue.num, issue.category, issue.safety.label(), issue.problem, issue.solution));
+    }
+    script.push_str("rem\r\n");
+    let touches_power = issues.iter().flat_map(|i| i.changes.iter()).any(|c| matches!(c,
+        Change::PowerSchemeCopy { .. } | Change::ActivePowerScheme { .. } | Change::PowerSetting { .. }));
+    if touches_power {
+        script.push_str("rem Power plan changes are not undone by /rollback; use the .ps1 export for those.\r\n");
+    }
+    script.push_str("\r\n");
+    script.push_str("setlocal\r\n");
+    script.push_str("set \"BACKUP=%~dp0apex_backup\"\r\n");
+    script.push_str("if /i \"%~1\"==\"/rollback\" goto rollback\r\n");
+    script.push_str("if not exist \"%BACKUP%\" mkdir \"%BACKUP%\"\r\n\r\n");
+
+    // Export every touched key before the first change so a rollback never
+    // restores a value this script already modified
+    let mut keys: Vec<String> = Vec::new();
+    // Values the script sets, which a rollback deletes if they did not exist
+    let mut values: Vec<(String, &str)> = Vec::new();
+    for change in issues.iter().flat_map(|i| i.changes.iter()) {
+        let key = match change {
+            Change::RegistryDword { key, value, .. } => {
+                let key = format!("\"{}\\{}\"{}", key.hive.short_name(), key.physical_subkey(), view_flag(key));
+                if !values.iter().any(|(k, v)| *k == key && v.eq_ignore_ascii_case(value)) {
+                    values.push((key.clone(), value));
+                }
+                key
+            }
+            Change::RegistryDelete { key, .. } => {
+                format!("\"{}\\{}\"{}", key.hive.short_name(), key.physical_subkey(), view_flag(key))
+            }
+            // The service key holds the start type; the running state is not backed up
+            Change::ServiceStartType { name, .. } => format!("\"HKLM\\SYSTEM\\CurrentControlSet\\Services\\{}\"", name),
+            Change::ServiceRunning { .. } => continue,
+            Change::PowerSchemeCopy { .. } | Change::ActivePowerScheme { .. } | Change::PowerSetting { .. } => continue,
+        };
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    // A key that does not exist yet has nothing to export; its values are
+    // recorded as absent below
+    script.push_str("rem Back up affected keys (first run only)\r\n");
+    for (index, key) in keys.iter().enumerate() {
+        script.push_str(&format!(
+            "if not exist \"%BACKUP%\\{:03}.reg\" reg query {} >nul 2>&1 && (reg export {} \"%BACKUP%\\{:03}.reg\" /y >nul || goto failed)\r\n",
+            index + 1, key, key, index + 1
+        ));
+    }
+    script.push_str("rem Record values that do not exist yet, so /rollback deletes them\r\n");
+    for (index, (key, value)) in values.iter().enumerate() {
+        script.push_str(&format!(
+            "if not exist \"%BACKUP%\\{:03}.set\" (reg query {} /v \"{}\" >nul 2>&1 && (echo present) || (echo absent)) > \"%BACKUP%\\{:03}.set\"\r\n",
+            index + 1, key, value, index + 1
+        ));
+    }
+    script.push_str("\r\n");
+
+    // Plan order: services stop before being disabled, schemes exist before
+    // they are activated
+    let mut current: Option<&Vec<u32>> = None;
+    for step in &Plan::new(issues).steps {
+        if current != Some(&step.issues) {
+            if current.is_some() {
+                script.push_str("\r\n");
+            }
+            let nums: Vec<String> = step.issues.iter().map(|n| format!("#{}", n)).collect();
+            script.push_str(&format!("rem {}\r\n", nums.join(" ")));
+            current = Some(&step.issues);
+        }
+
+        match &step.change {
+            Change::RegistryDword { key, value, data } => {
+                script.push_str(&format!(
+                    "reg add \"{}\\{}\" /v \"{}\" /t REG_DWORD /d {} /f{} >nul || goto failed\r\n",
+                    key.hive.short_name(), key.physical_subkey(), value, data, view_flag(key)
+                ));
+            }
+            Change::RegistryDelete { key, value } => {
+                // Ignore the result: the value may already be gone
+                script.push_str(&format!(
+                    "reg delete \"{}\\{}\" /v \"{}\" /f{} >nul 2>&1\r\n",
+                    key.hive.short_name(), key.physical_subkey(), value, view_flag(key)
+                ));
+            }
+            Change::ServiceStartType { name, start_type, delayed_auto } => {
+                script.push_str(&format!(
+                    "sc query \"{}\" >nul 2>&1 && (sc config \"{}\" start= {} >nul || goto failed)\r\n",
+                    name, name, start_type.sc_keyword(*delayed_auto)
+                ));
+            }
+            Change::ServiceRunning { name, running } => {
+                // Ignore the result: the service may already be in the wanted state
+                script.push_str(&format!(
+                    "sc {} \"{}\" >nul 2>&1\r\n",
+                    if *running { "start" } else { "stop" }, name
+                ));
+            }
+            Change::PowerSchemeCopy { source, destination } => {
+                script.push_str(&format!(
+                    "powercfg /list | find /i \"{}\" >nul || (powercfg /duplicatescheme {} {} >nul || goto failed)\r\n",
+                    destination, source, destination
+                ));
+            }
+            Change::ActivePowerScheme { scheme } => {
+                script.push_str(&format!("powercfg /setactive {} || goto failed\r\n", scheme));
+            }
+            Change::PowerSetting { scheme, subgroup, setting, ac, dc } => {
+                if let Some(ac) = ac {
+                    script.push_str(&format!(
+                        "powercfg /setacvalueindex {} {} {} {} || goto failed\r\n",
+                        scheme, subgroup, setting, ac
+                    ));
+                }
+                if let Some(dc) = dc {
+                    script.push_str(&format!(
+                        "powercfg /setdcvalueindex {} {} {} {} || goto failed\r\n",
+                        scheme, subgroup, setting, dc
+                    ));
+                }
+                // Re-activate so changes to the current scheme take effect
+                script.push_str("powercfg /setactive SCHEME_CURRENT >nul\r\n");
+            }
+        }
+    }
+    script.push_str("\r\n");
+
+    script.push_str("echo Remediation complete.\r\n");
+    if issues.iter().any(|i| i.reboot) {
+        script.push_str("echo Some changes take effect after a reboot.\r\n");
+    }
+    script.push_str("exit /b 0\r\n\r\n");
+
+    script.push_str(":rollback\r\n");
+    script.push_str("if not exist \"%BACKUP%\" (\r\n");
+    script.push_str("    echo No backup found in %BACKUP%\r\n");
+    script.push_str("    exit /b 1\r\n");
+    script.push_str(")\r\n");
+    script.push_str("for %%f in (\"%BACKUP%\\*.reg\") do reg import \"%%f\" || goto failed\r\n");
+    for (index, (key, value)) in values.iter().enumerate() {
+        script.push_str(&format!(
+            "findstr /b absent \"%BACKUP%\\{:03}.set\" >nul 2>&1 && reg query {} /v \"{}\" >nul 2>&1 && (reg delete {} /v \"{}\" /f >nul || goto failed)\r\n",
+            index + 1, key, value, key, value
+        ));
+    }
+    script.push_str("echo Rollback complete.\r\n");
+    script.push_str("exit /b 0\r\n\r\n");
+
+    script.push_str(":failed\r\n");
+    script.push_str("echo Failed to apply a change. Check that the script runs as administrator.\r\n");
+    script.push_str("exit /b 1\r\n");
+
+    fs::write(path, script).map_err(|e| Error::io(e, path))
+}
+
+/// Keys pinned to a view are written by their 64-bit path (see
+/// `RegPath::physical_subkey`), which a 32-bit `reg.exe` would redirect.
+fn view_flag(key: &RegPath) -> &'static str {
+    if key.view.is_native() { "" } else { " /reg:64" }
+}
@@ -1,20 +1,24 @@
 use crate::types::AuditResults;
-use anyhow::Result;
+use crate::error::{Error, Result};
 use std::fs;
 
 pub fn export(results: &AuditResults, path: &str) -> Result<()> {
-    let mut csv = String::from("Category,Check,Status,Detail\n");
+    let mut csv = String::from("Category,Check,Status,Detail,Error\n");
     
     for check in &results.checks {
         csv.push_str(&format!(
-            "\"{}\",\"{}\",\"{:?}\",\"{}\"\n",
+            "\"{}\",\"{}\",\"{:?}\",\"{}\",{}\n",
             check.category,
             check.name,
             check.status,
-            check.detail.replace('"', "\"\"")
+            check.detail.replace('"', "\"\""),
+            check.error.map_or("", |kind| kind.label())
         ));
     }
     
-    fs::write(path, csv)?;
-    Ok(())
+    for (component, detail) in results.hardware.iter().flat_map(|h| h.summary()) {
+        csv.push_str(&format!("\"Hardware\",\"{}\",\"Info\",\"{}\",\n", component, detail.replace('"', "\"\"")));
+    }
+    
+    fs::write(path, csv).map_err(|e| Error::io(e, path))
 }
@@ -1,5 +1,5 @@
 use crate::types::AuditResults;
-use anyhow::Result;
+use crate::error::{Error, Result};
 use std::fs;
 
 pub fn export(results: &AuditResults, path: &str) -> Result<()> {
@@ -24,6 +24,14 @@ pub fn export(results: &AuditResults, path: &str) -> Result<()> {
 <body>
     <h1>APEX v11 Audit Report</h1>
     <div class="score">Score: {}/100</div>
+    <h2>Hardware</h2>
+    <table>
+        <tr>
+            <th>Component</th>
+            <th>Details</th>
+        </tr>
+        {}
+    </table>
     <h2>System Checks ({} total)</h2>
     <table>
         <tr>
@@ -40,6 +48,7 @@ pub fn export(results: &AuditResults, path: &str) -> Result<()> {
             <th>#</th>
             <th>Category</th>
             <th>Severity</th>
+            <th>Safety</th>
             <th>Problem</th>
             <th>Solution</th>
         </tr>
@@ -49,9 +58,15 @@ pub fn export(results: &AuditResults, path: &str) -> Result<()> {
 </html>"#,
         if results.score >= 90 { "#00ff00" } else if results.score >= 70 { "#ffaa00" } else { "#ff0000" },
         results.score,
+        results.hardware.iter().flat_map(|h| h.summary()).map(|(component, detail)| format!(
+            "<tr><td>{}</td><td>{}</td></tr>",
+            component,
+            detail
+        )).collect::<Vec<_>>().join("\n"),
         results.check_count,
         results.checks.iter().map(|c| format!(
-            "<tr><td>{}</td><td>{}</td><td class=\"{}\">{:?}</td><td>{}</td></tr>",
+            "<tr{}><td>{}</td><td>{}</td><td class=\"{}\">{:?}</td><td>{}</td></tr>",
+            c.error.map_or(String::new(), |kind| format!(" data-error=\"{}\"", kind.label())),
             c.category,
             c.name,
             match c.status {
@@ -65,15 +80,15 @@ pub fn export(results: &AuditResults, path: &str) -> Result<()> {
         )).collect::<Vec<_>>().join("\n"),
         results.issues.len(),
         results.issues.iter().map(|i| format!(
-            "<tr><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td></tr>",
+            "<tr><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
             i.num,
             i.category,
             i.severity,
+            i.safety.label(),
             i.problem,
             i.solution
         )).collect::<Vec<_>>().join("\n")
     );
     
-    fs::write(path, html)?;
-    Ok(())
+    fs::write(path, html).map_err(|e| Error::io(e, path))
 }
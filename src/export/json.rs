@@ -1,9 +1,8 @@
 use crate::types::AuditResults;
-use anyhow::Result;
+use crate::error::{Error, Result};
 use std::fs;
 
 pub fn export(results: &AuditResults, path: &str) -> Result<()> {
-    let json = serde_json::to_string_pretty(results)?;
-    fs::write(path, json)?;
-    Ok(())
+    let json = serde_json::to_string_pretty(results).map_err(|e| Error::invalid(path, e))?;
+    fs::write(path, json).map_err(|e| Error::io(e, path))
 }
@@ -1,3 +1,6 @@
 pub mod json;
 pub mod html;
 pub mod csv;
+pub mod powershell;
+pub mod regfile;
+pub mod batch;
@@ -0,0 +1,313 @@
+use crate::fixes::plan::Plan;
+use crate::types::{Change, Issue};
+use crate::error::{Error, Result};
+use chrono::Local;
+use std::fs;
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn change_entry(issue: u32, change: &Change) -> String {
+    match change {
+        Change::RegistryDword { key, value, data } => format!(
+            "    @{{ Issue = {}; Kind = 'Registry'; Path = {}; Name = {}; Type = 'DWord'; Value = {} }}",
+            issue,
+            quote(&key.provider_path()),
+            quote(value),
+            // Set-ItemProperty expects DWORDs as signed 32-bit integers
+            *data as i32
+        ),
+        Change::RegistryDelete { key, value } => format!(
+            "    @{{ Issue = {}; Kind = 'RegistryDelete'; Path = {}; Name = {} }}",
+            issue,
+            quote(&key.provider_path()),
+            quote(value)
+        ),
+        Change::ServiceStartType { name, start_type, delayed_auto } => format!(
+            "    @{{ Issue = {}; Kind = 'StartType'; Name = {}; Value = '{}' }}",
+            issue,
+            quote(name),
+            start_type.sc_keyword(*delayed_auto)
+        ),
+        Change::ServiceRunning { name, running } => format!(
+            "    @{{ Issue = {}; Kind = 'Running'; Name = {}; Value = ${} }}",
+            issue,
+            quote(name),
+            running
+        ),
+        Change::PowerSchemeCopy { source, destination } => format!(
+            "    @{{ Issue = {}; Kind = 'SchemeCopy'; Source = {}; Name = {} }}",
+            issue,
+            quote(source),
+            quote(destination)
+        ),
+        Change::ActivePowerScheme { scheme } => format!(
+            "    @{{ Issue = {}; Kind = 'ActiveScheme'; Name = {} }}",
+            issue,
+            quote(scheme)
+        ),
+        Change::PowerSetting { scheme, subgroup, setting, ac, dc } => {
+            let index = |v: &Option<u32>| v.map_or("$null".to_string(), |v| v.to_string());
+            format!(
+                "    @{{ Issue = {}; Kind = 'PowerSetting'; Scheme = {}; Subgroup = {}; Name = {}; AC = {}; DC = {} }}",
+                issue,
+                quote(scheme),
+                quote(subgroup),
+                quote(setting),
+                index(ac),
+                index(dc)
+            )
+        }
+    }
+}
+
+/// Writes an idempotent PowerShell script applying the issues' changes. The
+/// script records previous values before changing anything and restores them
+/// when run with `-Rollback`.
+pub fn export(issues: &[&Issue], path: &str) -> Result<()> {
+    let mut script = String::new();
+
+    script.push_str("# APEX v11 remediation script\n");
+    script.push_str(&format!("# Generated {}\n", Local::now().format("%Y-%m-%d %H:%M:%S")));
+    script.push_str("#\n");
+    for issue in issues {
+        script.push_str(&format!("#   #{} [{}] [{}] {} -> {}{}\n",
+            issue.num,
+            issue.category,
+            issue.safety.label(),
+            issue.problem,
+            issue.solution,
+            if issue.reboot { " (reboot)" } else { "" }
+        ));
+    }
+    script.push_str("#\n");
+    script.push_str("# Run as administrator. HKCU changes apply to the user running the script.\n");
+    script.push_str("# Re-running is safe: settings already in the desired state are left alone.\n");
+    script.push_str("# Restore the previous settings with: .\\<script>.ps1 -Rollback\n\n");
+
+    script.push_str("param(\n");
+    script.push_str("    [switch]$Rollback,\n");
+    script.push_str("    [string]$BackupFile = (Join-Path $PSScriptRoot 'apex_backup.json')\n");
+    script.push_str(")\n\n");
+    script.push_str("$ErrorActionPreference = 'Stop'\n\n");
+
+    script.push_str("$Changes = @(\n");
+    // In plan order, so services stop before being disabled and schemes exist
+    // before they are activated
+    let entries: Vec<String> = Plan::new(issues).steps.iter()
+        .map(|step| change_entry(step.issues[0], &step.change))
+        .collect();
+    script.push_str(&entries.join(",\n"));
+    script.push_str("\n)\n\n");
+
+    script.push_str(r#"function Get-StartType([string]$Name) {
+    $key = Get-ItemProperty -Path "HKLM:\SYSTEM\CurrentControlSet\Services\$Name" -ErrorAction SilentlyContinue
+    if ($null -eq $key) { return $null }
+    switch ($key.Start) {
+        0 { 'boot' }
+        1 { 'system' }
+        2 { if ($key.DelayedAutostart -eq 1) { 'delayed-auto' } else { 'auto' } }
+        3 { 'demand' }
+        4 { 'disabled' }
+    }
+}
+
+function Set-StartType([string]$Name, [string]$StartType) {
+    sc.exe config $Name start= $StartType | Out-Null
+    if ($LASTEXITCODE -ne 0) {
+        throw "sc.exe config $Name failed with exit code $LASTEXITCODE"
+    }
+}
+
+function Set-Running([string]$Name, [bool]$Running) {
+    if ($Running) { Start-Service -Name $Name } else { Stop-Service -Name $Name -Force }
+}
+
+function Invoke-PowerCfg {
+    $output = & powercfg.exe @args
+    if ($LASTEXITCODE -ne 0) {
+        throw "powercfg $($args -join ' ') failed: $output"
+    }
+    $output
+}
+
+function Get-ActiveScheme {
+    [regex]::Match((Invoke-PowerCfg /getactivescheme | Out-String), '[0-9a-fA-F-]{36}').Value.ToLower()
+}
+
+function Resolve-Scheme([string]$Scheme) {
+    if ($Scheme -eq 'SCHEME_CURRENT') { Get-ActiveScheme } else { $Scheme.ToLower() }
+}
+
+function Get-PowerValue([string]$Scheme, [string]$Subgroup, [string]$Setting) {
+    # The AC and DC indices are the last two hex values, whatever the display language
+    $hex = [regex]::Matches((Invoke-PowerCfg /qh $Scheme $Subgroup $Setting | Out-String), '0x[0-9a-fA-F]{8}')
+    @{
+        AC = [Convert]::ToUInt32($hex[$hex.Count - 2].Value, 16)
+        DC = [Convert]::ToUInt32($hex[$hex.Count - 1].Value, 16)
+    }
+}
+
+function Set-PowerValue([string]$Scheme, [string]$Subgroup, [string]$Setting, $AC, $DC) {
+    Invoke-PowerCfg /setacvalueindex $Scheme $Subgroup $Setting $AC | Out-Null
+    Invoke-PowerCfg /setdcvalueindex $Scheme $Subgroup $Setting $DC | Out-Null
+    if ((Get-ActiveScheme) -eq $Scheme) {
+        Invoke-PowerCfg /setactive $Scheme | Out-Null
+    }
+}
+
+if ($Rollback) {
+    if (-not (Test-Path $BackupFile)) {
+        throw "Backup file not found: $BackupFile"
+    }
+    $entries = @(Get-Content -Raw $BackupFile | ConvertFrom-Json)
+    # Undo in reverse so start types are restored before services are restarted
+    [array]::Reverse($entries)
+    foreach ($entry in $entries) {
+        if ($entry.Kind -eq 'Registry') {
+            if ($entry.Existed) {
+                Set-ItemProperty -Path $entry.Path -Name $entry.Name -Type $entry.Type -Value $entry.Value
+                Write-Host "RESTORED $($entry.Path)\$($entry.Name) = $($entry.Value)"
+            } else {
+                Remove-ItemProperty -Path $entry.Path -Name $entry.Name -ErrorAction SilentlyContinue
+                Write-Host "REMOVED  $($entry.Path)\$($entry.Name)"
+            }
+        } elseif ($entry.Kind -eq 'StartType') {
+            Set-StartType $entry.Name $entry.Value
+            Write-Host "RESTORED service $($entry.Name) start = $($entry.Value)"
+        } elseif ($entry.Kind -eq 'Running') {
+            Set-Running $entry.Name $entry.Value
+            Write-Host "RESTORED service $($entry.Name) running = $($entry.Value)"
+        } elseif ($entry.Kind -eq 'SchemeCopy') {
+            Invoke-PowerCfg /delete $entry.Name | Out-Null
+            Write-Host "REMOVED  power scheme $($entry.Name)"
+        } elseif ($entry.Kind -eq 'ActiveScheme') {
+            Invoke-PowerCfg /setactive $entry.Value | Out-Null
+            Write-Host "RESTORED active power scheme = $($entry.Value)"
+        } elseif ($entry.Kind -eq 'PowerSetting') {
+            Set-PowerValue $entry.Scheme $entry.Subgroup $entry.Name $entry.AC $entry.DC
+            Write-Host "RESTORED power $($entry.Name) AC = $($entry.AC), DC = $($entry.DC)"
+        }
+    }
+    Remove-Item $BackupFile
+    return
+}
+
+$backup = @()
+if (Test-Path $BackupFile) {
+    $backup = @(Get-Content -Raw $BackupFile | ConvertFrom-Json)
+}
+
+function Save-Backup($Entry) {
+    # Keep the oldest backup so repeated runs still roll back to the original setting
+    $existing = $script:backup | Where-Object {
+        $_.Kind -eq $Entry.Kind -and $_.Name -eq $Entry.Name -and $_.Path -eq $Entry.Path
+    }
+    if (-not $existing) {
+        $script:backup += [pscustomobject]$Entry
+        ConvertTo-Json -InputObject @($script:backup) | Set-Content -Path $BackupFile
+    }
+}
+
+foreach ($change in $Changes) {
+    if ($change.Kind -eq 'Registry') {
+        $current = Get-ItemProperty -Path $change.Path -Name $change.Name -ErrorAction SilentlyContinue
+        $existed = $null -ne $current
+        $value = if ($existed) { $current.($change.Name) } else { $null }
+
+        if ($existed -and $value -eq $change.Value) {
+            Write-Host "OK       $($change.Path)\$($change.Name) = $($change.Value)"
+            continue
+        }
+
+        $type = if ($existed) { (Get-Item -Path $change.Path).GetValueKind($change.Name).ToString() } else { $change.Type }
+        Save-Backup ([ordered]@{ Kind = 'Registry'; Path = $change.Path; Name = $change.Name; Type = $type; Existed = $existed; Value = $value })
+        if (-not (Test-Path $change.Path)) {
+            New-Item -Path $change.Path -Force | Out-Null
+        }
+        Set-ItemProperty -Path $change.Path -Name $change.Name -Type $change.Type -Value $change.Value
+        Write-Host "SET      $($change.Path)\$($change.Name) = $($change.Value)"
+    } elseif ($change.Kind -eq 'RegistryDelete') {
+        $current = Get-ItemProperty -Path $change.Path -Name $change.Name -ErrorAction SilentlyContinue
+        if ($null -eq $current) {
+            Write-Host "OK       $($change.Path)\$($change.Name) not set"
+            continue
+        }
+
+        # Keep the value's own kind so -Rollback recreates a string as a string
+        $type = (Get-Item -Path $change.Path).GetValueKind($change.Name).ToString()
+        Save-Backup ([ordered]@{ Kind = 'Registry'; Path = $change.Path; Name = $change.Name; Type = $type; Existed = $true; Value = $current.($change.Name) })
+        Remove-ItemProperty -Path $change.Path -Name $change.Name
+        Write-Host "REMOVED  $($change.Path)\$($change.Name)"
+    } elseif ($change.Kind -eq 'StartType') {
+        $current = Get-StartType $change.Name
+        if ($null -eq $current) {
+            Write-Host "SKIP     service $($change.Name) is not installed"
+            continue
+        }
+        if ($current -eq $change.Value) {
+            Write-Host "OK       service $($change.Name) start = $current"
+            continue
+        }
+
+        Save-Backup ([ordered]@{ Kind = 'StartType'; Path = $null; Name = $change.Name; Value = $current })
+        Set-StartType $change.Name $change.Value
+        Write-Host "SET      service $($change.Name) start = $($change.Value)"
+    } elseif ($change.Kind -eq 'Running') {
+        $service = Get-Service -Name $change.Name -ErrorAction SilentlyContinue
+        if ($null -eq $service) {
+            Write-Host "SKIP     service $($change.Name) is not installed"
+            continue
+        }
+        $running = $service.Status -eq 'Running'
+        if ($running -eq $change.Value) {
+            Write-Host "OK       service $($change.Name) running = $running"
+            continue
+        }
+
+        Save-Backup ([ordered]@{ Kind = 'Running'; Path = $null; Name = $change.Name; Value = $running })
+        Set-Running $change.Name $change.Value
+        Write-Host "SET      service $($change.Name) running = $($change.Value)"
+    } elseif ($change.Kind -eq 'SchemeCopy') {
+        if ((Invoke-PowerCfg /list | Out-String) -match $change.Name) {
+            Write-Host "OK       power scheme $($change.Name) exists"
+            continue
+        }
+
+        Save-Backup ([ordered]@{ Kind = 'SchemeCopy'; Path = $null; Name = $change.Name })
+        Invoke-PowerCfg /duplicatescheme $change.Source $change.Name | Out-Null
+        Write-Host "CREATED  power scheme $($change.Name)"
+    } elseif ($change.Kind -eq 'ActiveScheme') {
+        $current = Get-ActiveScheme
+        if ($current -eq $change.Name) {
+            Write-Host "OK       active power scheme = $current"
+            continue
+        }
+
+        Save-Backup ([ordered]@{ Kind = 'ActiveScheme'; Path = $null; Name = 'active'; Value = $current })
+        Invoke-PowerCfg /setactive $change.Name | Out-Null
+        Write-Host "SET      active power scheme = $($change.Name)"
+    } elseif ($change.Kind -eq 'PowerSetting') {
+        $scheme = Resolve-Scheme $change.Scheme
+        $current = Get-PowerValue $scheme $change.Subgroup $change.Name
+        $ac = if ($null -ne $change.AC) { $change.AC } else { $current.AC }
+        $dc = if ($null -ne $change.DC) { $change.DC } else { $current.DC }
+        if ($current.AC -eq $ac -and $current.DC -eq $dc) {
+            Write-Host "OK       power $($change.Name) AC = $ac, DC = $dc"
+            continue
+        }
+
+        Save-Backup ([ordered]@{ Kind = 'PowerSetting'; Path = "$scheme\$($change.Subgroup)"; Name = $change.Name; Scheme = $scheme; Subgroup = $change.Subgroup; AC = $current.AC; DC = $current.DC })
+        Set-PowerValue $scheme $change.Subgroup $change.Name $ac $dc
+        Write-Host "SET      power $($change.Name) AC = $ac, DC = $dc"
+    }
+}
+"#);
+
+    if issues.iter().any(|i| i.reboot) {
+        script.push_str("\nWrite-Host 'Some changes take effect after a reboot.'\n");
+    }
+
+    fs::write(path, script).map_err(|e| Error::io(e, path))
+}
@@ -0,0 +1,56 @@
+use crate::types::{Change, Issue};
+use crate::error::{Error, Result};
+use chrono::Local;
+use std::fs;
+
+/// Writes a `.reg` file with the registry changes of the given issues.
+/// Changes that are not plain registry writes are listed as comments so the
+/// reviewer can see what the file leaves out. Returns the issues with such
+/// changes, so the caller can warn about them.
+pub fn export<'a>(issues: &[&'a Issue], path: &str) -> Result<Vec<&'a Issue>> {
+    let mut skipped = Vec::new();
+    let mut reg = String::from("Windows Registry Editor Version 5.00\r\n\r\n");
+
+    reg.push_str("; APEX v11 remediation\r\n");
+    reg.push_str(&format!("; Generated {}\r\n\r\n", Local::now().format("%Y-%m-%d %H:%M:%S")));
+
+    for issue in issues {
+        reg.push_str(&format!("; #{} [{}] [{}] {} -> {}{}\r\n",
+            issue.num,
+            issue.category,
+            issue.safety.label(),
+            issue.problem,
+            issue.solution,
+            if issue.reboot { " (reboot)" } else { "" }
+        ));
+
+        for change in &issue.changes {
+            match change {
+                Change::RegistryDword { key, value, data } => {
+                    reg.push_str(&format!("[{}]\r\n", key.full_path()));
+                    reg.push_str(&format!("\"{}\"=dword:{:08x}\r\n", value.replace('\\', "\\\\").replace('"', "\\\""), data));
+                }
+                Change::RegistryDelete { key, value } => {
+                    reg.push_str(&format!("[{}]\r\n", key.full_path()));
+                    reg.push_str(&format!("\"{}\"=-\r\n", value.replace('\\', "\\\\").replace('"', "\\\"")));
+                }
+                other => {
+                    if !skipped.iter().any(|s: &&Issue| s.num == issue.num) {
+                        skipped.push(*issue);
+                    }
+                    reg.push_str(&format!("; not included (not a registry value): {}\r\n", other.describe()));
+                }
+            }
+        }
+        reg.push_str("\r\n");
+    }
+
+    // regedit expects UTF-16LE with a byte order mark for version 5.00 files
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in reg.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    fs::write(path, bytes).map_err(|e| Error::io(e, path))?;
+    Ok(skipped)
+}
@@ -0,0 +1,113 @@
+use crate::fixes::plan::Plan;
+use crate::fixes::{power, reboot, registry, services};
+use crate::types::{Change, Issue};
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Applies a single change, backing up the previous value first. Returns the
+/// backup file, or `None` when there was nothing to back up.
+pub fn apply_change(change: &Change) -> Result<Option<PathBuf>> {
+    match change {
+        Change::RegistryDword { key, value, data } => registry::write_dword_with_backup(key, value, *data),
+        Change::RegistryDelete { key, value } => registry::delete_value_with_backup(key, value),
+        Change::ServiceStartType { name, start_type, delayed_auto } => {
+            services::set_service_startup(name, *start_type, *delayed_auto).map(Some)
+        }
+        Change::ServiceRunning { name, running } => {
+            if *running {
+                services::start_service(name).map(Some)
+            } else {
+                services::stop_service(name).map(Some)
+            }
+        }
+        Change::PowerSchemeCopy { source, destination } => power::copy_scheme(source, destination),
+        Change::ActivePowerScheme { scheme } => power::set_active_scheme(scheme).map(Some),
+        Change::PowerSetting { scheme, subgroup, setting, ac, dc } => {
+            power::set_power_setting(scheme, subgroup, setting, *ac, *dc).map(Some)
+        }
+    }
+}
+
+/// Reads the live system state and reports whether the change is in effect.
+#[cfg(target_os = "windows")]
+pub fn is_applied(change: &Change) -> Result<bool> {
+    use crate::utils::registry as reg_utils;
+    use crate::utils::services as svc_utils;
+    use crate::utils::powercfg;
+
+    match change {
+        // A missing value is not applied yet, rather than unreadable
+        Change::RegistryDword { key, value, data } => match reg_utils::read_dword(key, value) {
+            Ok(current) => Ok(current == *data),
+            Err(e) if e.is_not_found() => Ok(false),
+            Err(e) => Err(e),
+        },
+        Change::RegistryDelete { key, value } => match reg_utils::read_value(key, value) {
+            Ok(_) => Ok(false),
+            Err(e) if e.is_not_found() => Ok(true),
+            Err(e) => Err(e),
+        },
+        // A service that is not installed is as good as disabled and
+        // stopped; for anything else it is not found rather than not applied
+        Change::ServiceStartType { name, start_type, delayed_auto } => match svc_utils::query(name)? {
+            Some(c) => Ok(c.start_type == *start_type
+                && (*start_type != svc_utils::StartType::Automatic || c.delayed_auto == *delayed_auto)),
+            None if *start_type == svc_utils::StartType::Disabled => Ok(true),
+            None => Err(Error::not_found(format!("service {}", name))),
+        },
+        Change::ServiceRunning { name, running } => match svc_utils::query(name)? {
+            Some(c) => Ok(c.state.is_running() == *running),
+            None if !*running => Ok(true),
+            None => Err(Error::not_found(format!("service {}", name))),
+        },
+        Change::PowerSchemeCopy { destination, .. } => {
+            Ok(powercfg::list_schemes()?.iter().any(|s| s.guid.eq_ignore_ascii_case(destination)))
+        }
+        Change::ActivePowerScheme { scheme } => {
+            Ok(powercfg::active_scheme()?.guid.eq_ignore_ascii_case(scheme))
+        }
+        Change::PowerSetting { scheme, subgroup, setting, ac, dc } => {
+            let details = powercfg::query_scheme(scheme)?;
+            let current = details
+                .setting(subgroup, setting)
+                .ok_or_else(|| Error::not_found(format!("power setting {}", setting)))?;
+            Ok(ac.is_none_or(|v| current.ac == Some(v)) && dc.is_none_or(|v| current.dc == Some(v)))
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn is_applied(_change: &Change) -> Result<bool> {
+    Err(Error::unsupported("Fix verification"))
+}
+
+/// Applies a plan step by step in its dependency order and returns the
+/// outcome per issue. Once a change fails, the rest of that issue's changes
+/// are skipped; steps shared with another issue still run for it.
+pub fn apply_plan(plan: &Plan, issues: &[&Issue]) -> Vec<(u32, anyhow::Result<()>)> {
+    let mut failed: BTreeMap<u32, Error> = BTreeMap::new();
+
+    for step in &plan.steps {
+        if step.issues.iter().all(|n| failed.contains_key(n)) {
+            continue;
+        }
+        if let Err(e) = apply_change(&step.change) {
+            for &num in &step.issues {
+                failed.entry(num).or_insert_with(|| e.clone());
+            }
+        }
+    }
+
+    issues
+        .iter()
+        .map(|issue| {
+            let result = match failed.remove(&issue.num) {
+                Some(e) => Err(e.into()),
+                None if issue.reboot => reboot::record(issue),
+                None => Ok(()),
+            };
+            (issue.num, result)
+        })
+        .collect()
+}
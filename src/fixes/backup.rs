@@ -1,4 +1,6 @@
-use anyhow::Result;
+use crate::utils::registry::RegPath;
+use crate::utils::services::{ServiceConfig, StartType};
+use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -7,53 +9,197 @@ use chrono::Local;
 #[derive(Debug, Serialize, Deserialize)]
 struct RegistryBackup {
     timestamp: String,
-    subkey: String,
+    #[serde(flatten)]
+    key: RegPath,
     value: String,
     data: u32,
 }
 
-pub fn backup_registry_value(subkey: &str, value: &str, data: u32) -> Result<()> {
+/// A value that did not exist before a fix created it.
+#[derive(Debug, Serialize, Deserialize)]
+struct AbsentRegistryBackup {
+    timestamp: String,
+    #[serde(flatten)]
+    key: RegPath,
+    absent_value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ServiceBackup {
+    timestamp: String,
+    service: String,
+    start_type: StartType,
+    delayed_auto: bool,
+    running: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PowerSettingBackup {
+    timestamp: String,
+    scheme: String,
+    subgroup: String,
+    setting: String,
+    ac: u32,
+    dc: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ActiveSchemeBackup {
+    timestamp: String,
+    active_scheme: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CreatedSchemeBackup {
+    timestamp: String,
+    created_scheme: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Backup {
+    Registry(RegistryBackup),
+    AbsentRegistry(AbsentRegistryBackup),
+    Service(ServiceBackup),
+    PowerSetting(PowerSettingBackup),
+    ActiveScheme(ActiveSchemeBackup),
+    CreatedScheme(CreatedSchemeBackup),
+}
+
+fn timestamp() -> String {
+    Local::now().format("%Y%m%d_%H%M%S").to_string()
+}
+
+fn write_backup(name: &str, backup: &Backup) -> Result<PathBuf> {
     let backup_dir = PathBuf::from("backups");
-    fs::create_dir_all(&backup_dir)?;
-    
-    let backup = RegistryBackup {
-        timestamp: Local::now().format("%Y%m%d_%H%M%S").to_string(),
-        subkey: subkey.to_string(),
-        value: value.to_string(),
-        data,
-    };
+    fs::create_dir_all(&backup_dir).map_err(|e| Error::io(e, backup_dir.display().to_string()))?;
     
-    let filename = format!("backup_{}_{}.json", 
-        backup.timestamp,
-        value.replace('\\', "_")
+    let stem = format!("backup_{}_{}", 
+        timestamp(),
+        name.replace('\\', "_")
     );
     
-    let path = backup_dir.join(filename);
-    let json = serde_json::to_string_pretty(&backup)?;
-    fs::write(path, json)?;
+    // Several changes to the same item within a second must not overwrite
+    // each other's backup
+    let mut path = backup_dir.join(format!("{}.json", stem));
+    let mut counter = 1;
+    while path.exists() {
+        counter += 1;
+        path = backup_dir.join(format!("{}_{}.json", stem, counter));
+    }
     
-    Ok(())
+    let json = serde_json::to_string_pretty(backup).map_err(|e| Error::invalid(name, e))?;
+    fs::write(&path, json).map_err(|e| Error::io(e, path.display().to_string()))?;
+    
+    Ok(path)
+}
+
+#[cfg(target_os = "windows")]
+pub fn backup_registry_value(key: &RegPath, value: &str, data: u32) -> Result<PathBuf> {
+    write_backup(value, &Backup::Registry(RegistryBackup {
+        timestamp: timestamp(),
+        key: key.clone(),
+        value: value.to_string(),
+        data,
+    }))
+}
+
+/// Records that a value did not exist, so rolling back deletes it again.
+#[cfg(target_os = "windows")]
+pub fn backup_absent_registry_value(key: &RegPath, value: &str) -> Result<PathBuf> {
+    write_backup(value, &Backup::AbsentRegistry(AbsentRegistryBackup {
+        timestamp: timestamp(),
+        key: key.clone(),
+        absent_value: value.to_string(),
+    }))
+}
+
+/// Records the start type, delayed-auto flag and running state of a service
+/// before a fix touches it.
+pub fn backup_service(name: &str, config: &ServiceConfig) -> Result<PathBuf> {
+    write_backup(name, &Backup::Service(ServiceBackup {
+        timestamp: timestamp(),
+        service: name.to_string(),
+        start_type: config.start_type,
+        delayed_auto: config.delayed_auto,
+        running: config.state.is_running(),
+    }))
+}
+
+pub fn backup_power_setting(scheme: &str, subgroup: &str, setting: &str, ac: u32, dc: u32) -> Result<PathBuf> {
+    write_backup(setting, &Backup::PowerSetting(PowerSettingBackup {
+        timestamp: timestamp(),
+        scheme: scheme.to_string(),
+        subgroup: subgroup.to_string(),
+        setting: setting.to_string(),
+        ac,
+        dc,
+    }))
+}
+
+pub fn backup_active_scheme(scheme: &str) -> Result<PathBuf> {
+    write_backup("active_scheme", &Backup::ActiveScheme(ActiveSchemeBackup {
+        timestamp: timestamp(),
+        active_scheme: scheme.to_string(),
+    }))
+}
+
+/// Records a scheme APEX created, so rolling back deletes it again.
+pub fn backup_created_scheme(scheme: &str) -> Result<PathBuf> {
+    write_backup(scheme, &Backup::CreatedScheme(CreatedSchemeBackup {
+        timestamp: timestamp(),
+        created_scheme: scheme.to_string(),
+    }))
 }
 
 #[cfg(target_os = "windows")]
 pub fn rollback_from_file(backup_file: &str) -> Result<()> {
-    use windows::Win32::System::Registry::*;
+    use crate::utils::{powercfg, services};
     
-    let json = fs::read_to_string(backup_file)?;
-    let backup: RegistryBackup = serde_json::from_str(&json)?;
+    let json = fs::read_to_string(backup_file).map_err(|e| Error::io(e, backup_file))?;
+    let backup: Backup = serde_json::from_str(&json).map_err(|e| Error::invalid(backup_file, e))?;
     
-    // Restore registry value
-    crate::utils::registry::write_dword(
-        HKEY_LOCAL_MACHINE,
-        &backup.subkey,
-        &backup.value,
-        backup.data,
-    )?;
+    match backup {
+        Backup::Registry(backup) => {
+            // Restore registry value
+            crate::utils::registry::write_dword(&backup.key, &backup.value, backup.data)?;
+        }
+        Backup::AbsentRegistry(backup) => {
+            crate::utils::registry::delete_value(&backup.key, &backup.absent_value)?;
+        }
+        Backup::Service(backup) => {
+            // Restore the start type first so a disabled service can start again
+            services::set_start_type(&backup.service, backup.start_type, backup.delayed_auto)?;
+            if backup.running {
+                services::start(&backup.service)?;
+            } else {
+                services::stop(&backup.service)?;
+            }
+        }
+        Backup::PowerSetting(backup) => {
+            powercfg::set_value(&backup.scheme, &backup.subgroup, &backup.setting, backup.ac, backup.dc)?;
+            if powercfg::active_scheme()?.guid == backup.scheme {
+                powercfg::set_active(&backup.scheme)?;
+            }
+        }
+        Backup::ActiveScheme(backup) => {
+            powercfg::set_active(&backup.active_scheme)?;
+        }
+        Backup::CreatedScheme(backup) => {
+            if powercfg::active_scheme()?.guid == backup.created_scheme {
+                return Err(Error::invalid(
+                    format!("power scheme {}", backup.created_scheme),
+                    "still active; roll back the active scheme first",
+                ));
+            }
+            powercfg::delete_scheme(&backup.created_scheme)?;
+        }
+    }
     
     Ok(())
 }
 
 #[cfg(not(target_os = "windows"))]
 pub fn rollback_from_file(_backup_file: &str) -> Result<()> {
-    Err(anyhow::anyhow!("Rollback only supported on Windows"))
+    Err(Error::unsupported("Rollback"))
 }
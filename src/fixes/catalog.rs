@@ -0,0 +1,324 @@
+use crate::checks::services::SERVICE_RULES;
+use crate::checks::tweaks::TWEAK_SIGNATURES;
+use crate::types::{Change, CheckResult, CheckStatus, Issue, Safety, Severity};
+use crate::utils::powercfg;
+use crate::utils::registry::{Hive, RegPath};
+use crate::utils::services::StartType;
+
+/// Remediation for a check, raised as an issue whenever that check reports
+/// Warn or Bad.
+pub struct FixDefinition {
+    pub check: String,
+    pub category: String,
+    pub problem: String,
+    pub solution: String,
+    pub severity: Severity,
+    pub safety: Safety,
+    pub reboot: bool,
+    pub changes: Vec<Change>,
+}
+
+fn hklm_dword(subkey: &str, value: &str, data: u32) -> Change {
+    Change::RegistryDword {
+        key: RegPath::new(Hive::LocalMachine, subkey),
+        value: value.to_string(),
+        data,
+    }
+}
+
+fn hkcu_dword(subkey: &str, value: &str, data: u32) -> Change {
+    Change::RegistryDword {
+        key: RegPath::new(Hive::CurrentUser, subkey),
+        value: value.to_string(),
+        data,
+    }
+}
+
+fn processor_setting(setting: &str, ac: u32) -> Change {
+    Change::PowerSetting {
+        scheme: "SCHEME_CURRENT".to_string(),
+        subgroup: powercfg::SUB_PROCESSOR.to_string(),
+        setting: setting.to_string(),
+        ac: Some(ac),
+        dc: None,
+    }
+}
+
+fn service_definitions() -> impl Iterator<Item = FixDefinition> {
+    SERVICE_RULES.iter().filter_map(|rule| {
+        let start_type = rule.recommended?;
+
+        let mut changes = Vec::new();
+        if start_type == StartType::Disabled {
+            // Stop first: a disabled service keeps running until it is stopped
+            changes.push(Change::ServiceRunning {
+                name: rule.service.to_string(),
+                running: false,
+            });
+        }
+        changes.push(Change::ServiceStartType {
+            name: rule.service.to_string(),
+            start_type,
+            delayed_auto: false,
+        });
+
+        Some(FixDefinition {
+            check: rule.display.into(),
+            category: "Services".into(),
+            problem: format!("{} service is enabled beyond {:?}", rule.display, start_type),
+            solution: format!("Set {} to {:?}", rule.service, start_type),
+            severity: Severity::Low,
+            safety: rule.safety,
+            reboot: false,
+            changes,
+        })
+    })
+}
+
+/// Reverting a third-party tweak puts every value it is known to change back
+/// to the Windows default. Deleting a `Policies` value can also undo what an
+/// administrator or MDM set on purpose, so those reverts are Moderate and
+/// name the values they remove.
+fn tweak_definitions() -> impl Iterator<Item = FixDefinition> {
+    TWEAK_SIGNATURES.iter().map(|signature| {
+        let changes: Vec<Change> = signature.settings.iter().map(|s| s.revert()).collect();
+        let removed_policies: Vec<String> = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::RegistryDelete { key, value }
+                    if key.subkey.split('\\').any(|part| part.eq_ignore_ascii_case("Policies")) =>
+                {
+                    Some(key.value_path(value))
+                }
+                _ => None,
+            })
+            .collect();
+        let solution = if removed_policies.is_empty() {
+            "Restore the Windows defaults".to_string()
+        } else {
+            format!("Restore the Windows defaults; deletes the policy value {}", removed_policies.join(", "))
+        };
+
+        FixDefinition {
+            check: signature.name.into(),
+            category: "Tweaks".into(),
+            problem: format!("{} (third-party tweak)", signature.name),
+            solution,
+            severity: signature.severity,
+            safety: if removed_policies.is_empty() { Safety::Safe } else { Safety::Moderate },
+            reboot: signature.reboot,
+            changes,
+        }
+    })
+}
+
+pub fn definitions() -> Vec<FixDefinition> {
+    let mut definitions = vec![
+        FixDefinition {
+            check: "HPET Status".into(),
+            category: "Latency".into(),
+            problem: "High Precision Event Timer is in use".into(),
+            solution: "Disable HPET as the platform clock source".into(),
+            severity: Severity::Medium,
+            safety: Safety::Moderate,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SYSTEM\\CurrentControlSet\\Control\\TimeProviders\\TimerResolution",
+                "HPETDisabled",
+                1,
+            )],
+        },
+        FixDefinition {
+            check: "TSC Sync Policy".into(),
+            category: "Latency".into(),
+            problem: "Platform clock is forced instead of the TSC".into(),
+            solution: "Stop forcing the platform clock".into(),
+            severity: Severity::Medium,
+            safety: Safety::Moderate,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\kernel",
+                "UsePlatformClock",
+                0,
+            )],
+        },
+        FixDefinition {
+            check: "Dynamic Tick".into(),
+            category: "Latency".into(),
+            problem: "Dynamic tick is enabled".into(),
+            solution: "Disable dynamic tick".into(),
+            severity: Severity::Low,
+            safety: Safety::Moderate,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\kernel",
+                "DisableDynamicTick",
+                1,
+            )],
+        },
+        FixDefinition {
+            check: "MMCSS System Responsiveness".into(),
+            category: "Latency".into(),
+            problem: "MMCSS reserves more than 10% CPU for background tasks".into(),
+            solution: "Set SystemResponsiveness to 10".into(),
+            severity: Severity::Medium,
+            safety: Safety::Safe,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Multimedia\\SystemProfile",
+                "SystemResponsiveness",
+                10,
+            )],
+        },
+        FixDefinition {
+            check: "Network Throttling".into(),
+            category: "Latency".into(),
+            problem: "Multimedia network throttling is enabled".into(),
+            solution: "Disable network throttling".into(),
+            severity: Severity::Low,
+            safety: Safety::Safe,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Multimedia\\SystemProfile",
+                "NetworkThrottlingIndex",
+                0xFFFFFFFF,
+            )],
+        },
+        FixDefinition {
+            check: "Timer Coalescing".into(),
+            category: "Latency".into(),
+            problem: "Kernel timer coalescing is enabled".into(),
+            solution: "Disable timer coalescing".into(),
+            severity: Severity::Low,
+            safety: Safety::Moderate,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\kernel",
+                "CoalescingTimerInterval",
+                0,
+            )],
+        },
+        FixDefinition {
+            check: "Power Plan".into(),
+            category: "CPU".into(),
+            problem: "Active power plan is not a high performance plan".into(),
+            solution: "Create and activate an Ultimate Performance plan".into(),
+            severity: Severity::Medium,
+            safety: Safety::Safe,
+            reboot: false,
+            changes: vec![
+                Change::PowerSchemeCopy {
+                    source: powercfg::SCHEME_ULTIMATE_PERFORMANCE.to_string(),
+                    destination: powercfg::SCHEME_APEX_ULTIMATE.to_string(),
+                },
+                Change::ActivePowerScheme {
+                    scheme: powercfg::SCHEME_APEX_ULTIMATE.to_string(),
+                },
+            ],
+        },
+        FixDefinition {
+            check: "Core Parking".into(),
+            category: "CPU".into(),
+            problem: "CPU cores are parked on AC power".into(),
+            solution: "Keep 100% of cores unparked on AC power".into(),
+            severity: Severity::Medium,
+            safety: Safety::Safe,
+            reboot: false,
+            changes: vec![processor_setting(powercfg::SETTING_CORE_PARKING_MIN, 100)],
+        },
+        FixDefinition {
+            check: "Turbo Boost".into(),
+            category: "CPU".into(),
+            problem: "Processor boost is disabled on AC power".into(),
+            solution: "Enable processor boost on AC power".into(),
+            severity: Severity::High,
+            safety: Safety::Safe,
+            reboot: false,
+            changes: vec![processor_setting(powercfg::SETTING_BOOST_MODE, 1)],
+        },
+        FixDefinition {
+            check: "Maximum Processor State".into(),
+            category: "CPU".into(),
+            problem: "Maximum processor state is capped below 100% on AC power".into(),
+            solution: "Allow 100% maximum processor state on AC power".into(),
+            severity: Severity::High,
+            safety: Safety::Safe,
+            reboot: false,
+            changes: vec![processor_setting(powercfg::SETTING_THROTTLE_MAX, 100)],
+        },
+        FixDefinition {
+            check: "Virtualization Based Security".into(),
+            category: "CPU".into(),
+            problem: "Virtualization Based Security is enabled".into(),
+            solution: "Disable VBS (reduces system security)".into(),
+            severity: Severity::High,
+            safety: Safety::Risky,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SYSTEM\\CurrentControlSet\\Control\\DeviceGuard",
+                "EnableVirtualizationBasedSecurity",
+                0,
+            )],
+        },
+        FixDefinition {
+            check: "Hypervisor-Enforced Code Integrity".into(),
+            category: "CPU".into(),
+            problem: "Memory integrity (HVCI) is enabled".into(),
+            solution: "Disable HVCI (reduces system security)".into(),
+            severity: Severity::High,
+            safety: Safety::Risky,
+            reboot: true,
+            changes: vec![hklm_dword(
+                "SYSTEM\\CurrentControlSet\\Control\\DeviceGuard\\Scenarios\\HypervisorEnforcedCodeIntegrity",
+                "Enabled",
+                0,
+            )],
+        },
+        FixDefinition {
+            check: "Game DVR".into(),
+            category: "GPU".into(),
+            problem: "Game DVR background recording is enabled".into(),
+            solution: "Disable Game DVR".into(),
+            severity: Severity::Medium,
+            safety: Safety::Safe,
+            reboot: false,
+            changes: vec![hkcu_dword("System\\GameConfigStore", "GameDVR_Enabled", 0)],
+        },
+    ];
+
+    definitions.extend(service_definitions());
+    definitions.extend(tweak_definitions());
+    definitions
+}
+
+/// Turns failing checks into numbered issues for every check the catalog
+/// knows how to fix.
+pub fn issues_for(checks: &[CheckResult]) -> Vec<Issue> {
+    let definitions = definitions();
+    let mut issues = Vec::new();
+
+    for check in checks {
+        if !matches!(check.status, CheckStatus::Warn | CheckStatus::Bad) {
+            continue;
+        }
+
+        if let Some(def) = definitions
+            .iter()
+            .find(|d| d.check == check.name && d.category == check.category)
+        {
+            issues.push(Issue {
+                num: issues.len() as u32 + 1,
+                check: check.name.clone(),
+                category: check.category.clone(),
+                problem: def.problem.clone(),
+                solution: def.solution.clone(),
+                severity: def.severity,
+                safety: def.safety,
+                reboot: def.reboot,
+                changes: def.changes.clone(),
+            });
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,93 @@
+use crate::fixes::apply;
+use crate::fixes::plan::Plan;
+use crate::types::{Change, Issue};
+use crate::error::{Error, ErrorKind, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Settings a machine should keep, as read from a `--state` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DesiredState {
+    pub changes: Vec<Change>,
+}
+
+/// How one entry of the desired state compares to the machine.
+#[derive(Debug)]
+pub enum Drift {
+    InSync,
+    Drifted,
+    /// The service the entry names is not installed; there is nothing to
+    /// enforce.
+    Missing,
+    Unreadable(Error),
+}
+
+/// What [`restore`] did with one change.
+#[derive(Debug)]
+pub enum Restored {
+    Applied,
+    /// The setting already held the wanted value; nothing was written or
+    /// backed up.
+    InSync,
+    /// The service the change names is not installed.
+    Missing,
+    Failed(Error),
+}
+
+pub fn load_state(path: &str) -> Result<DesiredState> {
+    let content = fs::read_to_string(path).map_err(|e| Error::io(e, path))?;
+    serde_json::from_str(&content).map_err(|e| Error::invalid(path, e))
+}
+
+/// Writes the changes of the given issues as a state file, in plan order.
+pub fn save_state(issues: &[&Issue], path: &str) -> Result<()> {
+    let state = DesiredState {
+        changes: Plan::new(issues).steps.into_iter().map(|s| s.change).collect(),
+    };
+    let json = serde_json::to_string_pretty(&state).map_err(|e| Error::invalid(path, e))?;
+    fs::write(path, json).map_err(|e| Error::io(e, path))
+}
+
+/// Compares every change of the state against the live system.
+pub fn check(state: &DesiredState) -> Vec<(&Change, Drift)> {
+    state
+        .changes
+        .iter()
+        .map(|change| {
+            let drift = match apply::is_applied(change) {
+                Ok(true) => Drift::InSync,
+                Ok(false) => Drift::Drifted,
+                Err(e) if missing_service(change, &e) => Drift::Missing,
+                Err(e) => Drift::Unreadable(e),
+            };
+            (change, drift)
+        })
+        .collect()
+}
+
+fn missing_service(change: &Change, error: &Error) -> bool {
+    matches!(change, Change::ServiceStartType { .. } | Change::ServiceRunning { .. }) && error.kind() == ErrorKind::NotFound
+}
+
+/// Re-applies drifted changes through the backed-up fix path, in dependency
+/// order. Each change is read again first and only written when it still
+/// differs, so settings that caught up in the meantime get no backup.
+pub fn restore(drifted: &[Change]) -> Vec<(Change, Restored)> {
+    Plan::from_changes(drifted)
+        .steps
+        .into_iter()
+        .map(|step| {
+            let restored = match apply::is_applied(&step.change) {
+                Ok(true) => Restored::InSync,
+                Err(e) if missing_service(&step.change, &e) => Restored::Missing,
+                // Unreadable settings are still written; the fix path reports
+                // its own error when that fails too
+                Ok(false) | Err(_) => match apply::apply_change(&step.change) {
+                    Ok(_) => Restored::Applied,
+                    Err(e) => Restored::Failed(e),
+                },
+            };
+            (step.change, restored)
+        })
+        .collect()
+}
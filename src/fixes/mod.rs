@@ -2,3 +2,9 @@ pub mod registry;
 pub mod services;
 pub mod power;
 pub mod backup;
+pub mod catalog;
+pub mod apply;
+pub mod reboot;
+pub mod plan;
+pub mod picker;
+pub mod enforce;
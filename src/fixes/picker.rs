@@ -0,0 +1,201 @@
+use crate::fixes::plan::Plan;
+use crate::types::{Issue, Safety};
+use anyhow::Result;
+use colored::*;
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Whether the picker can talk to a user; otherwise callers fall back to
+/// listing the issues.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Safety tier colored by how much care it needs.
+pub fn safety_label(safety: Safety) -> ColoredString {
+    match safety {
+        Safety::Safe => safety.label().green(),
+        Safety::Moderate => safety.label().yellow(),
+        Safety::Risky => safety.label().red(),
+    }
+}
+
+fn print_issues(issues: &[Issue], selected: &BTreeSet<u32>, allowed: Safety) {
+    let mut categories: Vec<&str> = Vec::new();
+    for issue in issues {
+        if !categories.contains(&issue.category.as_str()) {
+            categories.push(&issue.category);
+        }
+    }
+
+    for category in categories {
+        println!(" {}", category.bright_cyan().bold());
+        for issue in issues.iter().filter(|i| i.category == category) {
+            let mark = if selected.contains(&issue.num) { "[x]".green() } else { "[ ]".normal() };
+            let mut markers = vec![safety_label(issue.safety).to_string()];
+            if let Some(flag) = issue.safety.flag().filter(|_| issue.safety > allowed) {
+                markers.push(format!("needs {}", flag).dimmed().to_string());
+            }
+            if issue.reboot {
+                markers.push("⟳ reboot".yellow().to_string());
+            }
+            println!("   {} {:>3}  {:<8} {} {}",
+                mark,
+                issue.num,
+                format!("{:?}", issue.severity),
+                issue.problem,
+                markers.join(" ")
+            );
+        }
+    }
+    println!();
+}
+
+/// Prints the issues for a non-interactive session, with the flags that
+/// select them.
+pub fn print_list(issues: &[Issue], allowed: Safety) {
+    print_issues(issues, &BTreeSet::new(), allowed);
+    println!("Select fixes with --issue <N> or --all.");
+}
+
+fn parse_selection(input: &str, issues: &[Issue]) -> Option<Vec<u32>> {
+    let mut nums = Vec::new();
+
+    for token in input.split(|c: char| c == ',' || c.is_whitespace()).filter(|t| !t.is_empty()) {
+        let (start, end) = match token.split_once('-') {
+            Some((a, b)) => (a.trim().parse::<u32>().ok()?, b.trim().parse::<u32>().ok()?),
+            None => {
+                let n = token.parse::<u32>().ok()?;
+                (n, n)
+            }
+        };
+        for n in start..=end {
+            if !issues.iter().any(|i| i.num == n) {
+                return None;
+            }
+            nums.push(n);
+        }
+    }
+
+    Some(nums)
+}
+
+fn prompt(text: &str) -> Result<Option<String>> {
+    print!("{}", text);
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Asks a yes/no question; an empty answer picks `default`.
+pub fn confirm(question: &str, default: bool) -> Result<bool> {
+    Ok(match prompt(question)? {
+        Some(answer) if answer.is_empty() => default,
+        Some(answer) => answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"),
+        None => false,
+    })
+}
+
+/// Lets the user toggle issues until they confirm the change plan. Returns
+/// the selected issue numbers, or `None` if the user quit. Issues above the
+/// `allowed` tier are listed but cannot be selected.
+pub fn pick(issues: &[Issue], allowed: Safety) -> Result<Option<Vec<u32>>> {
+    let mut selected: BTreeSet<u32> = BTreeSet::new();
+
+    loop {
+        println!("{}", "Select fixes to apply:".bright_white().bold());
+        print_issues(issues, &selected, allowed);
+        println!("{}", "Numbers/ranges toggle (e.g. 1 3 5-7), a = all, s = safe only, n = none,".dimmed());
+        println!("{}", "p = show plan, y = apply selection, q = quit".dimmed());
+
+        let Some(input) = prompt("> ")? else {
+            return Ok(None);
+        };
+
+        match input.to_lowercase().as_str() {
+            "q" => return Ok(None),
+            "a" => selected = issues.iter().filter(|i| i.safety <= allowed).map(|i| i.num).collect(),
+            "s" => selected = issues.iter().filter(|i| i.safety == Safety::Safe).map(|i| i.num).collect(),
+            "n" => selected.clear(),
+            "p" => {
+                let chosen: Vec<&Issue> = issues.iter().filter(|i| selected.contains(&i.num)).collect();
+                Plan::new(&chosen).print();
+            }
+            "y" => {
+                if selected.is_empty() {
+                    println!("{}", "Nothing selected".bright_yellow());
+                    continue;
+                }
+
+                let chosen: Vec<&Issue> = issues.iter().filter(|i| selected.contains(&i.num)).collect();
+                let plan = Plan::new(&chosen);
+                plan.print();
+                if !plan.conflicts.is_empty() {
+                    println!("{}", "Deselect one fix of each conflicting pair first".bright_yellow());
+                    println!();
+                    continue;
+                }
+
+                match prompt(&format!("Apply {} fix(es)? [y/N] ", chosen.len()))? {
+                    Some(answer) if answer.eq_ignore_ascii_case("y") => {
+                        return Ok(Some(selected.into_iter().collect()));
+                    }
+                    Some(_) => continue,
+                    None => return Ok(None),
+                }
+            }
+            "" => {}
+            other => match parse_selection(other, issues) {
+                Some(nums) => {
+                    for n in nums {
+                        let issue = issues.iter().find(|i| i.num == n).expect("parsed issue exists");
+                        if let Some(flag) = issue.safety.flag().filter(|_| issue.safety > allowed) {
+                            println!("{}", format!("#{} is {}; rerun with {} to select it", n, issue.safety.label(), flag).bright_yellow());
+                            continue;
+                        }
+                        if !selected.remove(&n) {
+                            selected.insert(n);
+                        }
+                    }
+                }
+                None => println!("{}", format!("Unknown issue or command: {}", other).bright_yellow()),
+            },
+        }
+        println!();
+    }
+}
+
+/// Asks which fix to keep for each conflict in the plan of `nums`, removing
+/// the other one. Returns `false` if the user quit.
+pub fn resolve_conflicts(issues: &[Issue], nums: &mut Vec<u32>) -> Result<bool> {
+    loop {
+        let chosen: Vec<&Issue> = issues.iter().filter(|i| nums.contains(&i.num)).collect();
+        let plan = Plan::new(&chosen);
+        let Some(conflict) = plan.conflicts.first() else {
+            return Ok(true);
+        };
+
+        let first = conflict.first.issues[0];
+        let second = conflict.second.issues[0];
+        let text = format!("{} is changed by #{} and #{}. Keep which fix? [{}/{}, q = quit] ",
+            conflict.target, first, second, first, second);
+
+        let Some(answer) = prompt(&text)? else {
+            return Ok(false);
+        };
+        let drop: Vec<u32> = if answer == first.to_string() {
+            conflict.second.issues.clone()
+        } else if answer == second.to_string() {
+            conflict.first.issues.clone()
+        } else if answer.eq_ignore_ascii_case("q") {
+            return Ok(false);
+        } else {
+            continue;
+        };
+        nums.retain(|n| !drop.contains(n));
+    }
+}
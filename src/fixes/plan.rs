@@ -0,0 +1,169 @@
+use crate::types::{Change, Issue};
+use colored::*;
+
+/// A change scheduled by a plan, remembering which issues asked for it.
+#[derive(Debug, Clone)]
+pub struct PlannedChange {
+    pub issues: Vec<u32>,
+    pub change: Change,
+}
+
+/// Two selected fixes that want different results for the same setting.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub target: String,
+    pub first: PlannedChange,
+    pub second: PlannedChange,
+}
+
+/// Everything a set of selected fixes is going to change, in the order it
+/// has to be applied.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub steps: Vec<PlannedChange>,
+    pub conflicts: Vec<Conflict>,
+    pub reboot: bool,
+}
+
+/// Whether two changes to the same target can both be applied. Power
+/// settings only clash when they set the same side to different values.
+fn compatible(a: &Change, b: &Change) -> bool {
+    match (a, b) {
+        (Change::PowerSetting { ac: ac_a, dc: dc_a, .. }, Change::PowerSetting { ac: ac_b, dc: dc_b, .. }) => {
+            let agrees = |x: &Option<u32>, y: &Option<u32>| x.is_none() || y.is_none() || x == y;
+            agrees(ac_a, ac_b) && agrees(dc_a, dc_b)
+        }
+        _ => a == b,
+    }
+}
+
+/// Position of a change in the plan. A service is stopped before its start
+/// type changes and only started afterwards, since a disabled service cannot
+/// start; a power scheme is created and activated before any setting is
+/// written to the current scheme.
+fn phase(change: &Change) -> u8 {
+    match change {
+        Change::ServiceRunning { running: false, .. } | Change::PowerSchemeCopy { .. } => 0,
+        Change::ServiceStartType { .. } | Change::ActivePowerScheme { .. } => 1,
+        Change::RegistryDword { .. } | Change::RegistryDelete { .. } | Change::PowerSetting { .. } => 2,
+        Change::ServiceRunning { running: true, .. } => 3,
+    }
+}
+
+impl Plan {
+    pub fn new(issues: &[&Issue]) -> Self {
+        let mut plan = Plan::default();
+
+        for issue in issues {
+            plan.reboot |= issue.reboot;
+            for change in &issue.changes {
+                plan.add(issue.num, change);
+            }
+        }
+
+        plan.order();
+        plan
+    }
+
+    /// Plans loose changes, such as the entries of a desired state file.
+    /// Each change is numbered by its 1-based position in place of an issue.
+    pub fn from_changes(changes: &[Change]) -> Self {
+        let mut plan = Plan::default();
+
+        for (i, change) in changes.iter().enumerate() {
+            plan.add(i as u32 + 1, change);
+        }
+
+        plan.order();
+        plan
+    }
+
+    /// Merges a change into an identical step, or checks it against every
+    /// step with the same target before scheduling it.
+    fn add(&mut self, num: u32, change: &Change) {
+        let target = change.target();
+        let same_target = |step: &PlannedChange| step.change.target().eq_ignore_ascii_case(&target);
+
+        if let Some(step) = self.steps.iter_mut().find(|s| same_target(s) && s.change == *change) {
+            if !step.issues.contains(&num) {
+                step.issues.push(num);
+            }
+            return;
+        }
+
+        let planned = PlannedChange { issues: vec![num], change: change.clone() };
+        match self.steps.iter().find(|s| same_target(s) && !compatible(&s.change, change)) {
+            Some(step) => self.conflicts.push(Conflict { target, first: step.clone(), second: planned }),
+            None => self.steps.push(planned),
+        }
+    }
+
+    fn order(&mut self) {
+        // Stable, so independent changes keep the order the fixes listed them in
+        self.steps.sort_by_key(|s| phase(&s.change));
+    }
+
+    pub fn print_conflicts(&self) {
+        if self.conflicts.is_empty() {
+            return;
+        }
+
+        println!("{}", format!("⚠️  {} conflicting change(s):", self.conflicts.len()).bright_red());
+        for conflict in &self.conflicts {
+            println!("   {}", conflict.target.bright_white());
+            for side in [&conflict.first, &conflict.second] {
+                println!("      {} {}", issue_list(&side.issues).bright_white(), side.change.describe());
+            }
+        }
+        println!();
+    }
+
+    pub fn print(&self) {
+        println!("{}", format!("📝 Change plan ({} change(s)):", self.steps.len()).bright_cyan());
+        for (i, step) in self.steps.iter().enumerate() {
+            println!("   {:>2}. {} {}", i + 1, issue_list(&step.issues).bright_white(), step.change.describe());
+        }
+        if self.reboot {
+            println!("   {}", "⟳ A reboot is required for some changes to take effect".bright_yellow());
+        }
+        println!();
+        self.print_conflicts();
+    }
+}
+
+fn issue_list(issues: &[u32]) -> String {
+    issues.iter().map(|n| format!("#{}", n)).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn power(ac: Option<u32>, dc: Option<u32>) -> Change {
+        Change::PowerSetting {
+            scheme: "SCHEME_CURRENT".to_string(),
+            subgroup: "SUB_PROCESSOR".to_string(),
+            setting: "PROCTHROTTLEMIN".to_string(),
+            ac,
+            dc,
+        }
+    }
+
+    #[test]
+    fn conflict_with_a_later_step() {
+        // The third change agrees with the first step but not the second
+        let plan = Plan::from_changes(&[power(Some(100), None), power(None, Some(5)), power(None, Some(50))]);
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].first.issues, [2]);
+        assert_eq!(plan.conflicts[0].second.issues, [3]);
+    }
+
+    #[test]
+    fn identical_changes_merge() {
+        let plan = Plan::from_changes(&[power(Some(100), None), power(None, Some(5)), power(None, Some(5))]);
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[1].issues, [2, 3]);
+        assert!(plan.conflicts.is_empty());
+    }
+}
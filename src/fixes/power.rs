@@ -1,18 +1,60 @@
-use anyhow::Result;
-use std::process::Command;
-
-pub fn set_power_setting(scheme: &str, subgroup: &str, setting: &str, value: &str) -> Result<()> {
-    Command::new("powercfg")
-        .args(["/setacvalueindex", scheme, subgroup, setting, value])
-        .output()?;
-    
-    Command::new("powercfg")
-        .args(["/setdcvalueindex", scheme, subgroup, setting, value])
-        .output()?;
-    
-    Command::new("powercfg")
-        .args(["/setactive", scheme])
-        .output()?;
-    
-    Ok(())
+use crate::error::{Error, Result};
+use crate::fixes::backup;
+use crate::utils::powercfg;
+use std::path::PathBuf;
+
+/// Resolves aliases such as `SCHEME_CURRENT` so backups name the scheme that
+/// was actually changed.
+fn resolve_scheme(scheme: &str) -> Result<String> {
+    if scheme.eq_ignore_ascii_case("SCHEME_CURRENT") {
+        Ok(powercfg::active_scheme()?.guid)
+    } else {
+        Ok(scheme.to_lowercase())
+    }
+}
+
+pub fn set_power_setting(scheme: &str, subgroup: &str, setting: &str, ac: Option<u32>, dc: Option<u32>) -> Result<PathBuf> {
+    let scheme = resolve_scheme(scheme)?;
+    let details = powercfg::query_scheme(&scheme)?;
+    let current = details
+        .setting(subgroup, setting)
+        .ok_or_else(|| Error::not_found(format!("power setting {} in scheme {}", setting, scheme)))?;
+    let (old_ac, old_dc) = match (current.ac, current.dc) {
+        (Some(ac), Some(dc)) => (ac, dc),
+        _ => return Err(Error::invalid(format!("power setting {}", setting), "no current value")),
+    };
+
+    if let (Some(min), Some(max)) = (current.min, current.max) {
+        for value in [ac, dc].into_iter().flatten() {
+            if value < min || value > max {
+                return Err(Error::invalid(&current.name, format!("value {} out of range {}-{}", value, min, max)));
+            }
+        }
+    }
+
+    let backup = backup::backup_power_setting(&scheme, subgroup, setting, old_ac, old_dc)?;
+    powercfg::set_value(&scheme, subgroup, setting, ac.unwrap_or(old_ac), dc.unwrap_or(old_dc))?;
+
+    // Changes to the active scheme only apply once it is re-activated
+    if powercfg::active_scheme()?.guid == scheme {
+        powercfg::set_active(&scheme)?;
+    }
+
+    Ok(backup)
+}
+
+/// Returns the backup file, or `None` when the scheme already existed.
+pub fn copy_scheme(source: &str, destination: &str) -> Result<Option<PathBuf>> {
+    if powercfg::list_schemes()?.iter().any(|s| s.guid.eq_ignore_ascii_case(destination)) {
+        return Ok(None);
+    }
+
+    powercfg::duplicate_scheme(source, destination)?;
+    backup::backup_created_scheme(destination).map(Some)
+}
+
+pub fn set_active_scheme(scheme: &str) -> Result<PathBuf> {
+    let backup = backup::backup_active_scheme(&powercfg::active_scheme()?.guid)?;
+    powercfg::set_active(scheme)?;
+    Ok(backup)
 }
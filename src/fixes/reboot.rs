@@ -0,0 +1,139 @@
+use crate::fixes::apply;
+use crate::types::{Change, Issue};
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use sysinfo::System;
+
+/// A fix that was applied but only takes effect after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingFix {
+    pub check: String,
+    pub category: String,
+    pub problem: String,
+    pub changes: Vec<Change>,
+    /// Unix timestamp of when the fix was applied.
+    pub applied_at: u64,
+}
+
+impl PendingFix {
+    pub fn applied_at_display(&self) -> String {
+        Local
+            .timestamp_opt(self.applied_at as i64, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| self.applied_at.to_string())
+    }
+}
+
+/// A pending fix checked on the first audit after a reboot.
+#[derive(Debug)]
+pub struct VerifiedFix {
+    pub fix: PendingFix,
+    /// Changes that are not in effect after the reboot, e.g. because a group
+    /// policy or the firmware overrode them.
+    pub reverted: Vec<Change>,
+    /// Changes whose current state could not be read.
+    pub unreadable: Vec<Change>,
+}
+
+impl VerifiedFix {
+    pub fn stuck(&self) -> bool {
+        self.reverted.is_empty() && self.unreadable.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RebootStatus {
+    /// Fixes applied since the last boot, still waiting for a restart.
+    pub pending: Vec<PendingFix>,
+    /// Fixes verified now because the machine rebooted since they were applied.
+    pub verified: Vec<VerifiedFix>,
+}
+
+fn pending_path() -> PathBuf {
+    PathBuf::from("backups").join("pending_reboot.json")
+}
+
+fn load() -> Result<Vec<PendingFix>> {
+    let path = pending_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn save(fixes: &[PendingFix]) -> Result<()> {
+    let path = pending_path();
+    if fixes.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(fixes)?)?;
+    Ok(())
+}
+
+/// Remembers an applied fix so that the next audit after a reboot can confirm
+/// it took effect.
+pub fn record(issue: &Issue) -> Result<()> {
+    let mut fixes = load()?;
+
+    // Re-applying the same fix before rebooting replaces the older record.
+    fixes.retain(|f| !(f.check == issue.check && f.category == issue.category));
+    fixes.push(PendingFix {
+        check: issue.check.clone(),
+        category: issue.category.clone(),
+        problem: issue.problem.clone(),
+        changes: issue.changes.clone(),
+        applied_at: Local::now().timestamp() as u64,
+    });
+
+    save(&fixes)
+}
+
+/// Drops the record of a fix that was rolled back before the reboot, so it is
+/// not reported as reverted afterwards.
+pub fn forget(issue: &Issue) -> Result<()> {
+    let mut fixes = load()?;
+    fixes.retain(|f| !(f.check == issue.check && f.category == issue.category));
+    save(&fixes)
+}
+
+/// Splits recorded fixes into those still waiting for a reboot and those
+/// applied before the current boot, which are verified and then forgotten.
+pub fn check() -> Result<RebootStatus> {
+    let boot_time = System::boot_time();
+    let mut status = RebootStatus::default();
+
+    for fix in load()? {
+        if fix.applied_at >= boot_time {
+            status.pending.push(fix);
+            continue;
+        }
+
+        let mut reverted = Vec::new();
+        let mut unreadable = Vec::new();
+        for change in &fix.changes {
+            match apply::is_applied(change) {
+                Ok(true) => {}
+                Ok(false) => reverted.push(change.clone()),
+                Err(_) => unreadable.push(change.clone()),
+            }
+        }
+
+        status.verified.push(VerifiedFix { fix, reverted, unreadable });
+    }
+
+    save(&status.pending)?;
+    Ok(status)
+}
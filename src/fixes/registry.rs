@@ -1,24 +1,58 @@
-use anyhow::Result;
+use crate::error::{Error, Result};
+use crate::utils::registry::RegPath;
+use std::path::PathBuf;
 
 #[cfg(target_os = "windows")]
-use crate::utils::registry as reg_utils;
+use crate::utils::registry::{self as reg_utils, RegValue};
 #[cfg(target_os = "windows")]
 use crate::fixes::backup;
+
+/// Backups only hold DWORDs, so other types are left alone rather than
+/// replaced without a way back.
 #[cfg(target_os = "windows")]
-use windows::Win32::System::Registry::*;
+fn not_a_dword(key: &RegPath, value: &str, found: &RegValue) -> Error {
+    Error::WrongType { context: key.value_path(value), expected: "REG_DWORD", found: found.type_name() }
+}
 
+/// Returns the backup file. A value that did not exist is backed up as
+/// absent, so rolling back deletes it.
 #[cfg(target_os = "windows")]
-pub fn write_dword_with_backup(key: HKEY, subkey: &str, value: &str, data: u32) -> Result<()> {
+pub fn write_dword_with_backup(key: &RegPath, value: &str, data: u32) -> Result<Option<PathBuf>> {
     // Create backup
-    if let Ok(old_value) = reg_utils::read_dword(key, subkey, value) {
-        backup::backup_registry_value(subkey, value, old_value)?;
-    }
+    let backup = match reg_utils::read_value(key, value) {
+        Ok(RegValue::Dword(old_value)) => backup::backup_registry_value(key, value, old_value)?,
+        Ok(other) => return Err(not_a_dword(key, value, &other)),
+        Err(e) if e.is_not_found() => backup::backup_absent_registry_value(key, value)?,
+        Err(e) => return Err(e),
+    };
     
     // Write new value
-    reg_utils::write_dword(key, subkey, value, data)
+    reg_utils::write_dword(key, value, data)?;
+    Ok(Some(backup))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn write_dword_with_backup(_key: &RegPath, _value: &str, _data: u32) -> Result<Option<PathBuf>> {
+    Err(Error::unsupported("Registry access"))
+}
+
+/// Deletes a value so Windows falls back to its built-in default. Returns
+/// the backup file, or `None` when the value did not exist.
+#[cfg(target_os = "windows")]
+pub fn delete_value_with_backup(key: &RegPath, value: &str) -> Result<Option<PathBuf>> {
+    let old_value = match reg_utils::read_value(key, value) {
+        Ok(RegValue::Dword(old_value)) => old_value,
+        Ok(other) => return Err(not_a_dword(key, value, &other)),
+        Err(e) if e.is_not_found() => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    
+    let backup = backup::backup_registry_value(key, value, old_value)?;
+    reg_utils::delete_value(key, value)?;
+    Ok(Some(backup))
 }
 
 #[cfg(not(target_os = "windows"))]
-pub fn write_dword_with_backup(_key: usize, _subkey: &str, _value: &str, _data: u32) -> Result<()> {
-    Err(anyhow::anyhow!("Registry operations only supported on Windows"))
+pub fn delete_value_with_backup(_key: &RegPath, _value: &str) -> Result<Option<PathBuf>> {
+    Err(Error::unsupported("Registry access"))
 }
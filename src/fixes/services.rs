@@ -1,16 +1,29 @@
-use anyhow::Result;
+use crate::error::{Error, Result};
+use crate::fixes::backup;
+use crate::utils::services::{self, StartType};
+use std::path::PathBuf;
 
-pub fn start_service(_name: &str) -> Result<()> {
-    // Service management requires SCM API
-    Ok(())
+fn backup_service(name: &str) -> Result<PathBuf> {
+    match services::query(name)? {
+        Some(config) => backup::backup_service(name, &config),
+        None => Err(Error::not_found(format!("service {}", name))),
+    }
 }
 
-pub fn stop_service(_name: &str) -> Result<()> {
-    // Service management requires SCM API
-    Ok(())
+pub fn start_service(name: &str) -> Result<PathBuf> {
+    let backup = backup_service(name)?;
+    services::start(name)?;
+    Ok(backup)
 }
 
-pub fn set_service_startup(_name: &str, _startup_type: u32) -> Result<()> {
-    // Service management requires SCM API
-    Ok(())
+pub fn stop_service(name: &str) -> Result<PathBuf> {
+    let backup = backup_service(name)?;
+    services::stop(name)?;
+    Ok(backup)
+}
+
+pub fn set_service_startup(name: &str, start_type: StartType, delayed_auto: bool) -> Result<PathBuf> {
+    let backup = backup_service(name)?;
+    services::set_start_type(name, start_type, delayed_auto)?;
+    Ok(backup)
 }
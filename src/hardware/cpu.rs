@@ -1,27 +1,370 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
 use sysinfo::System;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::cpuid::{self, CacheKind, CacheLevel, Local};
+use super::microarch::{self, Capability, Microarch};
+pub use super::cpuid::CoreType;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CpuInfo {
     pub name: String,
     pub cores: usize,
     pub threads: usize,
     pub frequency: u64,
     pub vendor: String,
+    pub family: u32,
+    pub model: u32,
+    pub stepping: u32,
+    /// Known microarchitecture of the family/model/stepping, if any.
+    pub microarch: Option<Microarch>,
+    /// Nominal clock in MHz.
+    pub base_frequency: Option<u32>,
+    /// Highest boost clock in MHz.
+    pub max_frequency: Option<u32>,
+    /// Mixes performance and efficiency cores (Intel 12th gen and later).
+    pub hybrid: bool,
+    pub performance_cores: usize,
+    pub efficiency_cores: usize,
+    /// More than one logical processor per core is enabled.
+    pub smt: bool,
+    pub thread_director: bool,
+    /// Per-core P-states under hardware control (Intel HWP, AMD CPPC).
+    pub hardware_pstates: bool,
+    /// Running in a virtual machine; topology is what the hypervisor exposes.
+    pub hypervisor: bool,
+    pub caches: Vec<CacheInfo>,
+    pub numa_nodes: usize,
+    /// Separate L3 caches, i.e. CCDs (CCXs before Zen 3) on AMD.
+    pub l3_domains: usize,
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub level: u8,
+    pub kind: CacheKind,
+    pub size_kb: u32,
+    /// Logical processors that can share one instance.
+    pub shared_by: u32,
+    pub instances: usize,
+    /// Set when only one kind of core has this cache.
+    pub core_type: Option<CoreType>,
 }
 
-pub fn detect() -> CpuInfo {
-    let mut sys = System::new_all();
-    sys.refresh_cpu_all();
-    
+impl fmt::Display for CacheInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.kind {
+            CacheKind::Data => "d",
+            CacheKind::Instruction => "i",
+            CacheKind::Unified => "",
+        };
+        write!(f, "L{}{} ", self.level, suffix)?;
+        if self.size_kb >= 1024 && self.size_kb.is_multiple_of(1024) {
+            write!(f, "{} MB", self.size_kb / 1024)?;
+        } else {
+            write!(f, "{} KB", self.size_kb)?;
+        }
+        if self.instances > 1 {
+            write!(f, " x{}", self.instances)?;
+        }
+        match self.core_type {
+            Some(CoreType::Performance) => write!(f, " (P-cores)"),
+            Some(CoreType::Efficiency) => write!(f, " (E-cores)"),
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn detect(sys: &System) -> CpuInfo {
     let cpus = sys.cpus();
     let cpu = cpus.first();
-    
-    CpuInfo {
+
+    let mut info = CpuInfo {
         name: cpu.map(|c| c.brand().to_string()).unwrap_or_else(|| "Unknown".to_string()),
         cores: sys.physical_core_count().unwrap_or(0),
         threads: cpus.len(),
         frequency: cpu.map(|c| c.frequency()).unwrap_or(0),
         vendor: cpu.map(|c| c.vendor_id().to_string()).unwrap_or_else(|| "Unknown".to_string()),
+        family: 0,
+        model: 0,
+        stepping: 0,
+        microarch: None,
+        base_frequency: None,
+        max_frequency: None,
+        hybrid: false,
+        performance_cores: 0,
+        efficiency_cores: 0,
+        smt: false,
+        thread_director: false,
+        hardware_pstates: false,
+        hypervisor: false,
+        caches: Vec::new(),
+        numa_nodes: numa_nodes(),
+        l3_domains: 0,
+        features: Vec::new(),
+    };
+
+    if let Some(id) = cpuid::probe() {
+        if let Some(brand) = id.brand.filter(|_| info.name.trim().is_empty() || info.name == "Unknown") {
+            info.name = brand;
+        }
+        if info.vendor.trim().is_empty() || info.vendor == "Unknown" {
+            info.vendor = id.vendor.clone();
+        }
+        info.family = id.family;
+        info.model = id.model;
+        info.stepping = id.stepping;
+        info.microarch = microarch::identify(microarch::vendor(&id.vendor), id.family, id.model, id.stepping);
+        info.hybrid = id.hybrid;
+        info.thread_director = id.thread_director;
+        info.hardware_pstates = id.hardware_pstates;
+        info.hypervisor = id.hypervisor;
+        info.base_frequency = id.base_mhz;
+        info.max_frequency = id.max_mhz;
+        info.features = id.features.iter().map(|f| f.to_string()).collect();
+    }
+    let (base, max) = os_frequencies();
+    info.base_frequency = info.base_frequency.or(base);
+    info.max_frequency = info.max_frequency.or(max);
+
+    let mut locals = on_each_cpu(info.threads);
+    let complete = !locals.is_empty() && locals.len() == info.threads;
+    if locals.is_empty() {
+        locals.extend(cpuid::probe_local());
+    }
+
+    if complete {
+        apply_topology(&mut info, &locals);
+    }
+    info.smt = info.threads > info.cores && info.cores > 0;
+    info.caches = caches(&locals);
+    info.l3_domains = info.caches.iter().filter(|c| c.level == 3).map(|c| c.instances).sum();
+    info
+}
+
+impl CpuInfo {
+    pub fn has(&self, capability: Capability) -> bool {
+        self.microarch.as_ref().is_some_and(|m| m.has(capability))
+    }
+}
+
+static INFO: OnceLock<CpuInfo> = OnceLock::new();
+
+/// [`detect`], run once per process and shared by the CPU checks.
+pub fn info() -> &'static CpuInfo {
+    INFO.get_or_init(|| detect(&super::snapshot()))
+}
+
+/// [`info`], detected from a snapshot the caller already took.
+pub(super) fn info_from(sys: &System) -> &'static CpuInfo {
+    INFO.get_or_init(|| detect(sys))
+}
+
+/// Counts cores by the core part of each logical processor's APIC ID.
+fn apply_topology(info: &mut CpuInfo, locals: &[Local]) {
+    let core_id = |l: &Local| l.apic_id >> l.smt_shift;
+    let cores: BTreeSet<u32> = locals.iter().map(core_id).collect();
+    info.cores = cores.len();
+
+    if info.hybrid {
+        let count = |core_type| {
+            locals.iter().filter(|l| l.core_type == Some(core_type)).map(core_id).collect::<BTreeSet<_>>().len()
+        };
+        info.performance_cores = count(CoreType::Performance);
+        info.efficiency_cores = count(CoreType::Efficiency);
+    } else {
+        info.performance_cores = info.cores;
+    }
+}
+
+/// Merges the caches seen by each logical processor. Instances are told
+/// apart by the APIC ID bits above the sharing width.
+fn caches(locals: &[Local]) -> Vec<CacheInfo> {
+    let mut caches: Vec<(CacheLevel, CacheInfo)> = Vec::new();
+    let mut seen: HashSet<(CacheLevel, u32)> = HashSet::new();
+
+    for local in locals {
+        for level in &local.caches {
+            let instance = local.apic_id >> level.shared_by.next_power_of_two().trailing_zeros();
+            let new_instance = seen.insert((*level, instance));
+            match caches.iter_mut().find(|(l, _)| l == level) {
+                Some((_, cache)) => {
+                    cache.instances += usize::from(new_instance);
+                    if cache.core_type != local.core_type {
+                        cache.core_type = None;
+                    }
+                }
+                None => caches.push((*level, CacheInfo {
+                    level: level.level,
+                    kind: level.kind,
+                    size_kb: level.size_kb,
+                    shared_by: level.shared_by,
+                    instances: 1,
+                    core_type: local.core_type,
+                })),
+            }
+        }
+    }
+
+    let mut caches: Vec<CacheInfo> = caches.into_iter().map(|(_, c)| c).collect();
+    // Core types only matter when the two kinds of core differ
+    if !caches.iter().any(|c| c.core_type == Some(CoreType::Efficiency)) {
+        caches.iter_mut().for_each(|c| c.core_type = None);
+    }
+    caches.sort_by_key(|c| (c.level, c.kind, c.core_type));
+    caches
+}
+
+/// Runs [`cpuid::probe_local`] pinned to each logical processor in turn,
+/// on a scratch thread so the caller keeps its affinity. Processors that
+/// cannot be pinned to are left out.
+fn on_each_cpu(count: usize) -> Vec<Local> {
+    std::thread::spawn(move || {
+        (0..count).filter(|&cpu| pin_to(cpu)).filter_map(|_| cpuid::probe_local()).collect()
+    })
+    .join()
+    .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn pin_to(cpu: usize) -> bool {
+    use windows::Win32::System::Threading::{GetCurrentThread, SetThreadAffinityMask};
+
+    // Processor group 0 only
+    if cpu >= usize::BITS as usize {
+        return false;
+    }
+    unsafe { SetThreadAffinityMask(GetCurrentThread(), 1 << cpu) != 0 }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_to(cpu: usize) -> bool {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(cpu, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn pin_to(_cpu: usize) -> bool {
+    false
+}
+
+#[cfg(target_os = "windows")]
+fn numa_nodes() -> usize {
+    use windows::Win32::System::Threading::GetNumaHighestNodeNumber;
+
+    let mut highest = 0u32;
+    match unsafe { GetNumaHighestNodeNumber(&mut highest) } {
+        Ok(()) => highest as usize + 1,
+        Err(_) => 1,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn numa_nodes() -> usize {
+    let Ok(entries) = std::fs::read_dir("/sys/devices/system/node") else { return 1 };
+    let nodes = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("node").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .count();
+    nodes.max(1)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn numa_nodes() -> usize {
+    1
+}
+
+/// Base and maximum MHz from the OS, for CPUs without CPUID leaf 0x16.
+#[cfg(target_os = "windows")]
+fn os_frequencies() -> (Option<u32>, Option<u32>) {
+    use crate::utils::registry::{self, RegPath};
+
+    let key = RegPath::hklm("HARDWARE\\DESCRIPTION\\System\\CentralProcessor\\0");
+    (registry::read_dword(&key, "~MHz").ok().filter(|&mhz| mhz > 0), None)
+}
+
+#[cfg(target_os = "linux")]
+fn os_frequencies() -> (Option<u32>, Option<u32>) {
+    let khz = |file: &str| {
+        std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu0/cpufreq/{}", file))
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok())
+            .filter(|&khz| khz > 0)
+            .map(|khz| khz / 1000)
+    };
+    (khz("base_frequency"), khz("cpuinfo_max_freq"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn os_frequencies() -> (Option<u32>, Option<u32>) {
+    (None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn cache(level: u8, kind: CacheKind, size_kb: u32, shared_by: u32) -> CacheLevel {
+        CacheLevel { level, kind, size_kb, shared_by }
+    }
+
+    fn local(apic_id: u32, core_type: Option<CoreType>, caches: &[CacheLevel]) -> Local {
+        Local { apic_id, smt_shift: 1, core_type, caches: caches.to_vec() }
+    }
+
+    fn topology(hybrid: bool, locals: &[Local]) -> (CpuInfo, Vec<String>) {
+        let mut info = CpuInfo { hybrid, threads: locals.len(), ..CpuInfo::default() };
+        apply_topology(&mut info, locals);
+        (info, caches(locals).iter().map(CacheInfo::to_string).collect())
+    }
+
+    #[test]
+    fn hybrid_with_smt_on_p_cores() {
+        // 8 P-cores with two threads at APIC IDs 0-15, 8 E-cores in two
+        // clusters of four at 0x40-0x4e; the SMT shift is package-wide
+        const L3: CacheLevel = cache(3, CacheKind::Unified, 30 * 1024, 128);
+        let p = [cache(1, CacheKind::Data, 48, 2), cache(2, CacheKind::Unified, 2048, 2), L3];
+        let e = [cache(1, CacheKind::Data, 32, 1), cache(2, CacheKind::Unified, 4096, 8), L3];
+        let locals: Vec<Local> = (0..16)
+            .map(|apic| local(apic, Some(CoreType::Performance), &p))
+            .chain((0..8).map(|core| local(0x40 + core * 2, Some(CoreType::Efficiency), &e)))
+            .collect();
+
+        let (info, caches) = topology(true, &locals);
+        assert_eq!(info.cores, 16);
+        assert_eq!(info.performance_cores, 8);
+        assert_eq!(info.efficiency_cores, 8);
+        assert_eq!(
+            caches,
+            ["L1d 48 KB x8 (P-cores)", "L1d 32 KB x8 (E-cores)", "L2 2 MB x8 (P-cores)", "L2 4 MB x2 (E-cores)", "L3 30 MB"]
+        );
+    }
+
+    #[test]
+    fn two_ccds() {
+        // Six cores per CCD: each CCD starts at a multiple of 16 APIC IDs,
+        // leaving a gap after the first
+        let levels = [
+            cache(1, CacheKind::Data, 32, 2),
+            cache(2, CacheKind::Unified, 1024, 2),
+            cache(3, CacheKind::Unified, 32 * 1024, 16),
+        ];
+        let locals: Vec<Local> = (0..12).chain(16..28).map(|apic| local(apic, None, &levels)).collect();
+
+        let (info, caches) = topology(false, &locals);
+        assert_eq!(info.cores, 12);
+        assert_eq!(info.performance_cores, 12);
+        assert_eq!(info.efficiency_cores, 0);
+        assert_eq!(caches, ["L1d 32 KB x12", "L2 1 MB x12", "L3 32 MB x2"]);
     }
 }
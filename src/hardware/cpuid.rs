@@ -0,0 +1,271 @@
+//! Decoding of the x86 CPUID leaves APEX uses for CPU topology. Values
+//! that differ between cores (APIC ID, core type, caches) are read by
+//! [`probe_local`] on the logical processor the calling thread runs on.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CoreType {
+    Performance,
+    Efficiency,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CacheKind {
+    Data,
+    Instruction,
+    Unified,
+}
+
+/// One cache level as seen from a single logical processor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheLevel {
+    pub level: u8,
+    pub kind: CacheKind,
+    pub size_kb: u32,
+    /// Maximum number of logical processors sharing one instance.
+    pub shared_by: u32,
+}
+
+/// Package-wide CPUID data.
+#[derive(Debug, Clone, Default)]
+pub struct Identity {
+    pub vendor: String,
+    pub brand: Option<String>,
+    /// Display family, model and stepping from leaf 1.
+    pub family: u32,
+    pub model: u32,
+    pub stepping: u32,
+    pub hybrid: bool,
+    pub hypervisor: bool,
+    /// Intel Thread Director (hardware feedback interface).
+    pub thread_director: bool,
+    /// Hardware-controlled P-states: Intel HWP or AMD CPPC.
+    pub hardware_pstates: bool,
+    pub base_mhz: Option<u32>,
+    pub max_mhz: Option<u32>,
+    pub features: Vec<&'static str>,
+}
+
+/// CPUID data of the logical processor the thread runs on.
+#[derive(Debug, Clone, Default)]
+pub struct Local {
+    pub apic_id: u32,
+    /// Bits of `apic_id` that select the thread within a core.
+    pub smt_shift: u32,
+    pub core_type: Option<CoreType>,
+    pub caches: Vec<CacheLevel>,
+}
+
+#[derive(Clone, Copy)]
+enum Reg {
+    Eax,
+    Ebx,
+    Ecx,
+}
+
+/// Instruction set extensions reported by `features`: name, leaf, subleaf,
+/// register and bit.
+const FEATURES: &[(&str, u32, u32, Reg, u32)] = &[
+    ("SSE4.2", 1, 0, Reg::Ecx, 20),
+    ("POPCNT", 1, 0, Reg::Ecx, 23),
+    ("AES", 1, 0, Reg::Ecx, 25),
+    ("AVX", 1, 0, Reg::Ecx, 28),
+    ("FMA", 1, 0, Reg::Ecx, 12),
+    ("AVX2", 7, 0, Reg::Ebx, 5),
+    ("BMI2", 7, 0, Reg::Ebx, 8),
+    ("AVX-512F", 7, 0, Reg::Ebx, 16),
+    ("SHA", 7, 0, Reg::Ebx, 29),
+    ("VAES", 7, 0, Reg::Ecx, 9),
+    ("AVX-VNNI", 7, 1, Reg::Eax, 4),
+];
+
+const HYBRID_BIT: u32 = 15;
+const HYPERVISOR_BIT: u32 = 31;
+const HWP_BIT: u32 = 7;
+const THREAD_DIRECTOR_BIT: u32 = 23;
+const AMD_CPPC_BIT: u32 = 27;
+const AMD_TOPOLOGY_EXTENSIONS_BIT: u32 = 22;
+
+const INTEL_ATOM: u32 = 0x20;
+const INTEL_CORE: u32 = 0x40;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpuid(leaf: u32, subleaf: u32) -> [u32; 4] {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid_count;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid_count;
+
+    // Safe since Rust 1.87; the block keeps older compilers happy
+    #[allow(unused_unsafe)]
+    let r = unsafe { __cpuid_count(leaf, subleaf) };
+    [r.eax, r.ebx, r.ecx, r.edx]
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn bit(value: u32, bit: u32) -> bool {
+    value >> bit & 1 == 1
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn ascii(registers: &[u32]) -> String {
+    let bytes: Vec<u8> = registers.iter().flat_map(|r| r.to_le_bytes()).collect();
+    String::from_utf8_lossy(&bytes).trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string()
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn probe() -> Option<Identity> {
+    let [max_leaf, b, c, d] = cpuid(0, 0);
+    let max_extended = cpuid(0x8000_0000, 0)[0];
+    let leaf = |leaf: u32, subleaf: u32| {
+        let max = if leaf >= 0x8000_0000 { max_extended } else { max_leaf };
+        if leaf <= max { cpuid(leaf, subleaf) } else { [0; 4] }
+    };
+
+    let brand = (max_extended >= 0x8000_0004)
+        .then(|| ascii(&[0x8000_0002, 0x8000_0003, 0x8000_0004].map(|l| cpuid(l, 0)).concat()))
+        .filter(|b| !b.is_empty());
+
+    let features = FEATURES
+        .iter()
+        .filter(|(_, l, s, reg, b)| bit(leaf(*l, *s)[*reg as usize], *b))
+        .map(|(name, ..)| *name)
+        .collect();
+
+    // Extended family and model only count for families 6 and 15
+    let signature = leaf(1, 0)[0];
+    let base_family = signature >> 8 & 0xf;
+    let mut family = base_family;
+    let mut model = signature >> 4 & 0xf;
+    if base_family == 0xf {
+        family += signature >> 20 & 0xff;
+    }
+    if base_family == 0x6 || base_family == 0xf {
+        model |= (signature >> 16 & 0xf) << 4;
+    }
+
+    // Leaf 0x16 is zero on CPUs and hypervisors that do not fill it in
+    let [base, max, ..] = leaf(0x16, 0);
+
+    Some(Identity {
+        vendor: ascii(&[b, d, c]),
+        brand,
+        family,
+        model,
+        stepping: signature & 0xf,
+        hybrid: bit(leaf(7, 0)[3], HYBRID_BIT),
+        hypervisor: bit(leaf(1, 0)[2], HYPERVISOR_BIT),
+        thread_director: bit(leaf(6, 0)[0], THREAD_DIRECTOR_BIT),
+        hardware_pstates: bit(leaf(6, 0)[0], HWP_BIT) || bit(leaf(0x8000_0008, 0)[1], AMD_CPPC_BIT),
+        base_mhz: (base & 0xffff != 0).then_some(base & 0xffff),
+        max_mhz: (max & 0xffff != 0).then_some(max & 0xffff),
+        features,
+    })
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn probe_local() -> Option<Local> {
+    let max_leaf = cpuid(0, 0)[0];
+    let max_extended = cpuid(0x8000_0000, 0)[0];
+
+    // Prefer the x2APIC topology leaves; leaf 1 only has an 8-bit APIC ID
+    let topology_leaf = [0x1f, 0xb].into_iter().find(|&l| l <= max_leaf && cpuid(l, 0)[1] != 0);
+    let (apic_id, smt_shift) = match topology_leaf {
+        Some(l) => {
+            let [eax, _, _, edx] = cpuid(l, 0);
+            (edx, eax & 0x1f)
+        }
+        None => {
+            let apic_id = cpuid(1, 0)[1] >> 24;
+            // AMD without x2APIC leaves: threads per core from leaf 0x8000001E
+            let threads = if max_extended >= 0x8000_001e { (cpuid(0x8000_001e, 0)[1] >> 8 & 0xff) + 1 } else { 1 };
+            (apic_id, threads.next_power_of_two().trailing_zeros())
+        }
+    };
+
+    let core_type = if max_leaf >= 0x1a {
+        match cpuid(0x1a, 0)[0] >> 24 {
+            INTEL_ATOM => Some(CoreType::Efficiency),
+            INTEL_CORE => Some(CoreType::Performance),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    // Intel describes caches in leaf 4, AMD in 0x8000001D with the same layout
+    let amd_topology = max_extended >= 0x8000_0001 && bit(cpuid(0x8000_0001, 0)[2], AMD_TOPOLOGY_EXTENSIONS_BIT);
+    let cache_leaf = if amd_topology && max_extended >= 0x8000_001d {
+        Some(0x8000_001d)
+    } else {
+        (max_leaf >= 4).then_some(4)
+    };
+    let caches = cache_leaf.map(caches).unwrap_or_default();
+
+    Some(Local { apic_id, smt_shift, core_type, caches })
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn caches(leaf: u32) -> Vec<CacheLevel> {
+    (0..16)
+        .map(|subleaf| cpuid(leaf, subleaf))
+        .take_while(|[eax, ..]| eax & 0x1f != 0)
+        .filter_map(|[eax, ebx, ecx, _]| cache_level(eax, ebx, ecx))
+        .collect()
+}
+
+/// Decodes one subleaf of leaf 4 or 0x8000001D.
+fn cache_level(eax: u32, ebx: u32, ecx: u32) -> Option<CacheLevel> {
+    let kind = match eax & 0x1f {
+        1 => CacheKind::Data,
+        2 => CacheKind::Instruction,
+        3 => CacheKind::Unified,
+        _ => return None,
+    };
+    let ways = (ebx >> 22) + 1;
+    let partitions = (ebx >> 12 & 0x3ff) + 1;
+    let line = (ebx & 0xfff) + 1;
+    let sets = ecx + 1;
+    Some(CacheLevel {
+        level: (eax >> 5 & 0x7) as u8,
+        kind,
+        size_kb: ways * partitions * line * sets / 1024,
+        shared_by: (eax >> 14 & 0xfff) + 1,
+    })
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn probe() -> Option<Identity> {
+    None
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn probe_local() -> Option<Local> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intel_cache_leaf() {
+        // Leaf 4 on a Raptor Lake P-core: L1d and L2 per core of two
+        // threads, L3 for the package
+        let l1d = cache_level(0xfc00_4121, 0x02c0_003f, 0x0000_003f).unwrap();
+        assert_eq!(l1d, CacheLevel { level: 1, kind: CacheKind::Data, size_kb: 48, shared_by: 2 });
+        let l2 = cache_level(0xfc00_4143, 0x03c0_003f, 0x0000_07ff).unwrap();
+        assert_eq!(l2, CacheLevel { level: 2, kind: CacheKind::Unified, size_kb: 2048, shared_by: 2 });
+        let l3 = cache_level(0xfc1f_c163, 0x02c0_003f, 0x0000_bfff).unwrap();
+        assert_eq!(l3, CacheLevel { level: 3, kind: CacheKind::Unified, size_kb: 36 * 1024, shared_by: 128 });
+    }
+
+    #[test]
+    fn amd_cache_leaf() {
+        // Leaf 0x8000001D on Zen 4: one 32 MB L3 per CCD of 16 threads
+        let l3 = cache_level(0x0003_c163, 0x03c0_003f, 0x0000_7fff).unwrap();
+        assert_eq!(l3, CacheLevel { level: 3, kind: CacheKind::Unified, size_kb: 32 * 1024, shared_by: 16 });
+        assert_eq!(cache_level(0, 0, 0), None);
+    }
+}
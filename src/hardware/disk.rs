@@ -0,0 +1,536 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BusType {
+    Nvme,
+    Sata,
+    Sas,
+    Scsi,
+    Usb,
+    Raid,
+    SdCard,
+    Virtual,
+    Other,
+    Unknown,
+}
+
+impl fmt::Display for BusType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BusType::Nvme => "NVMe",
+            BusType::Sata => "SATA",
+            BusType::Sas => "SAS",
+            BusType::Scsi => "SCSI",
+            BusType::Usb => "USB",
+            BusType::Raid => "RAID",
+            BusType::SdCard => "SD/MMC",
+            BusType::Virtual => "Virtual",
+            BusType::Other => "Other",
+            BusType::Unknown => "Unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MediaType {
+    Ssd,
+    Hdd,
+    Unknown,
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MediaType::Ssd => "SSD",
+            MediaType::Hdd => "HDD",
+            MediaType::Unknown => "Unknown media",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartitionStyle {
+    Mbr,
+    Gpt,
+    Raw,
+}
+
+impl fmt::Display for PartitionStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PartitionStyle::Mbr => "MBR",
+            PartitionStyle::Gpt => "GPT",
+            PartitionStyle::Raw => "unpartitioned",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Partition {
+    pub number: u32,
+    /// Byte offset from the start of the disk.
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl Partition {
+    /// Starts on a 4 KiB boundary, as SSDs and Advanced Format disks need.
+    pub fn is_aligned(&self) -> bool {
+        self.offset.is_multiple_of(4096)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhysicalDisk {
+    /// Windows disk number (`\\.\PhysicalDriveN`), or the index in
+    /// `/sys/block` on Linux.
+    pub number: u32,
+    /// Device path, e.g. `\\.\PhysicalDrive0` or `/dev/nvme0n1`.
+    pub device: String,
+    pub model: String,
+    pub serial: String,
+    pub firmware: String,
+    pub bus: BusType,
+    pub media: MediaType,
+    pub size: u64,
+    pub trim: Option<bool>,
+    pub partition_style: Option<PartitionStyle>,
+    pub partitions: Vec<Partition>,
+}
+
+/// [`detect`], run once per process and shared by the storage checks.
+pub fn disks() -> &'static [PhysicalDisk] {
+    static DISKS: OnceLock<Vec<PhysicalDisk>> = OnceLock::new();
+    DISKS.get_or_init(detect)
+}
+
+/// Disk and partition number a mounted volume lives on. `name` and
+/// `mount_point` are as reported by sysinfo; no partition means the
+/// volume spans the whole disk.
+#[cfg(target_os = "windows")]
+pub fn locate_volume(_name: &str, mount_point: &str) -> Option<(u32, Option<u32>)> {
+    // Windows reports where the volume starts; match it to a partition
+    let (number, offset) = windows_disk::volume_extent(mount_point)?;
+    let disk = disks().iter().find(|d| d.number == number)?;
+    Some((number, disk.partitions.iter().find(|p| p.offset == offset).map(|p| p.number)))
+}
+
+#[cfg(target_os = "linux")]
+pub fn locate_volume(name: &str, _mount_point: &str) -> Option<(u32, Option<u32>)> {
+    let (disk, partition) = linux_disk::block_device(name.strip_prefix("/dev/")?)?;
+    let number = disks().iter().find(|d| d.device == format!("/dev/{}", disk))?.number;
+    Some((number, partition))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn locate_volume(_name: &str, _mount_point: &str) -> Option<(u32, Option<u32>)> {
+    None
+}
+
+/// Raw NVMe SMART/Health Information log page (512 bytes) of a disk.
+#[cfg(target_os = "windows")]
+pub fn nvme_health_log(number: u32) -> Option<Vec<u8>> {
+    windows_disk::nvme_health_log(number)
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect() -> Vec<PhysicalDisk> {
+    // Disk numbers can have gaps after drives are removed
+    (0..64).filter_map(windows_disk::disk).collect()
+}
+
+#[cfg(target_os = "windows")]
+mod windows_disk {
+    use super::{BusType, MediaType, Partition, PartitionStyle, PhysicalDisk};
+    use std::ffi::c_void;
+    use std::mem::{offset_of, size_of};
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Storage::FileSystem::{
+        self, CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_READ, FILE_SHARE_WRITE, IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS,
+        OPEN_EXISTING, STORAGE_BUS_TYPE,
+    };
+    use windows::Win32::System::IO::DeviceIoControl;
+    use windows::Win32::System::Ioctl::*;
+
+    struct Handle(HANDLE);
+
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = CloseHandle(self.0);
+            }
+        }
+    }
+
+    /// Opens a device for queries only, which does not need admin rights.
+    fn open(path: &str) -> Option<Handle> {
+        let wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            CreateFileW(
+                PCWSTR(wide.as_ptr()),
+                0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                None,
+            )
+        }
+        .ok()
+        .map(Handle)
+    }
+
+    fn control(handle: &Handle, code: u32, input: Option<(*const c_void, usize)>, size: usize) -> Option<Vec<u8>> {
+        let mut output = vec![0u8; size];
+        let mut returned = 0u32;
+        unsafe {
+            DeviceIoControl(
+                handle.0,
+                code,
+                input.map(|(data, _)| data),
+                input.map_or(0, |(_, len)| len as u32),
+                Some(output.as_mut_ptr().cast()),
+                output.len() as u32,
+                Some(&mut returned),
+                None,
+            )
+        }
+        .ok()?;
+        output.truncate(returned as usize);
+        Some(output)
+    }
+
+    fn ioctl(handle: &Handle, code: u32, size: usize) -> Option<Vec<u8>> {
+        control(handle, code, None, size)
+    }
+
+    /// IOCTL_STORAGE_QUERY_PROPERTY with a query that starts like
+    /// STORAGE_PROPERTY_QUERY.
+    fn query<T>(handle: &Handle, query: &T, size: usize) -> Option<Vec<u8>> {
+        control(handle, IOCTL_STORAGE_QUERY_PROPERTY, Some(((query as *const T).cast(), size_of::<T>())), size)
+    }
+
+    /// Reads a `T` at `offset` in an IOCTL output buffer.
+    fn read<T: Copy>(buffer: &[u8], offset: usize) -> Option<T> {
+        if buffer.len() < offset + size_of::<T>() {
+            return None;
+        }
+        Some(unsafe { std::ptr::read_unaligned(buffer.as_ptr().add(offset) as *const T) })
+    }
+
+    fn property(handle: &Handle, id: STORAGE_PROPERTY_ID) -> Option<Vec<u8>> {
+        let request = STORAGE_PROPERTY_QUERY {
+            PropertyId: id,
+            QueryType: PropertyStandardQuery,
+            AdditionalParameters: [0],
+        };
+        query(handle, &request, 1024)
+    }
+
+    /// NUL-terminated string at `offset` in a device descriptor; 0 means
+    /// the device did not report it.
+    fn c_string(buffer: &[u8], offset: u32) -> String {
+        let Some(bytes) = buffer.get(offset as usize..).filter(|_| offset != 0) else { return String::new() };
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+    }
+
+    fn bus_type(bus: STORAGE_BUS_TYPE) -> BusType {
+        match bus {
+            FileSystem::BusTypeNvme => BusType::Nvme,
+            FileSystem::BusTypeSata | FileSystem::BusTypeAta | FileSystem::BusTypeAtapi => BusType::Sata,
+            FileSystem::BusTypeSas => BusType::Sas,
+            FileSystem::BusTypeScsi | FileSystem::BusTypeiScsi => BusType::Scsi,
+            FileSystem::BusTypeUsb => BusType::Usb,
+            FileSystem::BusTypeRAID | FileSystem::BusTypeSpaces => BusType::Raid,
+            FileSystem::BusTypeSd | FileSystem::BusTypeMmc => BusType::SdCard,
+            FileSystem::BusTypeVirtual | FileSystem::BusTypeFileBackedVirtual => BusType::Virtual,
+            FileSystem::BusTypeUnknown => BusType::Unknown,
+            _ => BusType::Other,
+        }
+    }
+
+    pub fn disk(number: u32) -> Option<PhysicalDisk> {
+        let device = format!("\\\\.\\PhysicalDrive{}", number);
+        let handle = open(&device)?;
+
+        let descriptor = property(&handle, StorageDeviceProperty)?;
+        let header: STORAGE_DEVICE_DESCRIPTOR = read(&descriptor, 0)?;
+        let model = [c_string(&descriptor, header.VendorIdOffset), c_string(&descriptor, header.ProductIdOffset)]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let media = match property(&handle, StorageDeviceSeekPenaltyProperty)
+            .and_then(|b| read::<DEVICE_SEEK_PENALTY_DESCRIPTOR>(&b, 0))
+        {
+            Some(penalty) if penalty.IncursSeekPenalty.0 != 0 => MediaType::Hdd,
+            Some(_) => MediaType::Ssd,
+            None => MediaType::Unknown,
+        };
+        let trim = property(&handle, StorageDeviceTrimProperty)
+            .and_then(|b| read::<DEVICE_TRIM_DESCRIPTOR>(&b, 0))
+            .map(|t| t.TrimEnabled.0 != 0);
+
+        // IOCTL_DISK_GET_LENGTH_INFO needs read access, which the handle
+        // lacks; the geometry call does not. Room for the partition and
+        // detection info the driver appends.
+        let size = ioctl(&handle, IOCTL_DISK_GET_DRIVE_GEOMETRY_EX, 256)
+            .and_then(|b| read::<DISK_GEOMETRY_EX>(&b, 0))
+            .map_or(0, |g| g.DiskSize as u64);
+
+        let (partition_style, partitions) = layout(&handle).unwrap_or((None, Vec::new()));
+
+        Some(PhysicalDisk {
+            number,
+            device,
+            model: if model.is_empty() { "Unknown".to_string() } else { model },
+            serial: c_string(&descriptor, header.SerialNumberOffset),
+            firmware: c_string(&descriptor, header.ProductRevisionOffset),
+            bus: bus_type(header.BusType),
+            media,
+            size,
+            trim,
+            partition_style,
+            partitions,
+        })
+    }
+
+    fn layout(handle: &Handle) -> Option<(Option<PartitionStyle>, Vec<Partition>)> {
+        // GPT allows 128 entries; MBR reports 4 per extended partition
+        const ENTRIES: usize = 128;
+        let entries_at = offset_of!(DRIVE_LAYOUT_INFORMATION_EX, PartitionEntry);
+        let entry_size = size_of::<PARTITION_INFORMATION_EX>();
+        let buffer = ioctl(handle, IOCTL_DISK_GET_DRIVE_LAYOUT_EX, entries_at + ENTRIES * entry_size)?;
+
+        let style = read::<u32>(&buffer, offset_of!(DRIVE_LAYOUT_INFORMATION_EX, PartitionStyle))?;
+        let count = read::<u32>(&buffer, offset_of!(DRIVE_LAYOUT_INFORMATION_EX, PartitionCount))? as usize;
+        let style = match PARTITION_STYLE(style as i32) {
+            PARTITION_STYLE_MBR => Some(PartitionStyle::Mbr),
+            PARTITION_STYLE_GPT => Some(PartitionStyle::Gpt),
+            PARTITION_STYLE_RAW => Some(PartitionStyle::Raw),
+            _ => None,
+        };
+
+        let partitions = (0..count.min(ENTRIES))
+            .filter_map(|i| read::<PARTITION_INFORMATION_EX>(&buffer, entries_at + i * entry_size))
+            // Unused MBR slots and extended partition containers
+            .filter(|p| p.PartitionNumber > 0 && p.PartitionLength > 0)
+            .map(|p| Partition {
+                number: p.PartitionNumber,
+                offset: p.StartingOffset as u64,
+                size: p.PartitionLength as u64,
+            })
+            .collect();
+        Some((style, partitions))
+    }
+
+    /// STORAGE_PROPERTY_QUERY with STORAGE_PROTOCOL_SPECIFIC_DATA in place
+    /// of its additional parameters.
+    #[repr(C)]
+    struct ProtocolQuery {
+        property_id: STORAGE_PROPERTY_ID,
+        query_type: STORAGE_QUERY_TYPE,
+        data: STORAGE_PROTOCOL_SPECIFIC_DATA,
+    }
+
+    pub fn nvme_health_log(number: u32) -> Option<Vec<u8>> {
+        const NVME_LOG_PAGE_HEALTH_INFO: u32 = 2;
+        const LOG_SIZE: usize = 512;
+
+        let handle = open(&format!("\\\\.\\PhysicalDrive{}", number))?;
+        let request = ProtocolQuery {
+            property_id: StorageDeviceProtocolSpecificProperty,
+            query_type: PropertyStandardQuery,
+            data: STORAGE_PROTOCOL_SPECIFIC_DATA {
+                ProtocolType: ProtocolTypeNvme,
+                DataType: NVMeDataTypeLogPage.0 as u32,
+                ProtocolDataRequestValue: NVME_LOG_PAGE_HEALTH_INFO,
+                ProtocolDataOffset: size_of::<STORAGE_PROTOCOL_SPECIFIC_DATA>() as u32,
+                ProtocolDataLength: LOG_SIZE as u32,
+                ..Default::default()
+            },
+        };
+        let data_at = offset_of!(STORAGE_PROTOCOL_DATA_DESCRIPTOR, ProtocolSpecificData);
+        let buffer = query(&handle, &request, data_at + size_of::<STORAGE_PROTOCOL_SPECIFIC_DATA>() + LOG_SIZE)?;
+
+        // The log follows the returned protocol data, at its offset
+        let data: STORAGE_PROTOCOL_SPECIFIC_DATA = read(&buffer, data_at)?;
+        let start = data_at + data.ProtocolDataOffset as usize;
+        buffer.get(start..start + data.ProtocolDataLength as usize).map(<[u8]>::to_vec)
+    }
+
+    /// Disk number and byte offset of the first extent of a volume such
+    /// as `C:\`.
+    pub fn volume_extent(mount_point: &str) -> Option<(u32, u64)> {
+        let handle = open(&format!("\\\\.\\{}", mount_point.trim_end_matches('\\')))?;
+        let size = size_of::<VOLUME_DISK_EXTENTS>() + 7 * size_of::<DISK_EXTENT>();
+        let buffer = ioctl(&handle, IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS, size)?;
+
+        let count = read::<u32>(&buffer, 0)?;
+        let extent = read::<DISK_EXTENT>(&buffer, offset_of!(VOLUME_DISK_EXTENTS, Extents)).filter(|_| count > 0)?;
+        Some((extent.DiskNumber, extent.StartingOffset as u64))
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect() -> Vec<PhysicalDisk> {
+    let Ok(entries) = std::fs::read_dir("/sys/block") else { return Vec::new() };
+    let mut names: Vec<String> = entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+
+    names
+        .iter()
+        .filter_map(|name| linux_disk::disk(name))
+        .enumerate()
+        .map(|(number, mut disk)| {
+            disk.number = number as u32;
+            disk
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+mod linux_disk {
+    use super::{BusType, MediaType, Partition, PartitionStyle, PhysicalDisk};
+    use std::fs;
+    use std::path::Path;
+
+    fn read(path: &Path) -> Option<String> {
+        fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+    }
+
+    fn read_u64(path: &Path) -> Option<u64> {
+        read(path)?.parse().ok()
+    }
+
+    /// `KEY=value` properties udev recorded for a block device.
+    fn udev_property(dev: &str, key: &str) -> Option<String> {
+        let data = fs::read_to_string(format!("/run/udev/data/b{}", dev)).ok()?;
+        data.lines()
+            .filter_map(|l| l.strip_prefix("E:"))
+            .find_map(|l| l.strip_prefix(key).and_then(|v| v.strip_prefix('=')))
+            .map(str::to_string)
+    }
+
+    fn bus_type(name: &str, sysfs_path: &str) -> BusType {
+        if name.starts_with("nvme") {
+            BusType::Nvme
+        } else if sysfs_path.contains("/usb") {
+            BusType::Usb
+        } else if sysfs_path.contains("/ata") {
+            BusType::Sata
+        } else if sysfs_path.contains("/virtio") {
+            BusType::Virtual
+        } else if name.starts_with("mmcblk") {
+            BusType::SdCard
+        } else if sysfs_path.contains("/host") {
+            BusType::Scsi
+        } else {
+            BusType::Unknown
+        }
+    }
+
+    /// Partition table type from udev, or from the disk itself when it is
+    /// readable (root).
+    fn partition_style(name: &str, dev: &str, has_partitions: bool) -> Option<PartitionStyle> {
+        match udev_property(dev, "ID_PART_TABLE_TYPE").as_deref() {
+            Some("gpt") => return Some(PartitionStyle::Gpt),
+            Some("dos") => return Some(PartitionStyle::Mbr),
+            _ => {}
+        }
+
+        use std::io::Read;
+        let mut header = [0u8; 520];
+        fs::File::open(format!("/dev/{}", name)).ok()?.read_exact(&mut header).ok()?;
+        if &header[512..520] == b"EFI PART" {
+            Some(PartitionStyle::Gpt)
+        } else if header[510..512] == [0x55, 0xaa] && has_partitions {
+            Some(PartitionStyle::Mbr)
+        } else if !has_partitions {
+            Some(PartitionStyle::Raw)
+        } else {
+            None
+        }
+    }
+
+    pub fn disk(name: &str) -> Option<PhysicalDisk> {
+        let block = Path::new("/sys/block").join(name);
+        let sysfs_path = fs::canonicalize(&block).ok()?.to_string_lossy().into_owned();
+        // Loop, RAM, zram and device-mapper devices have no hardware behind them
+        if sysfs_path.contains("/virtual/") {
+            return None;
+        }
+
+        let device = block.join("device");
+        let dev = read(&block.join("dev")).unwrap_or_default();
+        let vendor = read(&device.join("vendor")).filter(|v| v != "ATA" && !v.starts_with("0x"));
+        let model = [vendor, read(&device.join("model"))].into_iter().flatten().collect::<Vec<_>>().join(" ");
+        let serial = read(&device.join("serial"))
+            .or_else(|| read(&block.join("serial")))
+            .or_else(|| udev_property(&dev, "ID_SERIAL_SHORT"))
+            .unwrap_or_default();
+        let firmware = read(&device.join("firmware_rev")).or_else(|| read(&device.join("rev"))).unwrap_or_default();
+
+        let media = match read(&block.join("queue/rotational")).as_deref() {
+            Some("1") => MediaType::Hdd,
+            Some("0") => MediaType::Ssd,
+            _ => MediaType::Unknown,
+        };
+        let trim = read_u64(&block.join("queue/discard_max_bytes")).map(|max| max > 0);
+
+        // Partitions are subdirectories with a `partition` file; offsets in
+        // 512-byte sectors regardless of the logical block size
+        let mut partitions: Vec<Partition> = fs::read_dir(&block)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let path = e.path();
+                Some(Partition {
+                    number: read_u64(&path.join("partition"))? as u32,
+                    offset: read_u64(&path.join("start"))? * 512,
+                    size: read_u64(&path.join("size")).unwrap_or(0) * 512,
+                })
+            })
+            .collect();
+        partitions.sort_by_key(|p| p.number);
+
+        Some(PhysicalDisk {
+            number: 0,
+            device: format!("/dev/{}", name),
+            model: if model.is_empty() { "Unknown".to_string() } else { model },
+            serial,
+            firmware,
+            bus: bus_type(name, &sysfs_path),
+            media,
+            size: read_u64(&block.join("size")).unwrap_or(0) * 512,
+            trim,
+            partition_style: partition_style(name, &dev, !partitions.is_empty()),
+            partitions,
+        })
+    }
+
+    /// Disk name and partition number of a block device such as `sda2`.
+    pub fn block_device(name: &str) -> Option<(String, Option<u32>)> {
+        let class = Path::new("/sys/class/block").join(name);
+        match read_u64(&class.join("partition")) {
+            Some(partition) => {
+                let parent = fs::canonicalize(&class).ok()?.parent()?.file_name()?.to_string_lossy().into_owned();
+                Some((parent, Some(partition as u32)))
+            }
+            None => class.exists().then(|| (name.to_string(), None)),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn detect() -> Vec<PhysicalDisk> {
+    Vec::new()
+}
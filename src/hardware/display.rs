@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use super::edid::{Edid, Mode};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    /// Monitor name from the EDID, or what the OS calls it.
+    pub name: String,
+    /// `\\.\DISPLAY1` on Windows, `card0-DP-1` on Linux.
+    pub connector: String,
+    pub primary: bool,
+    /// Resolution and refresh rate in use, where the OS reports it.
+    pub current_mode: Option<Mode>,
+    pub edid: Option<Edid>,
+}
+
+impl DisplayInfo {
+    fn new(name: String, connector: String, edid: Option<Edid>) -> Self {
+        let name = edid.as_ref().and_then(|e| e.name.clone()).unwrap_or(name);
+        DisplayInfo { name, connector, primary: false, current_mode: None, edid }
+    }
+
+    /// Highest refresh rate the monitor supports at the current resolution,
+    /// or at any resolution when the current one is unknown.
+    pub fn max_refresh(&self) -> Option<f64> {
+        let edid = self.edid.as_ref()?;
+        edid.max_refresh(self.current_mode.as_ref()).or_else(|| edid.max_refresh(None))
+    }
+}
+
+/// [`detect`], run once per process and shared by the display checks.
+pub fn displays() -> &'static [DisplayInfo] {
+    static DISPLAYS: OnceLock<Vec<DisplayInfo>> = OnceLock::new();
+    DISPLAYS.get_or_init(detect)
+}
+
+/// Active monitors, with the EDID Windows stored for each under
+/// `Enum\DISPLAY`.
+#[cfg(target_os = "windows")]
+pub fn detect() -> Vec<DisplayInfo> {
+    use std::mem::size_of;
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayDevicesW, EnumDisplaySettingsW, DEVMODEW, DISPLAY_DEVICEW, DISPLAY_DEVICE_ACTIVE,
+        DISPLAY_DEVICE_ATTACHED_TO_DESKTOP, DISPLAY_DEVICE_PRIMARY_DEVICE, ENUM_CURRENT_SETTINGS,
+    };
+
+    // Makes EnumDisplayDevices return the monitor's device interface path
+    const EDD_GET_DEVICE_INTERFACE_NAME: u32 = 1;
+
+    let wide = |s: &[u16]| String::from_utf16_lossy(&s[..s.iter().position(|&c| c == 0).unwrap_or(s.len())]);
+    let device = || DISPLAY_DEVICEW { cb: size_of::<DISPLAY_DEVICEW>() as u32, ..Default::default() };
+
+    let mut displays = Vec::new();
+    for source_index in 0..64 {
+        let mut source = device();
+        if !unsafe { EnumDisplayDevicesW(PCWSTR::null(), source_index, &mut source, 0) }.as_bool() {
+            break;
+        }
+        if source.StateFlags & DISPLAY_DEVICE_ATTACHED_TO_DESKTOP == 0 {
+            continue;
+        }
+        let source_name = PCWSTR(source.DeviceName.as_ptr());
+
+        let mut settings = DEVMODEW { dmSize: size_of::<DEVMODEW>() as u16, ..Default::default() };
+        let current_mode = unsafe { EnumDisplaySettingsW(source_name, ENUM_CURRENT_SETTINGS, &mut settings) }
+            .as_bool()
+            .then(|| Mode {
+                width: settings.dmPelsWidth,
+                height: settings.dmPelsHeight,
+                refresh: f64::from(settings.dmDisplayFrequency),
+                interlaced: false,
+            });
+
+        for monitor_index in 0..16 {
+            let mut monitor = device();
+            let found = unsafe {
+                EnumDisplayDevicesW(source_name, monitor_index, &mut monitor, EDD_GET_DEVICE_INTERFACE_NAME)
+            };
+            if !found.as_bool() {
+                break;
+            }
+            if monitor.StateFlags & DISPLAY_DEVICE_ACTIVE == 0 {
+                continue;
+            }
+            let edid = registry_edid(&wide(&monitor.DeviceID)).and_then(|data| Edid::parse(&data).ok());
+            let mut display = DisplayInfo::new(wide(&monitor.DeviceString), wide(&source.DeviceName), edid);
+            display.primary = source.StateFlags & DISPLAY_DEVICE_PRIMARY_DEVICE != 0;
+            display.current_mode = current_mode;
+            displays.push(display);
+        }
+    }
+    displays
+}
+
+/// EDID of a monitor interface such as
+/// `\\?\DISPLAY#GSM5B7F#5&2a7b5e4c&0&UID4352#{e6f07b5f-...}`.
+#[cfg(target_os = "windows")]
+fn registry_edid(interface: &str) -> Option<Vec<u8>> {
+    use crate::utils::registry::{self, RegPath};
+
+    let parts: Vec<&str> = interface.trim_start_matches("\\\\?\\").split('#').collect();
+    let [_, model, instance, ..] = parts[..] else { return None };
+    let key = RegPath::hklm("SYSTEM\\CurrentControlSet\\Enum\\DISPLAY")
+        .join(model)
+        .join(instance)
+        .join("Device Parameters");
+    registry::read_binary(&key, "EDID").ok()
+}
+
+/// Connected DRM connectors. The refresh rate in use is not exposed in
+/// sysfs.
+#[cfg(target_os = "linux")]
+pub fn detect() -> Vec<DisplayInfo> {
+    use std::fs;
+
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else { return Vec::new() };
+    let mut connectors: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| fs::read_to_string(e.path().join("status")).is_ok_and(|s| s.trim() == "connected"))
+        .collect();
+    connectors.sort_by_key(|e| e.file_name());
+
+    connectors
+        .iter()
+        .map(|entry| {
+            let connector = entry.file_name().to_string_lossy().into_owned();
+            let edid = fs::read(entry.path().join("edid")).ok().and_then(|data| Edid::parse(&data).ok());
+            DisplayInfo::new(connector.clone(), connector, edid)
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn detect() -> Vec<DisplayInfo> {
+    Vec::new()
+}
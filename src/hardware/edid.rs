@@ -0,0 +1,558 @@
+//! Parser for EDID 1.3/1.4 blobs with CTA-861 and DisplayID extension
+//! blocks.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+const BLOCK: usize = 128;
+const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+const TAG_CTA: u8 = 0x02;
+const TAG_DISPLAYID: u8 = 0x70;
+
+/// IEEE OUIs of the vendor-specific data blocks with a VRR range.
+const OUI_HDMI_FORUM: u32 = 0xc45dd8;
+const OUI_AMD: u32 = 0x00001a;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Mode {
+    pub width: u32,
+    pub height: u32,
+    /// Hz; fractional for rates such as 59.94 or 143.97.
+    pub refresh: f64,
+    pub interlaced: bool,
+}
+
+impl Mode {
+    fn new(width: u32, height: u32, refresh: f64) -> Self {
+        Mode { width, height, refresh, interlaced: false }
+    }
+
+    /// Mode of a timing from its pixel clock in Hz and total pixels.
+    fn from_timing(clock: u64, width: u32, height: u32, h_blank: u32, v_blank: u32, interlaced: bool) -> Option<Self> {
+        let total = u64::from(width + h_blank) * u64::from(height + v_blank);
+        if clock == 0 || width == 0 || height == 0 || total == 0 {
+            return None;
+        }
+        // Interlaced timings describe one field of half the lines
+        let refresh = (clock as f64 / total as f64 * 100.0).round() / 100.0;
+        let height = if interlaced { height * 2 } else { height };
+        Some(Mode { width, height, refresh, interlaced })
+    }
+
+    pub fn same_resolution(&self, other: &Mode) -> bool {
+        self.width == other.width && self.height == other.height
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}{} @ {} Hz", self.width, self.height, if self.interlaced { "i" } else { "" }, self.refresh)
+    }
+}
+
+/// Where a variable refresh range was advertised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VrrSource {
+    /// AMD FreeSync vendor-specific data block (HDMI).
+    FreeSync,
+    /// HDMI 2.1 VRR in the HDMI Forum data block.
+    HdmiVrr,
+    /// DisplayID range limits with seamless refresh changes (Adaptive-Sync).
+    DisplayId,
+}
+
+impl fmt::Display for VrrSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VrrSource::FreeSync => "FreeSync",
+            VrrSource::HdmiVrr => "HDMI VRR",
+            VrrSource::DisplayId => "Adaptive-Sync",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VrrRange {
+    pub min: u32,
+    pub max: u32,
+    pub source: VrrSource,
+}
+
+/// CTA-861 HDR static metadata and colorimetry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HdrInfo {
+    /// SMPTE ST 2084 transfer function, i.e. HDR10.
+    pub pq: bool,
+    pub hlg: bool,
+    pub bt2020: bool,
+    /// Desired content luminance in cd/m² (nits).
+    pub max_luminance: Option<f64>,
+    pub max_average_luminance: Option<f64>,
+    pub min_luminance: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Edid {
+    /// Three-letter PNP ID, e.g. `GSM` or `DEL`.
+    pub manufacturer: String,
+    pub product_code: u16,
+    /// Monitor name descriptor.
+    pub name: Option<String>,
+    /// Serial number descriptor, or the numeric serial.
+    pub serial: Option<String>,
+    pub year: u16,
+    pub version: String,
+    pub digital: bool,
+    /// Panel bit depth per color (EDID 1.4).
+    pub bits_per_color: Option<u8>,
+    /// Physical size in centimeters.
+    pub size_cm: Option<(u8, u8)>,
+    pub preferred: Option<Mode>,
+    /// Supported modes, highest resolution and refresh rate first.
+    pub modes: Vec<Mode>,
+    /// Vertical refresh limits from the range limits descriptor, in Hz.
+    pub refresh_range: Option<(u32, u32)>,
+    pub vrr: Option<VrrRange>,
+    pub hdr: Option<HdrInfo>,
+}
+
+impl Edid {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        const CONTEXT: &str = "EDID";
+        if data.len() < BLOCK || data[..8] != HEADER {
+            return Err(Error::invalid(CONTEXT, "missing EDID header"));
+        }
+
+        let id = u16::from_be_bytes([data[8], data[9]]);
+        let letter = |shift: u16| char::from(b'@' + ((id >> shift) & 0x1f) as u8);
+        let numeric_serial = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+        let digital = data[20] & 0x80 != 0;
+        let version = (data[18], data[19]);
+
+        let mut edid = Edid {
+            manufacturer: [letter(10), letter(5), letter(0)].iter().collect(),
+            product_code: u16::from_le_bytes([data[10], data[11]]),
+            name: None,
+            serial: (numeric_serial != 0).then(|| numeric_serial.to_string()),
+            year: 1990 + u16::from(data[17]),
+            version: format!("{}.{}", version.0, version.1),
+            digital,
+            bits_per_color: match (data[20] >> 4) & 0x07 {
+                depth @ 1..=6 if digital && version >= (1, 4) => Some(4 + depth * 2),
+                _ => None,
+            },
+            size_cm: (data[21] > 0 && data[22] > 0).then_some((data[21], data[22])),
+            preferred: None,
+            modes: Vec::new(),
+            refresh_range: None,
+            vrr: None,
+            hdr: None,
+        };
+
+        for descriptor in data[54..126].chunks_exact(18) {
+            edid.descriptor(descriptor);
+        }
+        // The first detailed timing is the preferred mode
+        edid.preferred = edid.modes.first().copied();
+        edid.established_timings(&data[35..38]);
+        edid.standard_timings(&data[38..54], version >= (1, 3));
+
+        let extensions = usize::from(data[126]);
+        for block in data[BLOCK..].chunks_exact(BLOCK).take(extensions) {
+            match block[0] {
+                TAG_CTA => edid.cta_block(block),
+                TAG_DISPLAYID => edid.displayid_block(block),
+                _ => {}
+            }
+        }
+
+        edid.modes.sort_by(|a, b| {
+            (b.width * b.height, b.refresh).partial_cmp(&(a.width * a.height, a.refresh)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        edid.modes.dedup_by(|a, b| a.same_resolution(b) && a.interlaced == b.interlaced && (a.refresh - b.refresh).abs() < 0.5);
+        Ok(edid)
+    }
+
+    /// Highest refresh rate of any progressive mode, optionally at one
+    /// resolution.
+    pub fn max_refresh(&self, at: Option<&Mode>) -> Option<f64> {
+        self.modes
+            .iter()
+            .filter(|m| !m.interlaced && at.is_none_or(|at| m.same_resolution(at)))
+            .map(|m| m.refresh)
+            .max_by(f64::total_cmp)
+    }
+
+    fn established_timings(&mut self, bytes: &[u8]) {
+        const TIMINGS: [(u32, u32, f64); 17] = [
+            (720, 400, 70.0), (720, 400, 88.0), (640, 480, 60.0), (640, 480, 67.0),
+            (640, 480, 72.0), (640, 480, 75.0), (800, 600, 56.0), (800, 600, 60.0),
+            (800, 600, 72.0), (800, 600, 75.0), (832, 624, 75.0), (1024, 768, 87.0),
+            (1024, 768, 60.0), (1024, 768, 70.0), (1024, 768, 75.0), (1280, 1024, 75.0),
+            (1152, 870, 75.0),
+        ];
+        for (i, &(width, height, refresh)) in TIMINGS.iter().enumerate() {
+            if bytes[i / 8] & (0x80 >> (i % 8)) != 0 {
+                // 1024x768 at 87 Hz is interlaced
+                let mode = Mode { interlaced: i == 11, ..Mode::new(width, height, refresh) };
+                self.modes.push(mode);
+            }
+        }
+    }
+
+    fn standard_timings(&mut self, bytes: &[u8], wide_default: bool) {
+        for pair in bytes.chunks_exact(2) {
+            if pair == [0x01, 0x01] || pair[0] == 0 {
+                continue;
+            }
+            let width = (u32::from(pair[0]) + 31) * 8;
+            let height = match pair[1] >> 6 {
+                0 if wide_default => width * 10 / 16,
+                0 => width,
+                1 => width * 3 / 4,
+                2 => width * 4 / 5,
+                _ => width * 9 / 16,
+            };
+            self.modes.push(Mode::new(width, height, f64::from(pair[1] & 0x3f) + 60.0));
+        }
+    }
+
+    /// An 18-byte detailed timing or display descriptor.
+    fn descriptor(&mut self, d: &[u8]) {
+        if d[0] != 0 || d[1] != 0 {
+            self.modes.extend(detailed_timing(d));
+            return;
+        }
+        let text = || {
+            let end = d[5..].iter().position(|&b| b == 0x0a).map_or(d.len(), |p| p + 5);
+            Some(String::from_utf8_lossy(&d[5..end]).trim().to_string()).filter(|s| !s.is_empty())
+        };
+        match d[3] {
+            0xfc => self.name = text(),
+            0xff => self.serial = text().or(self.serial.take()),
+            0xfd => {
+                // Offsets of 255 Hz for high refresh monitors (EDID 1.4)
+                let min_offset = if d[4] & 0x03 == 0x03 { 255 } else { 0 };
+                let max_offset = if d[4] & 0x02 != 0 { 255 } else { 0 };
+                let range = (u32::from(d[5]) + min_offset, u32::from(d[6]) + max_offset);
+                self.refresh_range = Some(range).filter(|(min, max)| *min > 0 && max >= min);
+            }
+            _ => {}
+        }
+    }
+
+    fn cta_block(&mut self, block: &[u8]) {
+        let dtd_offset = usize::from(block[2]);
+        if (4..BLOCK).contains(&dtd_offset) {
+            let mut offset = 4;
+            while offset < dtd_offset {
+                let header = block[offset];
+                let end = (offset + 1 + usize::from(header & 0x1f)).min(dtd_offset);
+                let payload = &block[offset + 1..end];
+                match header >> 5 {
+                    2 => self.modes.extend(payload.iter().filter_map(|&vic| vic_mode(vic))),
+                    3 => self.vendor_block(payload),
+                    7 => self.extended_block(payload),
+                    _ => {}
+                }
+                offset = end;
+            }
+        }
+        if dtd_offset >= 4 {
+            for d in block[dtd_offset.min(BLOCK - 1)..BLOCK - 1].chunks_exact(18) {
+                if d[0] == 0 && d[1] == 0 {
+                    break;
+                }
+                self.modes.extend(detailed_timing(d));
+            }
+        }
+    }
+
+    fn vendor_block(&mut self, payload: &[u8]) {
+        if payload.len() < 3 {
+            return;
+        }
+        let oui = u32::from_le_bytes([payload[0], payload[1], payload[2], 0]);
+        let data = &payload[3..];
+        let range = match oui {
+            // Version, flags, minimum and maximum refresh rate
+            OUI_AMD if data.len() >= 4 => Some((u32::from(data[2]), u32::from(data[3]), VrrSource::FreeSync)),
+            // After version, TMDS rate and three flag bytes: VRRmin in bits
+            // 5:0, VRRmax bits 9:8 above it and 7:0 in the next byte
+            OUI_HDMI_FORUM if data.len() >= 7 => {
+                let max = (u32::from(data[5] >> 6) << 8) | u32::from(data[6]);
+                Some((u32::from(data[5] & 0x3f), max, VrrSource::HdmiVrr))
+            }
+            _ => None,
+        };
+        if let Some((min, max, source)) = range.filter(|(min, max, _)| *min > 0 && max > min) {
+            // FreeSync ranges also cover HDMI VRR displays; keep the widest
+            if self.vrr.is_none_or(|vrr| max - min > vrr.max - vrr.min) {
+                self.vrr = Some(VrrRange { min, max, source });
+            }
+        }
+    }
+
+    fn extended_block(&mut self, payload: &[u8]) {
+        let Some((&tag, data)) = payload.split_first() else { return };
+        match tag {
+            // Colorimetry: BT.2020 YCC and RGB
+            0x05 if !data.is_empty() => {
+                self.hdr.get_or_insert_with(HdrInfo::default).bt2020 = data[0] & 0xc0 != 0;
+            }
+            // HDR static metadata: EOTFs, descriptor types, then luminance codes
+            0x06 if !data.is_empty() => {
+                let hdr = self.hdr.get_or_insert_with(HdrInfo::default);
+                hdr.pq = data[0] & 0x04 != 0;
+                hdr.hlg = data[0] & 0x08 != 0;
+                let luminance = |code: u8| 50.0 * 2f64.powf(f64::from(code) / 32.0);
+                hdr.max_luminance = data.get(2).filter(|&&c| c > 0).map(|&c| luminance(c));
+                hdr.max_average_luminance = data.get(3).filter(|&&c| c > 0).map(|&c| luminance(c));
+                hdr.min_luminance = data
+                    .get(4)
+                    .zip(hdr.max_luminance)
+                    .map(|(&c, max)| max * (f64::from(c) / 255.0).powi(2) / 100.0);
+            }
+            _ => {}
+        }
+    }
+
+    fn displayid_block(&mut self, block: &[u8]) {
+        let version = block[1];
+        let end = (5 + usize::from(block[2])).min(BLOCK - 1);
+        let mut offset = 5;
+        while offset + 3 <= end {
+            let (tag, length) = (block[offset], usize::from(block[offset + 2]));
+            let payload = &block[(offset + 3).min(end)..(offset + 3 + length).min(end)];
+            match tag {
+                // Type I (10 kHz) and, in DisplayID 2.0, Type VII (1 kHz) timings
+                0x03 => self.modes.extend(payload.chunks_exact(20).filter_map(|t| displayid_timing(t, 10_000))),
+                0x22 => self.modes.extend(payload.chunks_exact(20).filter_map(|t| displayid_timing(t, 1_000))),
+                // Dynamic video timing range limits (2.0)
+                0x25 if payload.len() >= 9 => {
+                    let max_high = if block[offset + 1] & 0x07 >= 1 { u32::from(payload[8] & 0x03) << 8 } else { 0 };
+                    let (min, max) = (u32::from(payload[6]), max_high | u32::from(payload[7]));
+                    if min > 0 && max > min {
+                        self.refresh_range.get_or_insert((min, max));
+                        if payload[8] & 0x80 != 0 && self.vrr.is_none() {
+                            self.vrr = Some(VrrRange { min, max, source: VrrSource::DisplayId });
+                        }
+                    }
+                }
+                // Video timing range limits (1.x)
+                0x09 if version < 0x20 && payload.len() >= 12 => {
+                    let (min, max) = (u32::from(payload[10]), u32::from(payload[11]));
+                    if min > 0 && max > min {
+                        self.refresh_range.get_or_insert((min, max));
+                    }
+                }
+                _ => {}
+            }
+            offset += 3 + length;
+        }
+    }
+}
+
+fn detailed_timing(d: &[u8]) -> Option<Mode> {
+    let clock = u64::from(u16::from_le_bytes([d[0], d[1]])) * 10_000;
+    let width = u32::from(d[2]) | (u32::from(d[4] & 0xf0) << 4);
+    let h_blank = u32::from(d[3]) | (u32::from(d[4] & 0x0f) << 8);
+    let height = u32::from(d[5]) | (u32::from(d[7] & 0xf0) << 4);
+    let v_blank = u32::from(d[6]) | (u32::from(d[7] & 0x0f) << 8);
+    Mode::from_timing(clock, width, height, h_blank, v_blank, d[17] & 0x80 != 0)
+}
+
+/// A 20-byte DisplayID timing; fields are stored minus one.
+fn displayid_timing(t: &[u8], clock_unit: u64) -> Option<Mode> {
+    let word = |offset: usize| u32::from(u16::from_le_bytes([t[offset], t[offset + 1]])) + 1;
+    let clock = (u64::from(u32::from_le_bytes([t[0], t[1], t[2], 0])) + 1) * clock_unit;
+    Mode::from_timing(clock, word(4), word(12), word(6), word(14), t[3] & 0x10 != 0)
+}
+
+/// Common CTA-861 video identification codes.
+fn vic_mode(code: u8) -> Option<Mode> {
+    const VICS: &[(u8, u32, u32, f64)] = &[
+        (1, 640, 480, 60.0),
+        (2, 720, 480, 60.0),
+        (4, 1280, 720, 60.0),
+        (16, 1920, 1080, 60.0),
+        (17, 720, 576, 50.0),
+        (19, 1280, 720, 50.0),
+        (31, 1920, 1080, 50.0),
+        (32, 1920, 1080, 24.0),
+        (33, 1920, 1080, 25.0),
+        (34, 1920, 1080, 30.0),
+        (47, 1280, 720, 120.0),
+        (63, 1920, 1080, 120.0),
+        (64, 1920, 1080, 100.0),
+        (93, 3840, 2160, 24.0),
+        (94, 3840, 2160, 25.0),
+        (95, 3840, 2160, 30.0),
+        (96, 3840, 2160, 50.0),
+        (97, 3840, 2160, 60.0),
+        (98, 4096, 2160, 24.0),
+        (102, 4096, 2160, 60.0),
+        (117, 3840, 2160, 100.0),
+        (118, 3840, 2160, 120.0),
+        (218, 4096, 2160, 120.0),
+    ];
+    // Codes 129-192 are 1-64 flagged as native
+    let code = if (129..=192).contains(&code) { code & 0x7f } else { code };
+    VICS.iter().find(|v| v.0 == code).map(|&(_, width, height, refresh)| Mode::new(width, height, refresh))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4K monitor with an HDMI 2.1 CTA block: HF-VSDB version 1, 600 MHz
+    /// TMDS, FRL at 48 Gbit/s, ALLM, VRR 40-120 Hz and DSC.
+    const HDMI_FORUM: &str = "
+00 ff ff ff ff ff ff 00 10 ac 81 41 4c 45 4a 30
+1e 1e 01 04 b5 3c 22 78 3a ee 95 a3 54 4c 99 26
+0f 50 54 21 08 00 d1 c0 81 80 01 01 01 01 01 01
+01 01 01 01 01 01 4d d0 00 a0 f0 70 3e 80 30 20
+35 00 54 4f 21 00 00 1a 00 00 00 fd 00 18 4b 1e
+8c 3c 00 0a 20 20 20 20 20 20 00 00 00 fc 00 44
+45 4c 4c 20 55 32 37 32 30 51 0a 20 00 00 00 ff
+00 46 38 4b 4c 4d 30 33 0a 20 20 20 20 20 01 e3
+02 03 17 f0 44 90 61 3f 76 6d d8 5d c4 01 78 8a
+63 08 28 78 8f 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 07
+";
+
+    /// The base block alone: a 3840x2160 60 Hz preferred timing, a 24-75 Hz
+    /// range descriptor, three established and two standard timings.
+    const BASE: &str = "
+00 ff ff ff ff ff ff 00 10 ac 81 41 4c 45 4a 30
+1e 1e 01 04 b5 3c 22 78 3a ee 95 a3 54 4c 99 26
+0f 50 54 21 08 00 d1 c0 81 80 01 01 01 01 01 01
+01 01 01 01 01 01 4d d0 00 a0 f0 70 3e 80 30 20
+35 00 54 4f 21 00 00 1a 00 00 00 fd 00 18 4b 1e
+8c 3c 00 0a 20 20 20 20 20 20 00 00 00 fc 00 44
+45 4c 4c 20 55 32 37 32 30 51 0a 20 00 00 00 ff
+00 46 38 4b 4c 4d 30 33 0a 20 20 20 20 20 00 e4
+";
+
+    /// A CTA block with 4K and 1080p VICs up to 120 Hz, BT.2020
+    /// colorimetry, HDR static metadata (PQ, 672 nits peak) and a FreeSync
+    /// 48-120 Hz vendor block.
+    const CTA_HDR_FREESYNC: &str = "
+00 ff ff ff ff ff ff 00 10 ac 81 41 4c 45 4a 30
+1e 1e 01 04 b5 3c 22 78 3a ee 95 a3 54 4c 99 26
+0f 50 54 21 08 00 d1 c0 81 80 01 01 01 01 01 01
+01 01 01 01 01 01 4d d0 00 a0 f0 70 3e 80 30 20
+35 00 54 4f 21 00 00 1a 00 00 00 fd 00 18 4b 1e
+8c 3c 00 0a 20 20 20 20 20 20 00 00 00 fc 00 44
+45 4c 4c 20 55 32 37 32 30 51 0a 20 00 00 00 ff
+00 46 38 4b 4c 4d 30 33 0a 20 20 20 20 20 01 e3
+02 03 1d f0 44 90 61 3f 76 e3 05 c0 00 e6 06 05
+01 78 5a 20 68 1a 00 00 01 01 30 78 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 4c
+";
+
+    /// A DisplayID 2.0 block with a 2560x1440 165 Hz Type VII timing and
+    /// 48-165 Hz dynamic range limits that allow seamless changes. The base
+    /// block has no range descriptor.
+    const DISPLAYID: &str = "
+00 ff ff ff ff ff ff 00 10 ac 81 41 4c 45 4a 30
+1e 1e 01 04 b5 3c 22 78 3a ee 95 a3 54 4c 99 26
+0f 50 54 21 08 00 d1 c0 81 80 01 01 01 01 01 01
+01 01 01 01 01 01 4d d0 00 a0 f0 70 3e 80 30 20
+35 00 54 4f 21 00 00 1a 00 00 00 10 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 fc 00 44
+45 4c 4c 20 55 32 37 32 30 51 0a 20 00 00 00 ff
+00 46 38 4b 4c 4d 30 33 0a 20 20 20 20 20 01 e3
+70 20 23 03 00 22 00 14 83 71 0a 00 ff 09 9f 00
+00 00 00 00 9f 05 54 00 00 00 00 00 25 01 09 00
+00 00 00 00 00 30 a5 80 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 f3
+";
+
+    fn bytes(dump: &str) -> Vec<u8> {
+        dump.split_whitespace().map(|b| u8::from_str_radix(b, 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn hdmi_forum_vrr_range() {
+        let edid = Edid::parse(&bytes(HDMI_FORUM)).unwrap();
+        assert_eq!(edid.vrr, Some(VrrRange { min: 40, max: 120, source: VrrSource::HdmiVrr }));
+        assert_eq!(edid.max_refresh(None), Some(120.0));
+    }
+
+    #[test]
+    fn base_block() {
+        let edid = Edid::parse(&bytes(BASE)).unwrap();
+        assert_eq!(edid.manufacturer, "DEL");
+        assert_eq!(edid.product_code, 0x4181);
+        assert_eq!(edid.name.as_deref(), Some("DELL U2720Q"));
+        assert_eq!(edid.serial.as_deref(), Some("F8KLM03"));
+        assert_eq!(edid.year, 2020);
+        assert_eq!(edid.version, "1.4");
+        assert_eq!(edid.bits_per_color, Some(10));
+        assert_eq!(edid.preferred, Some(Mode::new(3840, 2160, 60.0)));
+        let modes: Vec<String> = edid.modes.iter().map(Mode::to_string).collect();
+        assert_eq!(
+            modes,
+            [
+                "3840x2160 @ 60 Hz",
+                "1920x1080 @ 60 Hz",
+                "1280x1024 @ 60 Hz",
+                "1024x768 @ 60 Hz",
+                "800x600 @ 60 Hz",
+                "640x480 @ 60 Hz",
+            ]
+        );
+        assert_eq!(edid.refresh_range, Some((24, 75)));
+        assert_eq!(edid.max_refresh(None), Some(60.0));
+        assert_eq!(edid.vrr, None);
+        assert_eq!(edid.hdr, None);
+    }
+
+    #[test]
+    fn cta_hdr_and_freesync() {
+        let edid = Edid::parse(&bytes(CTA_HDR_FREESYNC)).unwrap();
+        let uhd = Mode::new(3840, 2160, 60.0);
+        assert_eq!(edid.max_refresh(None), Some(120.0));
+        assert_eq!(edid.max_refresh(Some(&uhd)), Some(120.0));
+        assert!(edid.modes.contains(&Mode::new(1920, 1080, 120.0)));
+        assert!(edid.modes.contains(&uhd));
+        assert_eq!(edid.vrr, Some(VrrRange { min: 48, max: 120, source: VrrSource::FreeSync }));
+
+        let hdr = edid.hdr.unwrap();
+        assert!(hdr.pq && !hdr.hlg && hdr.bt2020);
+        assert_eq!(hdr.max_luminance.map(f64::round), Some(673.0));
+        assert_eq!(hdr.max_average_luminance.map(f64::round), Some(351.0));
+        assert_eq!(hdr.min_luminance.map(|l| (l * 100.0).round()), Some(11.0));
+    }
+
+    #[test]
+    fn displayid_range_limits() {
+        let edid = Edid::parse(&bytes(DISPLAYID)).unwrap();
+        let qhd = Mode::new(2560, 1440, 165.0);
+        assert!(edid.modes.contains(&qhd));
+        assert_eq!(edid.max_refresh(None), Some(165.0));
+        assert_eq!(edid.max_refresh(Some(&edid.preferred.unwrap())), Some(60.0));
+        assert_eq!(edid.refresh_range, Some((48, 165)));
+        assert_eq!(edid.vrr, Some(VrrRange { min: 48, max: 165, source: VrrSource::DisplayId }));
+        assert_eq!(edid.hdr, None);
+    }
+}
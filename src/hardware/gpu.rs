@@ -1,45 +1,404 @@
+use crate::hardware::pci::{PciId, Vendor};
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+#[cfg(target_os = "windows")]
+use crate::utils::registry::{self, RegPath, RegValue};
+#[cfg(target_os = "windows")]
+use crate::hardware::pci::windows_pnp;
+#[cfg(target_os = "windows")]
+use std::collections::HashMap;
+#[cfg(target_os = "windows")]
+use std::process::Command;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Where an adapter was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GpuSource {
+    /// The display adapter class key in the registry
+    Registry,
+    Dxdiag,
+    /// `/sys/class/drm`
+    Drm,
+    Nvml,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpuInfo {
     pub name: String,
-    pub vendor: String,
+    pub vendor: Vendor,
+    /// Dedicated video memory in bytes, 0 when unknown.
     pub memory: u64,
-    pub driver_version: String,
+    pub driver_version: Option<String>,
+    /// `YYYY-MM-DD` when the source's date could be read.
+    pub driver_date: Option<String>,
+    pub pci: Option<PciId>,
+    /// Drives the primary display on Windows, or was the boot VGA device on
+    /// Linux.
+    pub primary: bool,
+    pub sources: Vec<GpuSource>,
 }
 
+impl GpuInfo {
+    fn new(name: String, pci: Option<PciId>, source: GpuSource) -> Self {
+        GpuInfo {
+            vendor: pci.map_or_else(|| Vendor::from_name(&name), |p| Vendor::from_id(p.vendor)),
+            name,
+            memory: 0,
+            driver_version: None,
+            driver_date: None,
+            pci,
+            primary: false,
+            sources: vec![source],
+        }
+    }
+
+    fn same_adapter(&self, other: &GpuInfo) -> bool {
+        match (&self.pci, &other.pci) {
+            (Some(a), Some(b)) => a.same_device(b),
+            _ => self.name.eq_ignore_ascii_case(&other.name),
+        }
+    }
+
+    /// Fills in what this adapter does not know yet from another source's
+    /// view of the same adapter.
+    fn merge(&mut self, other: GpuInfo) {
+        // DRM only has IDs to build a name from
+        if self.sources == [GpuSource::Drm] {
+            self.name = other.name;
+            self.vendor = other.vendor;
+        }
+        if self.memory == 0 {
+            self.memory = other.memory;
+        }
+        self.driver_version = self.driver_version.take().or(other.driver_version);
+        self.driver_date = self.driver_date.take().or(other.driver_date);
+        self.pci = self.pci.or(other.pci);
+        self.primary |= other.primary;
+        self.sources.extend(other.sources);
+    }
+}
+
+/// Adds an adapter, or merges it into one another source already reported.
+/// Each listed adapter takes one adapter per source, so two identical cards
+/// stay two entries.
+fn merge_into(gpus: &mut Vec<GpuInfo>, gpu: GpuInfo) {
+    let source = gpu.sources[0];
+    match gpus.iter_mut().find(|g| !g.sources.contains(&source) && g.same_adapter(&gpu)) {
+        Some(existing) => existing.merge(gpu),
+        None => gpus.push(gpu),
+    }
+}
+
+/// Display adapters from every available source, merged.
 pub fn detect() -> Vec<GpuInfo> {
-    let mut gpus = Vec::new();
-    
-    // Try NVML for NVIDIA GPUs
-    if let Ok(nvml) = nvml_wrapper::Nvml::init() {
-        if let Ok(device_count) = nvml.device_count() {
-            for i in 0..device_count {
-                if let Ok(device) = nvml.device_by_index(i) {
-                    let name = device.name().unwrap_or_else(|_| "Unknown NVIDIA GPU".to_string());
-                    let memory = device.memory_info().map(|m| m.total).unwrap_or(0);
-                    let driver = nvml.sys_driver_version().unwrap_or_else(|_| "Unknown".to_string());
-                    
-                    gpus.push(GpuInfo {
-                        name,
-                        vendor: "NVIDIA".to_string(),
-                        memory,
-                        driver_version: driver,
-                    });
-                }
+    let mut gpus = platform_adapters();
+    for gpu in nvml_adapters() {
+        merge_into(&mut gpus, gpu);
+    }
+
+    mark_primary(&mut gpus);
+    if let [only] = gpus.as_mut_slice() {
+        only.primary = true;
+    }
+    gpus
+}
+
+/// [`detect`], run once per process and shared by the GPU checks.
+pub fn adapters() -> &'static [GpuInfo] {
+    static ADAPTERS: OnceLock<Vec<GpuInfo>> = OnceLock::new();
+    ADAPTERS.get_or_init(detect)
+}
+
+fn nvml_adapters() -> Vec<GpuInfo> {
+    let Ok(nvml) = nvml_wrapper::Nvml::init() else { return Vec::new() };
+    let driver = nvml.sys_driver_version().ok();
+    let count = nvml.device_count().unwrap_or(0);
+
+    (0..count)
+        .filter_map(|i| nvml.device_by_index(i).ok())
+        .map(|device| {
+            let pci = device.pci_info().ok().map(|info| PciId {
+                vendor: info.pci_device_id as u16,
+                device: (info.pci_device_id >> 16) as u16,
+                subsystem: info.pci_sub_system_id,
+                revision: None,
+            });
+            let name = device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string());
+            let mut gpu = GpuInfo::new(name, pci, GpuSource::Nvml);
+            gpu.vendor = Vendor::Nvidia;
+            gpu.memory = device.memory_info().map(|m| m.total).unwrap_or(0);
+            gpu.driver_version = driver.clone();
+            gpu
+        })
+        .collect()
+}
+
+/// `m-d-yyyy` or `m/d/yyyy`, optionally followed by a time, as `yyyy-mm-dd`.
+#[cfg(target_os = "windows")]
+pub(super) fn normalize_date(date: &str) -> String {
+    let day = date.split_whitespace().next().unwrap_or_default();
+    let parts: Vec<u32> = day.split(['-', '/']).filter_map(|p| p.parse().ok()).collect();
+    match parts[..] {
+        [month, day, year] if (1..=12).contains(&month) && (1..=31).contains(&day) && year > 1900 => {
+            format!("{:04}-{:02}-{:02}", year, month, day)
+        }
+        _ => date.trim().to_string(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_adapters() -> Vec<GpuInfo> {
+    let gpus = registry_adapters();
+    if !gpus.is_empty() {
+        return gpus;
+    }
+    // The class key can be unreadable under restricted accounts; dxdiag
+    // takes several seconds, so it is only the fallback
+    dxdiag_adapters()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_adapters() -> Vec<GpuInfo> {
+    drm_adapters(Path::new("/sys/class/drm"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn platform_adapters() -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+/// The display adapter device class.
+#[cfg(target_os = "windows")]
+const DISPLAY_CLASS_GUID: &str = "{4d36e968-e325-11ce-bfc1-08002be10318}";
+#[cfg(target_os = "windows")]
+const DISPLAY_CLASS: RegPath = RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\Class\\{4d36e968-e325-11ce-bfc1-08002be10318}");
+
+#[cfg(target_os = "windows")]
+fn registry_adapters() -> Vec<GpuInfo> {
+    let Ok(subkeys) = registry::enum_subkeys(&DISPLAY_CLASS) else { return Vec::new() };
+    let instances = pci_driver_instances();
+
+    // Driver instances are 0000, 0001, ...; skip Properties and the like.
+    // Adapters that were removed or swapped out keep their driver key, so
+    // only those whose device has a devnode are present.
+    subkeys
+        .iter()
+        .filter(|name| name.len() == 4 && name.chars().all(|c| c.is_ascii_digit()))
+        .filter(|name| {
+            let driver = format!("{}\\{}", DISPLAY_CLASS_GUID, name).to_ascii_lowercase();
+            instances.get(&driver).is_some_and(|id| windows_pnp::locate(id).is_some())
+        })
+        .filter_map(|name| registry_adapter(&DISPLAY_CLASS.join(name)))
+        .collect()
+}
+
+/// Device instance IDs under `Enum\PCI` by their lowercased `Driver`
+/// value, which names the class key instance, e.g.
+/// `{4d36e968-e325-11ce-bfc1-08002be10318}\0000`.
+#[cfg(target_os = "windows")]
+fn pci_driver_instances() -> HashMap<String, String> {
+    let root = RegPath::hklm("SYSTEM\\CurrentControlSet\\Enum\\PCI");
+    let mut instances = HashMap::new();
+    for model in registry::enum_subkeys(&root).unwrap_or_default() {
+        for instance in registry::enum_subkeys(&root.join(&model)).unwrap_or_default() {
+            if let Ok(driver) = registry::read_string(&root.join(&model).join(&instance), "Driver") {
+                instances.insert(driver.to_ascii_lowercase(), format!("PCI\\{}\\{}", model, instance));
             }
         }
     }
-    
-    // Fallback for other GPUs using sysinfo or registry
-    if gpus.is_empty() {
-        gpus.push(GpuInfo {
-            name: "Unknown GPU".to_string(),
-            vendor: "Unknown".to_string(),
-            memory: 0,
-            driver_version: "Unknown".to_string(),
+    instances
+}
+
+#[cfg(target_os = "windows")]
+fn registry_adapter(key: &RegPath) -> Option<GpuInfo> {
+    let name = registry::read_string(key, "DriverDesc").ok()?;
+    // Software adapters such as the Basic Render Driver are not PCI devices
+    let matching = registry::read_string(key, "MatchingDeviceId").ok()?;
+    if !matching.to_ascii_lowercase().starts_with("pci\\") {
+        return None;
+    }
+
+    let mut gpu = GpuInfo::new(name, PciId::from_hardware_id(&matching), GpuSource::Registry);
+    // The DWORD value caps at 4 GB; newer drivers also write the QWORD one
+    gpu.memory = ["HardwareInformation.qwMemorySize", "HardwareInformation.MemorySize"]
+        .iter()
+        .find_map(|value| registry::read_value(key, value).ok().and_then(|v| memory_size(&v)))
+        .unwrap_or(0);
+    gpu.driver_version = registry::read_string(key, "DriverVersion").ok();
+    gpu.driver_date = registry::read_string(key, "DriverDate").ok().map(|d| normalize_date(&d));
+    Some(gpu)
+}
+
+/// Memory sizes are DWORDs, QWORDs or raw little-endian bytes depending on
+/// the driver.
+#[cfg(target_os = "windows")]
+fn memory_size(value: &RegValue) -> Option<u64> {
+    let size = match value {
+        RegValue::Dword(v) => Some(*v as u64),
+        RegValue::Qword(v) => Some(*v),
+        RegValue::Binary(bytes) if bytes.len() >= 8 => {
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(&bytes[..8]);
+            Some(u64::from_le_bytes(raw))
+        }
+        RegValue::Binary(bytes) if bytes.len() >= 4 => {
+            Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64)
+        }
+        _ => None,
+    };
+    size.filter(|&size| size > 0)
+}
+
+#[cfg(target_os = "windows")]
+fn dxdiag_adapters() -> Vec<GpuInfo> {
+    let path = std::env::temp_dir().join(format!("apex_dxdiag_{}.xml", std::process::id()));
+    let written = Command::new("dxdiag").arg("/x").arg(&path).status().is_ok_and(|s| s.success());
+    let bytes = if written { fs::read(&path).unwrap_or_default() } else { Vec::new() };
+    let _ = fs::remove_file(&path);
+
+    // Some Windows versions write the report as UTF-16
+    let xml = match bytes.strip_prefix(&[0xFF, 0xFE]) {
+        Some(wide) => String::from_utf16_lossy(&wide.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect::<Vec<_>>()),
+        None => String::from_utf8_lossy(&bytes).into_owned(),
+    };
+    parse_dxdiag(&xml)
+}
+
+/// Text of the first `<tag>` in an XML fragment, with entities decoded.
+#[cfg(target_os = "windows")]
+fn xml_tag(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    let text = xml[start..end]
+        .trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    Some(text).filter(|t| !t.is_empty() && t != "n/a" && t != "Unknown")
+}
+
+pub(super) fn parse_hex(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+    u32::from_str_radix(digits, 16).ok()
+}
+
+/// Reads the display devices from a `dxdiag /x` report. dxdiag lists an
+/// adapter once per connected monitor; repeats are dropped.
+#[cfg(target_os = "windows")]
+pub fn parse_dxdiag(xml: &str) -> Vec<GpuInfo> {
+    let mut gpus: Vec<GpuInfo> = Vec::new();
+
+    for block in xml.split("<DisplayDevice>").skip(1) {
+        let block = block.split("</DisplayDevice>").next().unwrap_or(block);
+        let Some(name) = xml_tag(block, "CardName") else { continue };
+
+        let ids = xml_tag(block, "VendorID")
+            .and_then(|v| parse_hex(&v))
+            .zip(xml_tag(block, "DeviceID").and_then(|d| parse_hex(&d)));
+        let pci = ids.map(|(vendor, device)| PciId {
+            vendor: vendor as u16,
+            device: device as u16,
+            subsystem: xml_tag(block, "SubSys").and_then(|s| parse_hex(&s)),
+            revision: xml_tag(block, "Revision").and_then(|r| parse_hex(&r)).map(|r| r as u8),
         });
+
+        let mut gpu = GpuInfo::new(name, pci, GpuSource::Dxdiag);
+        // e.g. "24156 MB"
+        gpu.memory = xml_tag(block, "DedicatedMemory")
+            .and_then(|m| m.split_whitespace().next().and_then(|n| n.parse::<f64>().ok()))
+            .map_or(0, |mb| (mb * 1024.0 * 1024.0) as u64);
+        gpu.driver_version = xml_tag(block, "DriverVersion");
+        gpu.driver_date = xml_tag(block, "DriverDate").map(|d| normalize_date(&d));
+
+        if !gpus.iter().any(|g| g.name == gpu.name && g.pci == gpu.pci) {
+            gpus.push(gpu);
+        }
     }
-    
     gpus
 }
+
+/// Cards under a DRM class directory, in card order. Connectors
+/// (`card0-HDMI-A-1`) and render nodes are skipped, as are devices without
+/// PCI IDs such as `simpledrm`.
+#[cfg(target_os = "linux")]
+fn drm_adapters(root: &Path) -> Vec<GpuInfo> {
+    let Ok(entries) = fs::read_dir(root) else { return Vec::new() };
+
+    let mut cards: Vec<(u32, std::path::PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let index = entry.file_name().to_str()?.strip_prefix("card")?.parse().ok()?;
+            Some((index, entry.path().join("device")))
+        })
+        .collect();
+    cards.sort();
+
+    cards.iter().filter_map(|(_, device)| drm_adapter(device)).collect()
+}
+
+#[cfg(target_os = "linux")]
+fn drm_adapter(device: &Path) -> Option<GpuInfo> {
+    let read = |name: &str| fs::read_to_string(device.join(name)).ok().map(|s| s.trim().to_string());
+    let hex = |name: &str| read(name).and_then(|v| parse_hex(&v));
+
+    let pci = PciId {
+        vendor: hex("vendor")? as u16,
+        device: hex("device")? as u16,
+        subsystem: hex("subsystem_device").zip(hex("subsystem_vendor")).map(|(device, vendor)| device << 16 | vendor),
+        revision: hex("revision").map(|r| r as u8),
+    };
+    let driver = fs::read_link(device.join("driver"))
+        .ok()
+        .and_then(|link| link.file_name().map(|n| n.to_string_lossy().into_owned()));
+
+    let vendor = Vendor::from_id(pci.vendor);
+    let name = format!("{} GPU [{:04x}:{:04x}]", vendor, pci.vendor, pci.device);
+    let mut gpu = GpuInfo::new(name, Some(pci), GpuSource::Drm);
+    // Only amdgpu reports its VRAM here
+    gpu.memory = read("mem_info_vram_total").and_then(|v| v.parse().ok()).unwrap_or(0);
+    // In-tree drivers such as amdgpu and i915 have no version of their own
+    gpu.driver_version = driver.and_then(|d| {
+        let version = fs::read_to_string(Path::new("/sys/module").join(&d).join("version")).ok()?;
+        Some(format!("{} {}", d, version.trim()))
+    });
+    gpu.primary = read("boot_vga").as_deref() == Some("1");
+    Some(gpu)
+}
+
+/// Marks the adapter driving the primary display, as reported by
+/// `EnumDisplayDevices`.
+#[cfg(target_os = "windows")]
+fn mark_primary(gpus: &mut [GpuInfo]) {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{EnumDisplayDevicesW, DISPLAY_DEVICEW, DISPLAY_DEVICE_PRIMARY_DEVICE};
+
+    let mut index = 0;
+    loop {
+        let mut device = DISPLAY_DEVICEW { cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32, ..Default::default() };
+        if !unsafe { EnumDisplayDevicesW(PCWSTR::null(), index, &mut device, 0) }.as_bool() {
+            return;
+        }
+        index += 1;
+        if device.StateFlags & DISPLAY_DEVICE_PRIMARY_DEVICE == 0 {
+            continue;
+        }
+
+        let end = device.DeviceID.iter().position(|&c| c == 0).unwrap_or(device.DeviceID.len());
+        let Some(id) = PciId::from_hardware_id(&String::from_utf16_lossy(&device.DeviceID[..end])) else { return };
+        if let Some(gpu) = gpus.iter_mut().find(|g| g.pci.is_some_and(|p| p.same_device(&id))) {
+            gpu.primary = true;
+        }
+        return;
+    }
+}
+
+/// DRM already reports the boot VGA device.
+#[cfg(not(target_os = "windows"))]
+fn mark_primary(_gpus: &mut [GpuInfo]) {}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
 
+use super::smbios::{self, MemoryModule};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryInfo {
     pub total: u64,
@@ -8,16 +10,21 @@ pub struct MemoryInfo {
     pub used: u64,
     pub swap_total: u64,
     pub swap_used: u64,
+    /// Installed modules from SMBIOS; empty when the table is unreadable.
+    pub modules: Vec<MemoryModule>,
+    pub slots: usize,
 }
 
-pub fn detect() -> MemoryInfo {
-    let sys = System::new_all();
-    
+pub fn detect(sys: &System) -> MemoryInfo {
+    let table = smbios::table().ok();
+
     MemoryInfo {
         total: sys.total_memory(),
         available: sys.available_memory(),
         used: sys.used_memory(),
         swap_total: sys.total_swap(),
         swap_used: sys.used_swap(),
+        modules: table.map(|t| t.memory_modules()).unwrap_or_default(),
+        slots: table.map(|t| t.memory_slots()).unwrap_or(0),
     }
 }
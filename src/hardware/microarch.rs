@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::pci::Vendor;
+
+/// CPU core microarchitecture, named after the client generation that
+/// introduced it; server parts map to their client counterpart. Variants of
+/// one vendor are ordered oldest first, so `>=` means "this generation or
+/// newer"; use [`Uarch::at_least`] to also compare vendors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Uarch {
+    Zen,
+    ZenPlus,
+    Zen2,
+    Zen3,
+    Zen3Plus,
+    Zen4,
+    Zen5,
+    Skylake,
+    KabyLake,
+    CoffeeLake,
+    CometLake,
+    IceLake,
+    TigerLake,
+    RocketLake,
+    AlderLake,
+    RaptorLake,
+    MeteorLake,
+    LunarLake,
+    ArrowLake,
+}
+
+impl Uarch {
+    pub fn vendor(self) -> Vendor {
+        if self <= Uarch::Zen5 { Vendor::Amd } else { Vendor::Intel }
+    }
+
+    /// Same vendor and this generation or newer.
+    pub fn at_least(self, min: Uarch) -> bool {
+        self.vendor() == min.vendor() && self >= min
+    }
+}
+
+impl fmt::Display for Uarch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Uarch::Zen => "Zen",
+            Uarch::ZenPlus => "Zen+",
+            Uarch::Zen2 => "Zen 2",
+            Uarch::Zen3 => "Zen 3",
+            Uarch::Zen3Plus => "Zen 3+",
+            Uarch::Zen4 => "Zen 4",
+            Uarch::Zen5 => "Zen 5",
+            Uarch::Skylake => "Skylake",
+            Uarch::KabyLake => "Kaby Lake",
+            Uarch::CoffeeLake => "Coffee Lake",
+            Uarch::CometLake => "Comet Lake",
+            Uarch::IceLake => "Ice Lake",
+            Uarch::TigerLake => "Tiger Lake",
+            Uarch::RocketLake => "Rocket Lake",
+            Uarch::AlderLake => "Alder Lake",
+            Uarch::RaptorLake => "Raptor Lake",
+            Uarch::MeteorLake => "Meteor Lake",
+            Uarch::LunarLake => "Lunar Lake",
+            Uarch::ArrowLake => "Arrow Lake",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Market segment, as far as family and model tell it apart. Desktop and
+/// mobile APUs share model ranges and are counted as mobile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Segment {
+    #[default]
+    Desktop,
+    Mobile,
+    Server,
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Segment::Desktop => "desktop",
+            Segment::Mobile => "mobile",
+            Segment::Server => "server and workstation",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Vendor features that depend on the microarchitecture rather than on a
+/// CPUID bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Capability {
+    PrecisionBoostOverdrive,
+    CurveOptimizer,
+    SpeedShift,
+    /// Only on Core i9 parts of these generations.
+    ThermalVelocityBoost,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::PrecisionBoostOverdrive => "Precision Boost Overdrive",
+            Capability::CurveOptimizer => "Curve Optimizer",
+            Capability::SpeedShift => "Speed Shift",
+            Capability::ThermalVelocityBoost => "Thermal Velocity Boost",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// First generation with each capability, and the segments that expose
+/// it; later generations of the same vendor have it too. EPYC, Threadripper
+/// Pro and mobile Ryzen have no PBO or Curve Optimizer.
+const CAPABILITIES: &[(Capability, Uarch, &[Segment])] = &[
+    (Capability::PrecisionBoostOverdrive, Uarch::ZenPlus, &[Segment::Desktop]),
+    (Capability::CurveOptimizer, Uarch::Zen3, &[Segment::Desktop]),
+    (Capability::SpeedShift, Uarch::Skylake, &[Segment::Desktop, Segment::Mobile, Segment::Server]),
+    (Capability::ThermalVelocityBoost, Uarch::CoffeeLake, &[Segment::Desktop, Segment::Mobile]),
+];
+
+impl Capability {
+    /// Oldest microarchitecture with the capability.
+    pub fn since(self) -> Uarch {
+        CAPABILITIES.iter().find(|(c, _, _)| *c == self).map(|(_, since, _)| *since).expect("every capability has a row")
+    }
+}
+
+/// What a CPU signature was identified as.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Microarch {
+    pub uarch: Uarch,
+    pub codename: String,
+    /// Product line, e.g. "Ryzen 5000" or "Core 12th gen".
+    pub generation: String,
+    #[serde(default)]
+    pub segment: Segment,
+    pub capabilities: Vec<Capability>,
+}
+
+impl Microarch {
+    pub fn has(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
+
+impl fmt::Display for Microarch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}, {})", self.uarch, self.codename, self.generation)
+    }
+}
+
+struct Row {
+    vendor: Vendor,
+    family: u32,
+    models: (u32, u32),
+    steppings: (u32, u32),
+    segment: Segment,
+    uarch: Uarch,
+    codename: &'static str,
+    generation: &'static str,
+}
+
+const fn row(vendor: Vendor, family: u32, models: (u32, u32), uarch: Uarch, codename: &'static str, generation: &'static str) -> Row {
+    Row { vendor, family, models, steppings: (0, 0xf), segment: Segment::Desktop, uarch, codename, generation }
+}
+
+impl Row {
+    const fn steppings(mut self, first: u32, last: u32) -> Self {
+        self.steppings = (first, last);
+        self
+    }
+
+    const fn mobile(mut self) -> Self {
+        self.segment = Segment::Mobile;
+        self
+    }
+
+    const fn server(mut self) -> Self {
+        self.segment = Segment::Server;
+        self
+    }
+}
+
+const AMD: Vendor = Vendor::Amd;
+const INTEL: Vendor = Vendor::Intel;
+
+/// Display family and model ranges (as decoded from CPUID leaf 1) per
+/// microarchitecture. The first matching row wins.
+const MICROARCHS: &[Row] = &[
+    row(AMD, 0x17, (0x01, 0x01), Uarch::Zen, "Summit Ridge", "Ryzen 1000"),
+    row(AMD, 0x17, (0x08, 0x08), Uarch::ZenPlus, "Pinnacle Ridge", "Ryzen 2000"),
+    row(AMD, 0x17, (0x11, 0x11), Uarch::Zen, "Raven Ridge", "Ryzen 2000 APU").mobile(),
+    row(AMD, 0x17, (0x18, 0x18), Uarch::ZenPlus, "Picasso", "Ryzen 3000 APU").mobile(),
+    row(AMD, 0x17, (0x20, 0x20), Uarch::Zen, "Dali", "Athlon 3000").mobile(),
+    row(AMD, 0x17, (0x31, 0x31), Uarch::Zen2, "Rome / Castle Peak", "EPYC 7002 / Threadripper 3000").server(),
+    row(AMD, 0x17, (0x60, 0x6f), Uarch::Zen2, "Renoir", "Ryzen 4000").mobile(),
+    row(AMD, 0x17, (0x71, 0x71), Uarch::Zen2, "Matisse", "Ryzen 3000"),
+    row(AMD, 0x17, (0x90, 0x9f), Uarch::Zen2, "Van Gogh", "Steam Deck APU").mobile(),
+    row(AMD, 0x17, (0xa0, 0xaf), Uarch::Zen2, "Mendocino", "Ryzen 7020").mobile(),
+    row(AMD, 0x19, (0x00, 0x0f), Uarch::Zen3, "Milan", "EPYC 7003 / Threadripper 5000").server(),
+    row(AMD, 0x19, (0x10, 0x1f), Uarch::Zen4, "Genoa", "EPYC 9004 / Threadripper 7000").server(),
+    row(AMD, 0x19, (0x20, 0x2f), Uarch::Zen3, "Vermeer", "Ryzen 5000"),
+    row(AMD, 0x19, (0x40, 0x4f), Uarch::Zen3Plus, "Rembrandt", "Ryzen 6000").mobile(),
+    row(AMD, 0x19, (0x50, 0x5f), Uarch::Zen3, "Cezanne", "Ryzen 5000 APU").mobile(),
+    row(AMD, 0x19, (0x60, 0x6f), Uarch::Zen4, "Raphael", "Ryzen 7000"),
+    row(AMD, 0x19, (0x70, 0x7f), Uarch::Zen4, "Phoenix", "Ryzen 7040 / 8000").mobile(),
+    row(AMD, 0x19, (0xa0, 0xaf), Uarch::Zen4, "Bergamo / Siena", "EPYC 8004 / 9004").server(),
+    row(AMD, 0x1a, (0x00, 0x1f), Uarch::Zen5, "Turin", "EPYC 9005").server(),
+    row(AMD, 0x1a, (0x20, 0x2f), Uarch::Zen5, "Strix Point", "Ryzen AI 300").mobile(),
+    row(AMD, 0x1a, (0x40, 0x4f), Uarch::Zen5, "Granite Ridge", "Ryzen 9000"),
+    row(AMD, 0x1a, (0x60, 0x7f), Uarch::Zen5, "Krackan / Strix Halo", "Ryzen AI 300").mobile(),
+    row(INTEL, 6, (0x4e, 0x4e), Uarch::Skylake, "Skylake-U/Y", "Core 6th gen").mobile(),
+    row(INTEL, 6, (0x5e, 0x5e), Uarch::Skylake, "Skylake-S/H", "Core 6th gen"),
+    row(INTEL, 6, (0x55, 0x55), Uarch::Skylake, "Skylake-SP / Cascade Lake", "Xeon Scalable 1st/2nd gen").server(),
+    row(INTEL, 6, (0x8e, 0x8e), Uarch::KabyLake, "Kaby Lake-U/Y", "Core 7th gen").steppings(0x9, 0x9).mobile(),
+    row(INTEL, 6, (0x8e, 0x8e), Uarch::CoffeeLake, "Kaby Lake-R / Whiskey Lake", "Core 8th gen").steppings(0xa, 0xb).mobile(),
+    row(INTEL, 6, (0x8e, 0x8e), Uarch::CometLake, "Comet Lake-U / Amber Lake", "Core 10th gen").steppings(0xc, 0xc).mobile(),
+    row(INTEL, 6, (0x9e, 0x9e), Uarch::KabyLake, "Kaby Lake-S/H", "Core 7th gen").steppings(0x9, 0x9),
+    row(INTEL, 6, (0x9e, 0x9e), Uarch::CoffeeLake, "Coffee Lake", "Core 8th/9th gen").steppings(0xa, 0xd),
+    row(INTEL, 6, (0xa5, 0xa6), Uarch::CometLake, "Comet Lake", "Core 10th gen"),
+    row(INTEL, 6, (0x7d, 0x7e), Uarch::IceLake, "Ice Lake", "Core 10th gen").mobile(),
+    row(INTEL, 6, (0x6a, 0x6c), Uarch::IceLake, "Ice Lake-SP", "Xeon Scalable 3rd gen").server(),
+    row(INTEL, 6, (0x8c, 0x8d), Uarch::TigerLake, "Tiger Lake", "Core 11th gen").mobile(),
+    row(INTEL, 6, (0xa7, 0xa7), Uarch::RocketLake, "Rocket Lake", "Core 11th gen"),
+    row(INTEL, 6, (0x97, 0x97), Uarch::AlderLake, "Alder Lake-S", "Core 12th gen"),
+    row(INTEL, 6, (0x9a, 0x9a), Uarch::AlderLake, "Alder Lake-P", "Core 12th gen").mobile(),
+    row(INTEL, 6, (0xbe, 0xbe), Uarch::AlderLake, "Alder Lake-N", "Intel N-series").mobile(),
+    row(INTEL, 6, (0x8f, 0x8f), Uarch::AlderLake, "Sapphire Rapids", "Xeon Scalable 4th gen").server(),
+    row(INTEL, 6, (0xb7, 0xb7), Uarch::RaptorLake, "Raptor Lake-S", "Core 13th/14th gen"),
+    row(INTEL, 6, (0xba, 0xba), Uarch::RaptorLake, "Raptor Lake-P", "Core 13th gen").mobile(),
+    row(INTEL, 6, (0xbf, 0xbf), Uarch::RaptorLake, "Raptor Lake-S", "Core 13th/14th gen"),
+    row(INTEL, 6, (0xcf, 0xcf), Uarch::RaptorLake, "Emerald Rapids", "Xeon Scalable 5th gen").server(),
+    row(INTEL, 6, (0xaa, 0xac), Uarch::MeteorLake, "Meteor Lake", "Core Ultra Series 1").mobile(),
+    row(INTEL, 6, (0xbd, 0xbd), Uarch::LunarLake, "Lunar Lake", "Core Ultra 200V").mobile(),
+    row(INTEL, 6, (0xb5, 0xb5), Uarch::ArrowLake, "Arrow Lake-U", "Core Ultra 200U").mobile(),
+    row(INTEL, 6, (0xc5, 0xc6), Uarch::ArrowLake, "Arrow Lake", "Core Ultra 200S/H"),
+];
+
+/// Maps a CPUID vendor string to the vendor.
+pub fn vendor(cpuid_vendor: &str) -> Vendor {
+    match cpuid_vendor {
+        "GenuineIntel" => Vendor::Intel,
+        "AuthenticAMD" => Vendor::Amd,
+        _ => Vendor::Unknown,
+    }
+}
+
+/// Identifies a CPU by vendor and display family, model and stepping.
+pub fn identify(vendor: Vendor, family: u32, model: u32, stepping: u32) -> Option<Microarch> {
+    let row = MICROARCHS.iter().find(|r| {
+        r.vendor == vendor
+            && r.family == family
+            && (r.models.0..=r.models.1).contains(&model)
+            && (r.steppings.0..=r.steppings.1).contains(&stepping)
+    })?;
+
+    Some(Microarch {
+        uarch: row.uarch,
+        codename: row.codename.to_string(),
+        generation: row.generation.to_string(),
+        segment: row.segment,
+        capabilities: CAPABILITIES
+            .iter()
+            .filter(|(_, since, segments)| row.uarch.at_least(*since) && segments.contains(&row.segment))
+            .map(|(c, _, _)| *c)
+            .collect(),
+    })
+}
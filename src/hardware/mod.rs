@@ -1,31 +1,101 @@
 pub mod cpu;
+pub mod cpuid;
+pub mod disk;
+pub mod display;
+pub mod edid;
 pub mod gpu;
+pub mod microarch;
+pub mod pci;
+pub mod smart;
+pub mod smbios;
 pub mod memory;
 pub mod storage;
 pub mod network;
 pub mod system;
 
 use serde::{Deserialize, Serialize};
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HardwareInfo {
     pub cpu: cpu::CpuInfo,
     pub gpu: Vec<gpu::GpuInfo>,
+    pub displays: Vec<display::DisplayInfo>,
     pub memory: memory::MemoryInfo,
     pub storage: Vec<storage::StorageInfo>,
+    pub disks: Vec<disk::PhysicalDisk>,
     pub network: Vec<network::NetworkInfo>,
+    pub pci: Vec<pci::PciDevice>,
     pub system: system::SystemInfo,
 }
 
 impl HardwareInfo {
     pub fn detect() -> Self {
+        let sys = snapshot();
         Self {
-            cpu: cpu::detect(),
-            gpu: gpu::detect(),
-            memory: memory::detect(),
+            cpu: cpu::info_from(&sys).clone(),
+            gpu: gpu::adapters().to_vec(),
+            displays: display::displays().to_vec(),
+            memory: memory::detect(&sys),
             storage: storage::detect(),
-            network: network::detect(),
+            disks: disk::disks().to_vec(),
+            network: network::adapters().to_vec(),
+            pci: pci::devices().to_vec(),
             system: system::detect(),
         }
     }
+
+    /// One line per component, for reports that show the inventory as text.
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+        let mut lines = Vec::new();
+
+        let cpu = &self.cpu;
+        let microarch = cpu.microarch.as_ref().map(|m| format!(", {}", m)).unwrap_or_default();
+        lines.push(("CPU", format!("{} ({} cores, {} threads{})", cpu.name, cpu.cores, cpu.threads, microarch)));
+        lines.push(("Memory", format!("{:.1} GB", self.memory.total as f64 / GB)));
+        for module in &self.memory.modules {
+            let speed = module.configured_speed.map(|s| format!(" {} MT/s", s)).unwrap_or_default();
+            lines.push(("Memory Module", format!("{}: {} GB {}{}", module.slot, module.size >> 30, module.memory_type, speed)));
+        }
+        if let Some(board) = &self.system.board {
+            lines.push(("Board", format!("{} {}", board.manufacturer, board.product)));
+        }
+        if let Some(bios) = &self.system.bios {
+            lines.push(("BIOS", format!("{} {} ({})", bios.vendor, bios.version, bios.release_date)));
+        }
+        lines.push(("OS", format!("{} {} (kernel {})", self.system.os_name, self.system.os_version, self.system.kernel_version)));
+        for gpu in &self.gpu {
+            let driver = gpu.driver_version.as_ref().map(|v| format!(", driver {}", v)).unwrap_or_default();
+            lines.push(("GPU", format!("{} ({:.1} GB{})", gpu.name, gpu.memory as f64 / GB, driver)));
+        }
+        for display in &self.displays {
+            let mode = display.current_mode.map(|m| format!(" ({})", m)).unwrap_or_default();
+            lines.push(("Display", format!("{}{}", display.name, mode)));
+        }
+        for disk in &self.disks {
+            lines.push(("Disk", format!("{} ({} {}, {:.1} GB)", disk.model, disk.bus, disk.media, disk.size as f64 / GB)));
+        }
+        for adapter in self.network.iter().filter(|a| a.is_physical()) {
+            let name = if adapter.description.is_empty() { &adapter.name } else { &adapter.description };
+            let speed = adapter.speed.map(|s| format!(", {} Mbps", s / 1_000_000)).unwrap_or_default();
+            lines.push(("Network", format!("{} ({}{})", name, adapter.adapter_type, speed)));
+        }
+        for device in self.pci.iter().filter(|d| !d.is_bridge()) {
+            let link = device.link.map(|l| format!(", {}", l)).unwrap_or_default();
+            let class = device.class_name.as_deref().unwrap_or("PCI device");
+            lines.push(("PCI", format!("{} {} ({}{})", device.address, device.name(), class, link)));
+        }
+        lines
+    }
+}
+
+/// The sysinfo state the detectors read: the CPU list and memory, without
+/// the processes, disks and users `System::new_all` loads.
+pub(crate) fn snapshot() -> System {
+    System::new_with_specifics(
+        RefreshKind::new()
+            .with_cpu(CpuRefreshKind::new().with_frequency())
+            .with_memory(MemoryRefreshKind::everything()),
+    )
 }
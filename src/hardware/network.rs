@@ -1,22 +1,424 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::OnceLock;
 use sysinfo::Networks;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdapterType {
+    Ethernet,
+    WiFi,
+    /// VPN, Hyper-V, VMware, container and other software adapters.
+    Virtual,
+    Loopback,
+    Other,
+}
+
+impl fmt::Display for AdapterType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AdapterType::Ethernet => "Ethernet",
+            AdapterType::WiFi => "Wi-Fi",
+            AdapterType::Virtual => "Virtual",
+            AdapterType::Loopback => "Loopback",
+            AdapterType::Other => "Other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInfo {
     pub name: String,
+    /// Adapter model as the driver names it.
+    pub description: String,
+    pub mac: Option<String>,
+    pub adapter_type: AdapterType,
+    /// Operationally up, i.e. connected.
+    pub up: bool,
+    /// Link speed in bits per second.
+    pub speed: Option<u64>,
+    /// Negotiated duplex on Linux; on Windows only known when the speed and
+    /// duplex are forced in the driver settings.
+    pub full_duplex: Option<bool>,
+    pub mtu: Option<u32>,
+    /// Driver module or service name, e.g. `e1000e` or `e2fexpress`.
+    pub driver: Option<String>,
+    pub driver_version: Option<String>,
+    pub driver_date: Option<String>,
+    pub ipv4: Vec<Ipv4Addr>,
+    pub ipv6: Vec<Ipv6Addr>,
+    /// DNS servers; on Linux the system resolvers, listed for adapters with
+    /// a default route.
+    pub dns_servers: Vec<IpAddr>,
+    /// Has a default route.
+    pub default_gateway: bool,
     pub received: u64,
     pub transmitted: u64,
 }
 
+impl NetworkInfo {
+    fn new(name: String) -> Self {
+        NetworkInfo {
+            name,
+            description: String::new(),
+            mac: None,
+            adapter_type: AdapterType::Other,
+            up: false,
+            speed: None,
+            full_duplex: None,
+            mtu: None,
+            driver: None,
+            driver_version: None,
+            driver_date: None,
+            ipv4: Vec::new(),
+            ipv6: Vec::new(),
+            dns_servers: Vec::new(),
+            default_gateway: false,
+            received: 0,
+            transmitted: 0,
+        }
+    }
+
+    pub fn is_physical(&self) -> bool {
+        matches!(self.adapter_type, AdapterType::Ethernet | AdapterType::WiFi)
+    }
+}
+
 pub fn detect() -> Vec<NetworkInfo> {
+    let mut adapters = platform_adapters();
     let networks = Networks::new_with_refreshed_list();
-    
-    networks
-        .iter()
-        .map(|(name, data)| NetworkInfo {
-            name: name.clone(),
-            received: data.total_received(),
-            transmitted: data.total_transmitted(),
-        })
-        .collect()
+
+    // sysinfo also lists filter drivers and hidden interfaces on Windows, so
+    // it only adds adapters where there is no platform code
+    if adapters.is_empty() {
+        adapters = networks.keys().map(|name| NetworkInfo::new(name.clone())).collect();
+    }
+    for adapter in &mut adapters {
+        if let Some(data) = networks.get(&adapter.name) {
+            adapter.received = data.total_received();
+            adapter.transmitted = data.total_transmitted();
+        }
+    }
+    adapters
+}
+
+/// [`detect`], run once per process and shared by the network checks.
+pub fn adapters() -> &'static [NetworkInfo] {
+    static ADAPTERS: OnceLock<Vec<NetworkInfo>> = OnceLock::new();
+    ADAPTERS.get_or_init(detect)
+}
+
+/// The physical adapter traffic leaves through: connected, with a default
+/// route if any adapter has one, preferring wired and faster links.
+pub fn active_adapter(adapters: &[NetworkInfo]) -> Option<&NetworkInfo> {
+    let connected = || adapters.iter().filter(|a| a.up && a.is_physical());
+    let routed = connected().any(|a| a.default_gateway);
+    connected()
+        .filter(|a| a.default_gateway || !routed)
+        .max_by_key(|a| (a.adapter_type == AdapterType::Ethernet, a.speed.unwrap_or(0)))
+}
+
+/// Formats a hardware address as `aa:bb:cc:dd:ee:ff`; all zeros means none.
+fn format_mac(bytes: &[u8]) -> Option<String> {
+    if bytes.iter().all(|&b| b == 0) {
+        return None;
+    }
+    Some(bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"))
+}
+
+/// The network adapter device class.
+#[cfg(target_os = "windows")]
+const NET_CLASS: crate::utils::registry::RegPath =
+    crate::utils::registry::RegPath::hklm("SYSTEM\\CurrentControlSet\\Control\\Class\\{4d36e972-e325-11ce-bfc1-08002be10318}");
+
+#[cfg(target_os = "windows")]
+fn platform_adapters() -> Vec<NetworkInfo> {
+    use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR, WIN32_ERROR};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetAdaptersAddresses, GAA_FLAG_INCLUDE_GATEWAYS, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_MULTICAST,
+        IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows::Win32::Networking::WinSock::AF_UNSPEC;
+
+    let flags = GAA_FLAG_INCLUDE_GATEWAYS | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST;
+    let mut size = 16 * 1024u32;
+    // u64 elements keep the structures aligned
+    let mut buffer: Vec<u64> = Vec::new();
+    let mut result = ERROR_BUFFER_OVERFLOW;
+    // Adapters can appear between the two calls, so retry a few times
+    for _ in 0..3 {
+        buffer = vec![0u64; (size as usize).div_ceil(8)];
+        result = WIN32_ERROR(unsafe {
+            GetAdaptersAddresses(AF_UNSPEC.0 as u32, flags, None, Some(buffer.as_mut_ptr().cast()), &mut size)
+        });
+        if result != ERROR_BUFFER_OVERFLOW {
+            break;
+        }
+    }
+    if result != NO_ERROR {
+        return Vec::new();
+    }
+
+    let mut adapters = Vec::new();
+    let mut current = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+    while let Some(adapter) = unsafe { current.as_ref() } {
+        adapters.push(windows_adapter(adapter));
+        current = adapter.Next;
+    }
+    adapters
+}
+
+#[cfg(target_os = "windows")]
+fn windows_adapter(adapter: &windows::Win32::NetworkManagement::IpHelper::IP_ADAPTER_ADDRESSES_LH) -> NetworkInfo {
+    use crate::utils::registry;
+    use windows::Win32::NetworkManagement::IpHelper::{
+        IF_TYPE_ETHERNET_CSMACD, IF_TYPE_IEEE80211, IF_TYPE_PPP, IF_TYPE_SOFTWARE_LOOPBACK, IF_TYPE_TUNNEL,
+    };
+    use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+
+    // Registry value: adapter is a physical device
+    const NCF_PHYSICAL: u32 = 0x4;
+
+    let wide = |s: windows::core::PWSTR| {
+        if s.is_null() { String::new() } else { unsafe { s.to_string() }.unwrap_or_default() }
+    };
+    let mut info = NetworkInfo::new(wide(adapter.FriendlyName));
+    info.description = wide(adapter.Description);
+    let mac_length = (adapter.PhysicalAddressLength as usize).min(adapter.PhysicalAddress.len());
+    info.mac = format_mac(&adapter.PhysicalAddress[..mac_length]);
+    info.up = adapter.OperStatus == IfOperStatusUp;
+    info.speed = Some(adapter.TransmitLinkSpeed).filter(|&s| s > 0 && s != u64::MAX);
+    info.mtu = Some(adapter.Mtu).filter(|&m| m > 0 && m != u32::MAX);
+
+    let mut unicast = adapter.FirstUnicastAddress;
+    while let Some(address) = unsafe { unicast.as_ref() } {
+        match socket_address(&address.Address) {
+            Some(IpAddr::V4(ip)) => info.ipv4.push(ip),
+            Some(IpAddr::V6(ip)) => info.ipv6.push(ip),
+            None => {}
+        }
+        unicast = address.Next;
+    }
+    let mut dns = adapter.FirstDnsServerAddress;
+    while let Some(server) = unsafe { dns.as_ref() } {
+        info.dns_servers.extend(socket_address(&server.Address));
+        dns = server.Next;
+    }
+    info.default_gateway = !adapter.FirstGatewayAddress.is_null();
+
+    // The class key of the adapter's driver, matched by interface GUID
+    let guid = if adapter.AdapterName.is_null() {
+        String::new()
+    } else {
+        unsafe { adapter.AdapterName.to_string() }.unwrap_or_default()
+    };
+    let key = registry::enum_subkeys(&NET_CLASS)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| NET_CLASS.join(&name))
+        .find(|key| registry::read_string(key, "NetCfgInstanceId").is_ok_and(|id| id.eq_ignore_ascii_case(&guid)));
+    let physical = key
+        .as_ref()
+        .and_then(|key| registry::read_dword(key, "Characteristics").ok())
+        .map(|characteristics| characteristics & NCF_PHYSICAL != 0);
+
+    info.adapter_type = match adapter.IfType {
+        IF_TYPE_SOFTWARE_LOOPBACK => AdapterType::Loopback,
+        IF_TYPE_TUNNEL | IF_TYPE_PPP => AdapterType::Virtual,
+        _ if physical == Some(false) => AdapterType::Virtual,
+        IF_TYPE_ETHERNET_CSMACD => AdapterType::Ethernet,
+        IF_TYPE_IEEE80211 => AdapterType::WiFi,
+        _ => AdapterType::Other,
+    };
+
+    if let Some(key) = &key {
+        info.driver = registry::read_string(&key.join("Ndi"), "Service").ok();
+        info.driver_version = registry::read_string(key, "DriverVersion").ok();
+        info.driver_date = registry::read_string(key, "DriverDate").ok().map(|d| super::gpu::normalize_date(&d));
+        // *SpeedDuplex: 0 autonegotiates; 1 and 3 force 10/100 Mbps half duplex
+        info.full_duplex = match registry::read_string(key, "*SpeedDuplex").ok().as_deref() {
+            Some("1") | Some("3") => Some(false),
+            Some("2") | Some("4") | Some("6") => Some(true),
+            _ => None,
+        };
+    }
+    info
+}
+
+/// IPv4 or IPv6 address of a SOCKADDR, read from its raw bytes.
+#[cfg(target_os = "windows")]
+fn socket_address(address: &windows::Win32::Networking::WinSock::SOCKET_ADDRESS) -> Option<IpAddr> {
+    use windows::Win32::Networking::WinSock::{ADDRESS_FAMILY, AF_INET, AF_INET6};
+
+    let length = usize::try_from(address.iSockaddrLength).ok()?;
+    if address.lpSockaddr.is_null() || length < 2 {
+        return None;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(address.lpSockaddr as *const u8, length) };
+    match ADDRESS_FAMILY(u16::from_le_bytes([bytes[0], bytes[1]])) {
+        AF_INET if length >= 8 => Some(IpAddr::V4(Ipv4Addr::new(bytes[4], bytes[5], bytes[6], bytes[7]))),
+        AF_INET6 if length >= 24 => {
+            let octets: [u8; 16] = bytes[8..24].try_into().ok()?;
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn platform_adapters() -> Vec<NetworkInfo> {
+    use std::path::Path;
+
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else { return Vec::new() };
+    let mut names: Vec<String> = entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+
+    let mut adapters: Vec<NetworkInfo> =
+        names.into_iter().map(|name| linux_net::adapter(Path::new("/sys/class/net"), name)).collect();
+    linux_net::add_addresses(&mut adapters);
+
+    let gateways = linux_net::default_route_interfaces();
+    let dns = linux_net::resolvers();
+    for adapter in adapters.iter_mut().filter(|a| gateways.contains(&a.name)) {
+        adapter.default_gateway = true;
+        adapter.dns_servers = dns.clone();
+    }
+    adapters
+}
+
+#[cfg(target_os = "linux")]
+mod linux_net {
+    use super::{format_mac, AdapterType, NetworkInfo};
+    use std::collections::HashSet;
+    use std::fs;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::path::Path;
+
+    /// ARPHRD_* link types from `type`
+    const ARPHRD_ETHER: u32 = 1;
+    const ARPHRD_LOOPBACK: u32 = 772;
+
+    fn read(path: &Path) -> Option<String> {
+        fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+    }
+
+    /// `KEY=value` properties udev recorded for an interface.
+    fn udev_property(ifindex: &str, key: &str) -> Option<String> {
+        let data = fs::read_to_string(format!("/run/udev/data/n{}", ifindex)).ok()?;
+        data.lines()
+            .filter_map(|l| l.strip_prefix("E:"))
+            .find_map(|l| l.strip_prefix(key).and_then(|v| v.strip_prefix('=')))
+            .map(str::to_string)
+    }
+
+    pub fn adapter(class: &Path, name: String) -> NetworkInfo {
+        let dir = class.join(&name);
+        let device = dir.join("device");
+        let mut info = NetworkInfo::new(name);
+
+        let link_type = read(&dir.join("type")).and_then(|t| t.parse::<u32>().ok());
+        // Bridges, veth, tun/tap and the like have no device behind them
+        info.adapter_type = match link_type {
+            Some(ARPHRD_LOOPBACK) => AdapterType::Loopback,
+            _ if !device.exists() => AdapterType::Virtual,
+            _ if dir.join("wireless").exists() || dir.join("phy80211").exists() => AdapterType::WiFi,
+            Some(ARPHRD_ETHER) => AdapterType::Ethernet,
+            _ => AdapterType::Other,
+        };
+
+        info.mac = read(&dir.join("address")).and_then(|mac| {
+            let bytes: Vec<u8> = mac.split(':').filter_map(|b| u8::from_str_radix(b, 16).ok()).collect();
+            format_mac(&bytes)
+        });
+        info.up = read(&dir.join("operstate")).as_deref() == Some("up");
+        // -1 while the link is down
+        info.speed = read(&dir.join("speed"))
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|&s| s > 0)
+            .map(|s| s as u64 * 1_000_000);
+        info.full_duplex = match read(&dir.join("duplex")).as_deref() {
+            Some("full") => Some(true),
+            Some("half") => Some(false),
+            _ => None,
+        };
+        info.mtu = read(&dir.join("mtu")).and_then(|m| m.parse().ok());
+
+        info.driver = fs::read_link(device.join("driver"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()));
+        info.driver_version = info.driver.as_ref().and_then(|d| read(&Path::new("/sys/module").join(d).join("version")));
+
+        let ifindex = read(&dir.join("ifindex")).unwrap_or_default();
+        info.description = udev_property(&ifindex, "ID_MODEL_FROM_DATABASE")
+            .or_else(|| udev_property(&ifindex, "ID_MODEL"))
+            .or_else(|| info.driver.clone())
+            .unwrap_or_default();
+        info
+    }
+
+    pub fn add_addresses(adapters: &mut [NetworkInfo]) {
+        let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+        if unsafe { libc::getifaddrs(&mut list) } != 0 {
+            return;
+        }
+
+        let mut current = list;
+        while let Some(entry) = unsafe { current.as_ref() } {
+            current = entry.ifa_next;
+            let Some(address) = (unsafe { entry.ifa_addr.as_ref() }) else { continue };
+            let name = unsafe { std::ffi::CStr::from_ptr(entry.ifa_name) }.to_string_lossy();
+            let Some(adapter) = adapters.iter_mut().find(|a| a.name == name) else { continue };
+
+            match i32::from(address.sa_family) {
+                libc::AF_INET => {
+                    let address = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in) };
+                    adapter.ipv4.push(Ipv4Addr::from(u32::from_be(address.sin_addr.s_addr)));
+                }
+                libc::AF_INET6 => {
+                    let address = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in6) };
+                    adapter.ipv6.push(Ipv6Addr::from(address.sin6_addr.s6_addr));
+                }
+                _ => {}
+            }
+        }
+        unsafe { libc::freeifaddrs(list) };
+    }
+
+    /// Interfaces with an IPv4 or IPv6 default route.
+    pub fn default_route_interfaces() -> HashSet<String> {
+        let mut interfaces = HashSet::new();
+        // Iface Destination Gateway ...; destination 00000000 is the default
+        if let Ok(routes) = fs::read_to_string("/proc/net/route") {
+            for fields in routes.lines().skip(1).map(|l| l.split_whitespace().collect::<Vec<_>>()) {
+                if let [iface, "00000000", ..] = fields[..] {
+                    interfaces.insert(iface.to_string());
+                }
+            }
+        }
+        // Destination, prefix length, ..., device last; ::/0 is the default
+        if let Ok(routes) = fs::read_to_string("/proc/net/ipv6_route") {
+            for fields in routes.lines().map(|l| l.split_whitespace().collect::<Vec<_>>()) {
+                if let [destination, "00", .., iface] = fields[..] {
+                    if destination.bytes().all(|b| b == b'0') && iface != "lo" {
+                        interfaces.insert(iface.to_string());
+                    }
+                }
+            }
+        }
+        interfaces
+    }
+
+    pub fn resolvers() -> Vec<IpAddr> {
+        let Ok(conf) = fs::read_to_string("/etc/resolv.conf") else { return Vec::new() };
+        conf.lines()
+            .filter_map(|l| l.trim().strip_prefix("nameserver"))
+            .filter_map(|ip| ip.trim().parse().ok())
+            .collect()
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn platform_adapters() -> Vec<NetworkInfo> {
+    Vec::new()
 }
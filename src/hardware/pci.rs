@@ -0,0 +1,534 @@
+//! PCI devices and their names.
+//!
+//! Names are best-effort. They come from the first `pci.ids` database
+//! found: a file passed with `--pci-ids`, the system copy on Linux, or the
+//! subset bundled with APEX, which covers common GPUs, NICs, NVMe and
+//! chipset devices but not everything. Devices missing from the database
+//! are shown by their vendor and device IDs.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Maker of a device, from its PCI vendor ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Vendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Microsoft,
+    Other(u16),
+    Unknown,
+}
+
+impl Vendor {
+    pub fn from_id(id: u16) -> Self {
+        match id {
+            0x10de => Vendor::Nvidia,
+            0x1002 | 0x1022 => Vendor::Amd,
+            0x8086 => Vendor::Intel,
+            0x1414 => Vendor::Microsoft,
+            other => Vendor::Other(other),
+        }
+    }
+
+    /// Best guess from a product name, for sources without PCI IDs.
+    pub fn from_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if ["nvidia", "geforce", "quadro"].iter().any(|n| name.contains(n)) {
+            Vendor::Nvidia
+        } else if ["amd", "radeon", "ati "].iter().any(|n| name.contains(n)) {
+            Vendor::Amd
+        } else if name.contains("intel") {
+            Vendor::Intel
+        } else if name.contains("microsoft") {
+            Vendor::Microsoft
+        } else {
+            Vendor::Unknown
+        }
+    }
+}
+
+impl fmt::Display for Vendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Vendor::Nvidia => write!(f, "NVIDIA"),
+            Vendor::Amd => write!(f, "AMD"),
+            Vendor::Intel => write!(f, "Intel"),
+            Vendor::Microsoft => write!(f, "Microsoft"),
+            Vendor::Other(id) => write!(f, "vendor {:04x}", id),
+            Vendor::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// Identity of a PCI function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PciId {
+    pub vendor: u16,
+    pub device: u16,
+    /// Subsystem device ID in the high and subsystem vendor ID in the low
+    /// 16 bits, as in Windows hardware IDs.
+    pub subsystem: Option<u32>,
+    pub revision: Option<u8>,
+}
+
+impl PciId {
+    /// Parses a Windows hardware ID such as
+    /// `PCI\VEN_10DE&DEV_2684&SUBSYS_16F110DE&REV_A1`, in any case.
+    #[cfg(any(target_os = "windows", test))]
+    pub fn from_hardware_id(id: &str) -> Option<Self> {
+        let rest = id.get(..4).filter(|p| p.eq_ignore_ascii_case("PCI\\")).map(|_| &id[4..])?;
+
+        let mut vendor = None;
+        let mut device = None;
+        let mut subsystem = None;
+        let mut revision = None;
+        for part in rest.split(['&', '\\']) {
+            let Some((name, value)) = part.split_once('_') else { continue };
+            match name.to_ascii_uppercase().as_str() {
+                "VEN" => vendor = u16::from_str_radix(value, 16).ok(),
+                "DEV" => device = u16::from_str_radix(value, 16).ok(),
+                "SUBSYS" => subsystem = u32::from_str_radix(value, 16).ok(),
+                "REV" => revision = u8::from_str_radix(value, 16).ok(),
+                _ => {}
+            }
+        }
+
+        Some(PciId { vendor: vendor?, device: device?, subsystem, revision })
+    }
+
+    /// Same vendor and device, and the same subsystem when both know it.
+    pub fn same_device(&self, other: &PciId) -> bool {
+        self.vendor == other.vendor
+            && self.device == other.device
+            && (self.subsystem.is_none() || other.subsystem.is_none() || self.subsystem == other.subsystem)
+    }
+}
+
+impl fmt::Display for PciId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x}:{:04x}", self.vendor, self.device)?;
+        if let Some(subsystem) = self.subsystem {
+            write!(f, " subsys {:08x}", subsystem)?;
+        }
+        if let Some(revision) = self.revision {
+            write!(f, " rev {:02x}", revision)?;
+        }
+        Ok(())
+    }
+}
+
+/// Names from a `pci.ids` database.
+#[derive(Debug, Default)]
+pub struct PciIds {
+    vendors: HashMap<u16, String>,
+    devices: HashMap<(u16, u16), String>,
+    /// Keyed by vendor, device and subsystem as in [`PciId::subsystem`].
+    subsystems: HashMap<(u16, u16, u32), String>,
+    classes: HashMap<u8, String>,
+    subclasses: HashMap<(u8, u8), String>,
+}
+
+impl PciIds {
+    /// Parses the `pci.ids` text format. Lines it does not understand are
+    /// skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut ids = PciIds::default();
+        let hex16 = |s: &str| u16::from_str_radix(s, 16).ok();
+        let hex8 = |s: &str| u8::from_str_radix(s, 16).ok();
+        // Innermost vendor/device or class being read
+        let mut vendor = None;
+        let mut device = None;
+        let mut class = None;
+
+        for line in text.lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let depth = line.len() - line.trim_start_matches('\t').len();
+            let Some((id, name)) = line.trim_start().split_once("  ") else { continue };
+            let name = name.trim().to_string();
+
+            match (depth, class) {
+                (0, _) if id.starts_with("C ") => {
+                    class = hex8(&id[2..]);
+                    vendor = None;
+                    device = None;
+                    if let Some(class) = class {
+                        ids.classes.insert(class, name);
+                    }
+                }
+                (0, _) => {
+                    // Other lists (known countries, languages, ...) follow the classes
+                    vendor = hex16(id).filter(|_| id.len() == 4);
+                    device = None;
+                    class = None;
+                    if let Some(vendor) = vendor {
+                        ids.vendors.insert(vendor, name);
+                    }
+                }
+                (1, Some(class)) => {
+                    if let Some(subclass) = hex8(id) {
+                        ids.subclasses.insert((class, subclass), name);
+                    }
+                }
+                (1, None) => {
+                    device = vendor.zip(hex16(id));
+                    if let Some(key) = device {
+                        ids.devices.insert(key, name);
+                    }
+                }
+                (2, None) => {
+                    let Some((vendor, device)) = device else { continue };
+                    let Some((sub_vendor, sub_device)) = id.split_once(' ') else { continue };
+                    if let (Some(sub_vendor), Some(sub_device)) = (hex16(sub_vendor), hex16(sub_device)) {
+                        let subsystem = (u32::from(sub_device) << 16) | u32::from(sub_vendor);
+                        ids.subsystems.insert((vendor, device, subsystem), name);
+                    }
+                }
+                _ => {}
+            }
+        }
+        ids
+    }
+
+    pub fn vendor(&self, vendor: u16) -> Option<&str> {
+        self.vendors.get(&vendor).map(String::as_str)
+    }
+
+    pub fn device(&self, id: &PciId) -> Option<&str> {
+        self.devices.get(&(id.vendor, id.device)).map(String::as_str)
+    }
+
+    pub fn subsystem(&self, id: &PciId) -> Option<&str> {
+        self.subsystems.get(&(id.vendor, id.device, id.subsystem?)).map(String::as_str)
+    }
+
+    /// Subclass name, or the class name when the subclass is not listed.
+    pub fn class(&self, class: u32) -> Option<&str> {
+        let base = (class >> 16) as u8;
+        let subclass = (class >> 8) as u8;
+        self.subclasses.get(&(base, subclass)).or_else(|| self.classes.get(&base)).map(String::as_str)
+    }
+}
+
+static IDS: OnceLock<PciIds> = OnceLock::new();
+
+/// Names devices from a full `pci.ids`, e.g. one downloaded from
+/// <https://pci-ids.ucw.cz/>, instead of the system or bundled database.
+/// Has no effect once [`ids`] has been read.
+pub fn load_ids(path: &Path) -> Result<()> {
+    let text = std::fs::read_to_string(path).map_err(|e| Error::io(e, path.display().to_string()))?;
+    let ids = PciIds::parse(&text);
+    if ids.vendors.is_empty() {
+        return Err(Error::invalid(path.display().to_string(), "no vendors found; not a pci.ids file"));
+    }
+    let _ = IDS.set(ids);
+    Ok(())
+}
+
+/// The database passed to [`load_ids`], else the system `pci.ids` where
+/// one is installed, else the subset bundled with APEX.
+pub fn ids() -> &'static PciIds {
+    IDS.get_or_init(|| {
+        #[cfg(target_os = "linux")]
+        for path in ["/usr/share/hwdata/pci.ids", "/usr/share/misc/pci.ids", "/usr/share/pci.ids"] {
+            if let Ok(text) = std::fs::read_to_string(path) {
+                return PciIds::parse(&text);
+            }
+        }
+        PciIds::parse(include_str!("pci.ids"))
+    })
+}
+
+/// Negotiated or supported PCI Express link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcieLink {
+    /// 1 for 2.5 GT/s, 2 for 5 GT/s, ... 6 for 64 GT/s.
+    pub generation: u8,
+    pub width: u8,
+}
+
+impl PcieLink {
+    /// From a transfer rate such as `16.0 GT/s PCIe` (Linux sysfs).
+    #[cfg(target_os = "linux")]
+    fn from_rate(rate: &str, width: u8) -> Option<Self> {
+        let gts: f64 = rate.split_whitespace().next()?.parse().ok()?;
+        let generation = [2.5, 5.0, 8.0, 16.0, 32.0, 64.0].iter().position(|&g| g == gts)? as u8 + 1;
+        (width > 0).then_some(PcieLink { generation, width })
+    }
+}
+
+impl fmt::Display for PcieLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Gen{} x{}", self.generation, self.width)
+    }
+}
+
+/// A PCI function present in the system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PciDevice {
+    /// `domain:bus:device.function`, e.g. `0000:01:00.0`.
+    pub address: String,
+    pub id: PciId,
+    /// Base class, subclass and programming interface, e.g. `0x030000`.
+    pub class: Option<u32>,
+    pub vendor_name: Option<String>,
+    pub device_name: Option<String>,
+    pub subsystem_name: Option<String>,
+    pub class_name: Option<String>,
+    /// Driver service on Windows, kernel driver on Linux.
+    pub driver: Option<String>,
+    pub link: Option<PcieLink>,
+    pub max_link: Option<PcieLink>,
+}
+
+impl PciDevice {
+    fn new(address: String, id: PciId, class: Option<u32>) -> Self {
+        let ids = ids();
+        PciDevice {
+            address,
+            vendor_name: ids.vendor(id.vendor).map(str::to_string),
+            device_name: ids.device(&id).map(str::to_string),
+            subsystem_name: ids.subsystem(&id).map(str::to_string),
+            class_name: class.and_then(|c| ids.class(c)).map(str::to_string),
+            id,
+            class,
+            driver: None,
+            link: None,
+            max_link: None,
+        }
+    }
+
+    /// Vendor and device names, falling back to the IDs.
+    pub fn name(&self) -> String {
+        let vendor = self.vendor_name.clone().unwrap_or_else(|| format!("{:04x}", self.id.vendor));
+        let device = self.device_name.clone().unwrap_or_else(|| format!("device {:04x}", self.id.device));
+        format!("{} {}", vendor, device)
+    }
+
+    pub fn is_bridge(&self) -> bool {
+        self.class.is_some_and(|c| c >> 16 == 0x06)
+    }
+
+    /// Trained below what both ends support, e.g. a GPU at x8 in an x16
+    /// slot or at Gen1 while idle.
+    pub fn link_degraded(&self) -> bool {
+        matches!((self.link, self.max_link), (Some(link), Some(max)) if link.generation < max.generation || link.width < max.width)
+    }
+}
+
+/// [`detect`], run once per process.
+pub fn devices() -> &'static [PciDevice] {
+    static DEVICES: OnceLock<Vec<PciDevice>> = OnceLock::new();
+    DEVICES.get_or_init(detect)
+}
+
+/// Present devices under `Enum\PCI`, with link state from the PnP
+/// manager.
+#[cfg(target_os = "windows")]
+pub fn detect() -> Vec<PciDevice> {
+    use crate::utils::registry::{self, RegPath};
+
+    let root = RegPath::hklm("SYSTEM\\CurrentControlSet\\Enum\\PCI");
+    let mut devices = Vec::new();
+    for model in registry::enum_subkeys(&root).unwrap_or_default() {
+        let Some(hardware_id) = PciId::from_hardware_id(&format!("PCI\\{}", model)) else { continue };
+        for instance in registry::enum_subkeys(&root.join(&model)).unwrap_or_default() {
+            let instance_id = format!("PCI\\{}\\{}", model, instance);
+            // Devices that were once installed keep their key; only present ones have a devnode
+            let Some(devnode) = windows_pnp::locate(&instance_id) else { continue };
+            let key = root.join(&model).join(&instance);
+
+            // e.g. PCI\CC_030000 next to the shorter PCI\CC_0300
+            let class = registry::read_multi_string(&key, "CompatibleIDs")
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|id| id.get(..7).filter(|p| p.eq_ignore_ascii_case("PCI\\CC_")).map(|_| &id[7..]))
+                .find(|code| code.len() == 6)
+                .and_then(|code| u32::from_str_radix(code, 16).ok());
+            // "PCI bus 1, device 0, function 0"
+            let location = registry::read_string(&key, "LocationInformation").unwrap_or_default();
+            let numbers: Vec<u32> = location
+                .split(|c: char| !c.is_ascii_digit())
+                .filter_map(|n| n.parse().ok())
+                .collect();
+            let address = match numbers[..] {
+                [bus, device, function] => format!("0000:{:02x}:{:02x}.{}", bus, device, function),
+                _ => instance_id.clone(),
+            };
+
+            let mut device = PciDevice::new(address, hardware_id, class);
+            device.driver = registry::read_string(&key, "Service").ok().filter(|s| !s.is_empty());
+            device.link = windows_pnp::link(devnode, windows_pnp::CURRENT_LINK);
+            device.max_link = windows_pnp::link(devnode, windows_pnp::MAX_LINK);
+            devices.push(device);
+        }
+    }
+    devices.sort_by(|a, b| a.address.cmp(&b.address));
+    devices
+}
+
+#[cfg(target_os = "windows")]
+pub(super) mod windows_pnp {
+    use super::PcieLink;
+    use windows::core::{GUID, HSTRING};
+    use windows::Win32::Devices::DeviceAndDriverInstallation::{
+        CM_Get_DevNode_PropertyW, CM_Locate_DevNodeW, CM_LOCATE_DEVNODE_NORMAL, CR_SUCCESS,
+    };
+    use windows::Win32::Devices::Properties::{DEVPROPKEY, DEVPROPTYPE, DEVPROP_TYPE_UINT32};
+
+    /// `DEVPKEY_PciDevice_*` property IDs of the current and maximum link
+    /// speed; the width follows each.
+    pub const CURRENT_LINK: u32 = 9;
+    pub const MAX_LINK: u32 = 11;
+    const PCI_DEVICE: GUID = GUID::from_u128(0x3ab22e31_8264_4b4e_9af5_a8d2d8e33e62);
+
+    /// Devnode of a present device; removed devices have none.
+    pub fn locate(instance_id: &str) -> Option<u32> {
+        let mut devnode = 0;
+        let result = unsafe { CM_Locate_DevNodeW(&mut devnode, &HSTRING::from(instance_id), CM_LOCATE_DEVNODE_NORMAL) };
+        (result == CR_SUCCESS).then_some(devnode)
+    }
+
+    fn property(devnode: u32, pid: u32) -> Option<u32> {
+        let key = DEVPROPKEY { fmtid: PCI_DEVICE, pid };
+        let mut kind = DEVPROPTYPE::default();
+        let mut value = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let result = unsafe {
+            CM_Get_DevNode_PropertyW(devnode, &key, &mut kind, Some(&mut value as *mut u32 as *mut u8), &mut size, 0)
+        };
+        (result == CR_SUCCESS && kind == DEVPROP_TYPE_UINT32).then_some(value)
+    }
+
+    /// Speed is reported as the generation number; conventional PCI devices
+    /// have neither property.
+    pub fn link(devnode: u32, speed_pid: u32) -> Option<PcieLink> {
+        let generation = property(devnode, speed_pid)?;
+        let width = property(devnode, speed_pid + 1)?;
+        ((1..=6).contains(&generation) && width > 0)
+            .then_some(PcieLink { generation: generation as u8, width: width as u8 })
+    }
+}
+
+/// Functions under `/sys/bus/pci/devices`.
+#[cfg(target_os = "linux")]
+pub fn detect() -> Vec<PciDevice> {
+    use std::fs;
+    use super::gpu::parse_hex;
+
+    let Ok(entries) = fs::read_dir("/sys/bus/pci/devices") else { return Vec::new() };
+    let mut devices: Vec<PciDevice> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let read = |name: &str| fs::read_to_string(path.join(name)).ok();
+            let hex = |name: &str| read(name).and_then(|v| parse_hex(&v));
+
+            let subsystem = hex("subsystem_vendor").zip(hex("subsystem_device")).map(|(v, d)| (d << 16) | v);
+            let id = PciId {
+                vendor: hex("vendor")? as u16,
+                device: hex("device")? as u16,
+                subsystem: subsystem.filter(|&s| s != 0),
+                revision: hex("revision").map(|r| r as u8),
+            };
+            let link = |speed: &str, width: &str| {
+                let width = read(width)?.trim().parse().ok()?;
+                PcieLink::from_rate(&read(speed)?, width)
+            };
+
+            let mut device = PciDevice::new(entry.file_name().to_string_lossy().into_owned(), id, hex("class"));
+            device.driver = fs::read_link(path.join("driver"))
+                .ok()
+                .and_then(|target| target.file_name().map(|n| n.to_string_lossy().into_owned()));
+            device.link = link("current_link_speed", "current_link_width");
+            device.max_link = link("max_link_speed", "max_link_width");
+            Some(device)
+        })
+        .collect();
+    devices.sort_by(|a, b| a.address.cmp(&b.address));
+    devices
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn detect() -> Vec<PciDevice> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Excerpt of `pci.ids`, ending with a list that is not about PCI.
+    const IDS: &str = "
+# Vendors, devices and subsystems
+10de  NVIDIA Corporation
+\t2684  AD102 [GeForce RTX 4090]
+\t\t10de 16f1  GeForce RTX 4090 Founders Edition
+\t\t1043 889c  ROG Strix GeForce RTX 4090 OC
+\t22ba  AD102 High Definition Audio Controller
+8086  Intel Corporation
+\t7a70  Raptor Lake-S PCH Shared SRAM
+
+# List of known device classes, subclasses and programming interfaces
+C 01  Mass storage controller
+\t08  Non-Volatile memory controller
+\t\t02  NVM Express
+C 03  Display controller
+\t00  VGA compatible controller
+
+# List of known languages
+L 0409  English (United States)
+\t01  Primary
+";
+
+    #[test]
+    fn names() {
+        let ids = PciIds::parse(IDS);
+        let id = PciId { vendor: 0x10de, device: 0x2684, subsystem: Some(0x889c_1043), revision: Some(0xa1) };
+        assert_eq!(ids.vendor(0x10de), Some("NVIDIA Corporation"));
+        assert_eq!(ids.device(&id), Some("AD102 [GeForce RTX 4090]"));
+        assert_eq!(ids.subsystem(&id), Some("ROG Strix GeForce RTX 4090 OC"));
+        assert_eq!(ids.subsystem(&PciId { subsystem: Some(0x1234_10de), ..id }), None);
+        assert_eq!(ids.subsystem(&PciId { subsystem: None, ..id }), None);
+        let sram = PciId { vendor: 0x8086, device: 0x7a70, subsystem: None, revision: None };
+        assert_eq!(ids.device(&sram), Some("Raptor Lake-S PCH Shared SRAM"));
+    }
+
+    #[test]
+    fn classes() {
+        let ids = PciIds::parse(IDS);
+        assert_eq!(ids.class(0x01_08_02), Some("Non-Volatile memory controller"));
+        assert_eq!(ids.class(0x03_00_00), Some("VGA compatible controller"));
+        // Unlisted subclass: the class name
+        assert_eq!(ids.class(0x03_80_00), Some("Display controller"));
+        assert_eq!(ids.class(0x02_00_00), None);
+    }
+
+    #[test]
+    fn trailing_list_ignored() {
+        let ids = PciIds::parse(IDS);
+        assert_eq!(ids.vendors.len(), 2);
+        assert_eq!(ids.devices.len(), 3);
+        assert_eq!(ids.subclasses.len(), 2);
+        assert_eq!(ids.vendor(0x0409), None);
+    }
+
+    #[test]
+    fn hardware_id() {
+        let id = PciId::from_hardware_id("PCI\\VEN_10DE&DEV_2684&SUBSYS_16F110DE&REV_A1").unwrap();
+        assert_eq!(id, PciId { vendor: 0x10de, device: 0x2684, subsystem: Some(0x16f1_10de), revision: Some(0xa1) });
+        assert_eq!(id.to_string(), "10de:2684 subsys 16f110de rev a1");
+
+        let id = PciId::from_hardware_id("pci\\ven_8086&dev_7a70&cc_0500").unwrap();
+        assert_eq!(id, PciId { vendor: 0x8086, device: 0x7a70, subsystem: None, revision: None });
+
+        assert_eq!(PciId::from_hardware_id("PCI\\VEN_10DE&CC_0300"), None);
+        assert_eq!(PciId::from_hardware_id("USB\\VID_046D&PID_C52B"), None);
+    }
+}
@@ -0,0 +1,403 @@
+//! Drive health from `smartctl --json` reports and raw NVMe SMART/Health
+//! Information log pages.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+/// Health counters of one drive. Counters the drive or source does not
+/// report are `None`.
+#[derive(Debug, Clone)]
+pub struct SmartHealth {
+    /// Device as named by the source, e.g. `/dev/sda` or `\\.\PhysicalDrive0`.
+    pub device: String,
+    pub model: String,
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub serial: String,
+    /// Overall self-assessment.
+    pub passed: Option<bool>,
+    /// NVMe critical warning bits.
+    pub critical_warning: Option<u8>,
+    /// Share of the rated endurance used; can exceed 100.
+    pub percentage_used: Option<u32>,
+    pub media_errors: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    /// Celsius.
+    pub temperature: Option<i32>,
+    /// Highest temperature in the drive's history, Celsius (ATA and SCSI).
+    pub max_temperature: Option<i32>,
+    /// Minutes spent above the warning and critical temperatures (NVMe).
+    pub warning_temp_minutes: Option<u32>,
+    pub critical_temp_minutes: Option<u32>,
+    pub unsafe_shutdowns: Option<u64>,
+    pub power_on_hours: Option<u64>,
+}
+
+impl SmartHealth {
+    fn new(device: &str, model: &str, serial: &str) -> Self {
+        SmartHealth {
+            device: device.to_string(),
+            model: model.to_string(),
+            serial: serial.to_string(),
+            passed: None,
+            critical_warning: None,
+            percentage_used: None,
+            media_errors: None,
+            reallocated_sectors: None,
+            pending_sectors: None,
+            temperature: None,
+            max_temperature: None,
+            warning_temp_minutes: None,
+            critical_temp_minutes: None,
+            unsafe_shutdowns: None,
+            power_on_hours: None,
+        }
+    }
+
+    /// Model, or the device when the model is unknown.
+    pub fn label(&self) -> &str {
+        if self.model.is_empty() { &self.device } else { &self.model }
+    }
+
+    /// Parses the NVMe SMART/Health Information log page (log identifier 02h).
+    pub fn from_nvme_log(device: &str, model: &str, serial: &str, log: &[u8]) -> Result<Self> {
+        const LOG_SIZE: usize = 512;
+        if log.len() < LOG_SIZE {
+            return Err(Error::invalid("NVMe health log", format!("{} bytes, expected {}", log.len(), LOG_SIZE)));
+        }
+
+        let u16_at = |offset: usize| u16::from_le_bytes([log[offset], log[offset + 1]]);
+        let u32_at = |offset: usize| u32::from_le_bytes(log[offset..offset + 4].try_into().unwrap());
+        // 128-bit counters; the upper half is zero on any real drive
+        let u64_at = |offset: usize| u64::from_le_bytes(log[offset..offset + 8].try_into().unwrap());
+        // Kelvin, 0 when the sensor is not implemented
+        let celsius = |kelvin: u16| (kelvin > 0).then(|| i32::from(kelvin) - 273);
+
+        let mut health = SmartHealth::new(device, model, serial);
+        health.critical_warning = Some(log[0]);
+        health.passed = Some(log[0] == 0);
+        health.temperature = celsius(u16_at(1));
+        health.percentage_used = Some(u32::from(log[5]));
+        health.power_on_hours = Some(u64_at(128));
+        health.unsafe_shutdowns = Some(u64_at(144));
+        health.media_errors = Some(u64_at(160));
+        health.warning_temp_minutes = Some(u32_at(192));
+        health.critical_temp_minutes = Some(u32_at(196));
+        Ok(health)
+    }
+
+    /// Parses the output of `smartctl --json -x` (or `-a`) for ATA, SCSI
+    /// or NVMe drives.
+    pub fn from_smartctl_json(text: &str) -> Result<Self> {
+        const CONTEXT: &str = "smartctl JSON";
+        let json: Value = serde_json::from_str(text).map_err(|e| Error::invalid(CONTEXT, e))?;
+
+        // Bits 0-1: the command line was wrong or the device could not be opened
+        let exit_status = json.pointer("/smartctl/exit_status").and_then(Value::as_u64).unwrap_or(0);
+        if exit_status & 0b11 != 0 {
+            let message = json
+                .pointer("/smartctl/messages/0/string")
+                .and_then(Value::as_str)
+                .unwrap_or("device could not be read");
+            return Err(Error::invalid(CONTEXT, message));
+        }
+
+        let text_at = |pointer: &str| json.pointer(pointer).and_then(Value::as_str).unwrap_or("").trim().to_string();
+        let u64_at = |pointer: &str| json.pointer(pointer).and_then(Value::as_u64);
+        let i32_at = |pointer: &str| json.pointer(pointer).and_then(Value::as_i64).map(|v| v as i32);
+
+        let mut health = SmartHealth::new(&text_at("/device/name"), &text_at("/model_name"), &text_at("/serial_number"));
+        health.passed = json.pointer("/smart_status/passed").and_then(Value::as_bool);
+        health.temperature = i32_at("/temperature/current");
+        health.power_on_hours = u64_at("/power_on_time/hours");
+
+        if let Some(nvme) = json.get("nvme_smart_health_information_log") {
+            let field = |name: &str| nvme.get(name).and_then(Value::as_u64);
+            health.critical_warning = field("critical_warning").map(|v| v as u8);
+            health.percentage_used = field("percentage_used").map(|v| v as u32);
+            health.media_errors = field("media_errors");
+            health.unsafe_shutdowns = field("unsafe_shutdowns");
+            health.warning_temp_minutes = field("warning_temp_time").map(|v| v as u32);
+            health.critical_temp_minutes = field("critical_comp_time").map(|v| v as u32);
+        }
+
+        if let Some(table) = json.pointer("/ata_smart_attributes/table").and_then(Value::as_array) {
+            let attribute = |id: u64| table.iter().find(|a| a.get("id").and_then(Value::as_u64) == Some(id));
+            let normalized = |id: u64| attribute(id).and_then(|a| a.get("value")).and_then(Value::as_u64);
+            // Vendors pack extra data above the low 32 bits of some counters
+            let raw = |id: u64| {
+                attribute(id).and_then(|a| a.pointer("/raw/value")).and_then(Value::as_u64).map(|v| v & 0xffff_ffff)
+            };
+
+            health.reallocated_sectors = raw(5);
+            health.pending_sectors = match (raw(197), raw(198)) {
+                (None, None) => None,
+                (pending, offline) => Some(pending.unwrap_or(0).max(offline.unwrap_or(0))),
+            };
+            health.media_errors = raw(187);
+            // Unexpected power loss, reported by SSDs. Attribute 192 is not
+            // a substitute: many HDDs count every power-off retract there.
+            health.unsafe_shutdowns = raw(174);
+            // Remaining life as a normalized value counting down from 100
+            health.percentage_used = [231, 202, 233, 177]
+                .into_iter()
+                .find_map(normalized)
+                .filter(|&remaining| remaining <= 100)
+                .map(|remaining| 100 - remaining as u32);
+        }
+        if let Some(used) = u64_at("/endurance_used/current_percent") {
+            health.percentage_used = Some(used as u32);
+        }
+
+        if let Some(defects) = u64_at("/scsi_grown_defect_list") {
+            health.reallocated_sectors = Some(defects);
+        }
+        if let Some(uncorrected) = u64_at("/scsi_error_counter_log/read/total_uncorrected_errors") {
+            health.media_errors = Some(uncorrected);
+        }
+
+        let history = json
+            .pointer("/ata_sct_temperature_history/table")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t.as_i64().map(|t| t as i32));
+        health.max_temperature = history.chain(i32_at("/temperature/lifetime_max")).chain(health.temperature).max();
+
+        if health.model.is_empty() && health.device.is_empty() {
+            return Err(Error::invalid(CONTEXT, "not a smartctl device report"));
+        }
+        Ok(health)
+    }
+
+    /// Reads an exported report: smartctl JSON, or a raw NVMe log page as
+    /// saved by `nvme smart-log --raw-binary`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let context = path.display().to_string();
+        let bytes = std::fs::read(path).map_err(|e| Error::io(e, context.as_str()))?;
+
+        if bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{') {
+            let mut health = Self::from_smartctl_json(&String::from_utf8_lossy(&bytes))?;
+            if health.device.is_empty() {
+                health.device = context;
+            }
+            Ok(health)
+        } else {
+            Self::from_nvme_log(&context, "", "", &bytes)
+        }
+    }
+}
+
+/// Health of the local drives plus any imported reports. Local drives are
+/// read with smartctl when it is installed, and on Windows from the NVMe
+/// log of NVMe disks smartctl did not cover.
+pub fn reports(imports: &[PathBuf]) -> Vec<Result<SmartHealth>> {
+    let mut reports = smartctl_reports();
+    append_nvme_logs(&mut reports);
+    reports.extend(imports.iter().map(|path| SmartHealth::from_file(path)));
+    reports
+}
+
+/// Runs smartctl and parses its JSON, which it prints even when the exit
+/// status flags drive problems.
+fn smartctl(args: &[&str]) -> Result<Value> {
+    let output = Command::new("smartctl").args(args).output().map_err(|e| Error::io(e, "smartctl"))?;
+    serde_json::from_slice(&output.stdout).map_err(|_| Error::CommandFailed {
+        command: format!("smartctl {}", args.join(" ")),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+fn smartctl_reports() -> Vec<Result<SmartHealth>> {
+    // Not installed: nothing to report, not an error
+    let Ok(scan) = smartctl(&["--scan-open", "--json"]) else { return Vec::new() };
+
+    let devices = scan.get("devices").and_then(Value::as_array).cloned().unwrap_or_default();
+    devices
+        .iter()
+        .filter_map(|d| Some((d.get("name")?.as_str()?.to_string(), d.get("type")?.as_str()?.to_string())))
+        .map(|(name, kind)| {
+            let json = smartctl(&["--json", "-x", "-d", &kind, &name])?;
+            SmartHealth::from_smartctl_json(&json.to_string())
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn append_nvme_logs(reports: &mut Vec<Result<SmartHealth>>) {
+    use super::disk::{self, BusType};
+
+    for d in disk::disks().iter().filter(|d| d.bus == BusType::Nvme) {
+        let covered = reports.iter().flatten().any(|r| !d.serial.is_empty() && r.serial == d.serial);
+        if covered {
+            continue;
+        }
+        if let Some(log) = disk::nvme_health_log(d.number) {
+            reports.push(SmartHealth::from_nvme_log(&d.device, &d.model, &d.serial, &log));
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn append_nvme_logs(_reports: &mut Vec<Result<SmartHealth>>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `nvme smart-log --raw-binary` of a drive over its warning
+    /// temperature: 50 C, 7% used, 12345 hours, 57 unsafe shutdowns,
+    /// 3 media errors, 42 and 1 minutes above the warning and critical
+    /// temperatures.
+    const NVME_LOG: &str = "
+02 43 01 64 0a 07 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+3c 2b 1a 00 00 00 00 00 00 00 00 00 00 00 00 00
+4d 3c 2b 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+33 07 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+39 30 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+39 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+03 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+09 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+2a 00 00 00 01 00 00 00 4d 01 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+";
+
+    /// `smartctl --json -x` of a SATA SSD, trimmed. The error log bit (6)
+    /// is set in the exit status and 174 carries vendor data above bit 31.
+    const ATA: &str = r#"{
+  "smartctl": {"version": [7, 4], "exit_status": 64},
+  "device": {"name": "/dev/sda", "type": "sat", "protocol": "ATA"},
+  "model_name": "Samsung SSD 870 EVO 1TB",
+  "serial_number": "S626NF0R123456X",
+  "smart_status": {"passed": true},
+  "ata_smart_attributes": {
+    "revision": 1,
+    "table": [
+      {"id": 5, "name": "Reallocated_Sector_Ct", "value": 100, "raw": {"value": 0, "string": "0"}},
+      {"id": 9, "name": "Power_On_Hours", "value": 97, "raw": {"value": 9876, "string": "9876"}},
+      {"id": 174, "name": "Unexpect_Power_Loss_Ct", "value": 99, "raw": {"value": 4294967336, "string": "40"}},
+      {"id": 177, "name": "Wear_Leveling_Count", "value": 96, "raw": {"value": 41, "string": "41"}},
+      {"id": 192, "name": "Power-Off_Retract_Count", "value": 99, "raw": {"value": 311, "string": "311"}},
+      {"id": 197, "name": "Current_Pending_Sector", "value": 100, "raw": {"value": 8, "string": "8"}},
+      {"id": 198, "name": "Offline_Uncorrectable", "value": 100, "raw": {"value": 12, "string": "12"}}
+    ]
+  },
+  "power_on_time": {"hours": 9876},
+  "temperature": {"current": 34},
+  "ata_sct_temperature_history": {"table": [33, 34, null, 61, 35]}
+}"#;
+
+    const NVME: &str = r#"{
+  "smartctl": {"version": [7, 4], "exit_status": 0},
+  "device": {"name": "/dev/nvme0", "type": "nvme", "protocol": "NVMe"},
+  "model_name": "WD_BLACK SN850X 2000GB",
+  "serial_number": "23143M801234",
+  "smart_status": {"passed": true},
+  "nvme_smart_health_information_log": {
+    "critical_warning": 0,
+    "temperature": 41,
+    "percentage_used": 2,
+    "power_on_hours": 2211,
+    "unsafe_shutdowns": 19,
+    "media_errors": 0,
+    "warning_temp_time": 0,
+    "critical_comp_time": 0
+  },
+  "power_on_time": {"hours": 2211},
+  "temperature": {"current": 41}
+}"#;
+
+    /// The device could not be opened: bit 1 of the exit status.
+    const UNREADABLE: &str = r#"{
+  "smartctl": {
+    "version": [7, 4],
+    "messages": [{"string": "Smartctl open device: /dev/sdb failed: Permission denied", "severity": "error"}],
+    "exit_status": 2
+  },
+  "device": {"name": "/dev/sdb", "type": "sat"}
+}"#;
+
+    fn bytes(dump: &str) -> Vec<u8> {
+        dump.split_whitespace().map(|b| u8::from_str_radix(b, 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn nvme_log_offsets() {
+        let health = SmartHealth::from_nvme_log("nvme0", "", "", &bytes(NVME_LOG)).unwrap();
+        assert_eq!(health.critical_warning, Some(0x02));
+        assert_eq!(health.passed, Some(false));
+        assert_eq!(health.temperature, Some(50));
+        assert_eq!(health.percentage_used, Some(7));
+        assert_eq!(health.power_on_hours, Some(12345));
+        assert_eq!(health.unsafe_shutdowns, Some(57));
+        assert_eq!(health.media_errors, Some(3));
+        assert_eq!(health.warning_temp_minutes, Some(42));
+        assert_eq!(health.critical_temp_minutes, Some(1));
+    }
+
+    #[test]
+    fn nvme_log_truncated() {
+        let log = bytes(NVME_LOG);
+        assert!(SmartHealth::from_nvme_log("nvme0", "", "", &log[..256]).is_err());
+    }
+
+    #[test]
+    fn smartctl_ata() {
+        let health = SmartHealth::from_smartctl_json(ATA).unwrap();
+        assert_eq!(health.device, "/dev/sda");
+        assert_eq!(health.model, "Samsung SSD 870 EVO 1TB");
+        assert_eq!(health.passed, Some(true));
+        assert_eq!(health.reallocated_sectors, Some(0));
+        assert_eq!(health.pending_sectors, Some(12), "the larger of 197 and 198");
+        assert_eq!(health.unsafe_shutdowns, Some(40), "174 without its vendor bits, not 192");
+        assert_eq!(health.percentage_used, Some(4));
+        assert_eq!(health.power_on_hours, Some(9876));
+        assert_eq!(health.temperature, Some(34));
+        assert_eq!(health.max_temperature, Some(61));
+        assert_eq!(health.critical_warning, None);
+    }
+
+    #[test]
+    fn smartctl_nvme() {
+        let health = SmartHealth::from_smartctl_json(NVME).unwrap();
+        assert_eq!(health.label(), "WD_BLACK SN850X 2000GB");
+        assert_eq!(health.critical_warning, Some(0));
+        assert_eq!(health.percentage_used, Some(2));
+        assert_eq!(health.unsafe_shutdowns, Some(19));
+        assert_eq!(health.media_errors, Some(0));
+        assert_eq!(health.pending_sectors, None);
+        assert_eq!(health.warning_temp_minutes, Some(0));
+        assert_eq!(health.max_temperature, Some(41));
+    }
+
+    #[test]
+    fn smartctl_exit_status() {
+        let error = SmartHealth::from_smartctl_json(UNREADABLE).unwrap_err();
+        assert!(error.to_string().contains("Permission denied"), "{}", error);
+    }
+}
@@ -0,0 +1,442 @@
+//! Parser for the raw SMBIOS structure table: BIOS (type 0), baseboard
+//! (type 2) and memory devices (type 17).
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::error::{Error, Result};
+
+const TYPE_BIOS: u8 = 0;
+const TYPE_SYSTEM: u8 = 1;
+const TYPE_BASEBOARD: u8 = 2;
+const TYPE_MEMORY_DEVICE: u8 = 17;
+const TYPE_END_OF_TABLE: u8 = 127;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BiosInfo {
+    pub vendor: String,
+    pub version: String,
+    /// YYYY-MM-DD when the firmware date could be parsed.
+    pub release_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardInfo {
+    pub manufacturer: String,
+    pub product: String,
+    pub version: String,
+}
+
+/// An installed memory module.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryModule {
+    /// Slot label, e.g. `DIMM_A1` or `ChannelA-DIMM0`.
+    pub slot: String,
+    pub bank: String,
+    /// Channel guessed from the slot and bank labels.
+    pub channel: Option<String>,
+    pub size: u64,
+    pub memory_type: String,
+    /// Maximum speed in MT/s reported by the firmware, usually the JEDEC
+    /// speed from the module's SPD.
+    pub speed: Option<u32>,
+    pub configured_speed: Option<u32>,
+    /// Speed encoded in the part number, i.e. the XMP/EXPO rating.
+    pub part_speed: Option<u32>,
+    pub manufacturer: String,
+    pub part_number: String,
+}
+
+impl MemoryModule {
+    /// Highest speed the module is sold for.
+    pub fn rated_speed(&self) -> Option<u32> {
+        self.speed.max(self.part_speed)
+    }
+}
+
+/// One structure: the formatted area including its 4-byte header, and
+/// the strings that follow it.
+struct Structure {
+    kind: u8,
+    data: Vec<u8>,
+    strings: Vec<String>,
+}
+
+impl Structure {
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    fn word(&self, offset: usize) -> Option<u16> {
+        self.data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn dword(&self, offset: usize) -> Option<u32> {
+        self.data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// String referenced by the 1-based index at `offset`; empty when unset.
+    fn string(&self, offset: usize) -> String {
+        self.byte(offset)
+            .filter(|&i| i > 0)
+            .and_then(|i| self.strings.get(i as usize - 1))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    }
+}
+
+pub struct Smbios {
+    structures: Vec<Structure>,
+}
+
+impl Smbios {
+    /// Splits a structure table into structures. A truncated structure
+    /// ends the table.
+    pub fn parse(table: &[u8]) -> Self {
+        let mut structures = Vec::new();
+        let mut offset = 0;
+
+        while offset + 4 <= table.len() {
+            let kind = table[offset];
+            let length = table[offset + 1] as usize;
+            if length < 4 || offset + length > table.len() {
+                break;
+            }
+            let data = table[offset..offset + length].to_vec();
+
+            // Strings are NUL-terminated and the set ends with an extra NUL
+            let mut end = offset + length;
+            let mut strings = Vec::new();
+            loop {
+                let rest = &table[end.min(table.len())..];
+                let Some(len) = rest.iter().position(|&b| b == 0) else {
+                    end = table.len();
+                    break;
+                };
+                if len == 0 {
+                    end += 1;
+                    break;
+                }
+                strings.push(String::from_utf8_lossy(&rest[..len]).into_owned());
+                end += len + 1;
+            }
+            // A structure without strings is followed by two NULs
+            if strings.is_empty() && table.get(end) == Some(&0) {
+                end += 1;
+            }
+
+            if kind == TYPE_END_OF_TABLE {
+                break;
+            }
+            structures.push(Structure { kind, data, strings });
+            offset = end;
+        }
+
+        Smbios { structures }
+    }
+
+    fn of_type(&self, kind: u8) -> impl Iterator<Item = &Structure> {
+        self.structures.iter().filter(move |s| s.kind == kind)
+    }
+
+    pub fn bios(&self) -> Option<BiosInfo> {
+        let s = self.of_type(TYPE_BIOS).next()?;
+        Some(BiosInfo {
+            vendor: s.string(0x04),
+            version: s.string(0x05),
+            release_date: normalize_date(&s.string(0x08)),
+        })
+    }
+
+    /// System UUID, in the byte order Windows and Linux print it. `None`
+    /// when the firmware left it unset.
+    pub fn system_uuid(&self) -> Option<String> {
+        let s = self.of_type(TYPE_SYSTEM).next()?;
+        let b = s.data.get(0x08..0x18)?;
+        if b.iter().all(|&x| x == 0) || b.iter().all(|&x| x == 0xff) {
+            return None;
+        }
+        // The first three fields are little-endian since SMBIOS 2.6
+        Some(format!(
+            "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+            b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6], b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+        ))
+    }
+
+    pub fn board(&self) -> Option<BoardInfo> {
+        let s = self.of_type(TYPE_BASEBOARD).next()?;
+        Some(BoardInfo {
+            manufacturer: s.string(0x04),
+            product: s.string(0x05),
+            version: s.string(0x06),
+        })
+    }
+
+    /// Number of memory slots, populated or not.
+    pub fn memory_slots(&self) -> usize {
+        self.of_type(TYPE_MEMORY_DEVICE).count()
+    }
+
+    /// Populated memory slots.
+    pub fn memory_modules(&self) -> Vec<MemoryModule> {
+        self.of_type(TYPE_MEMORY_DEVICE).filter_map(memory_module).collect()
+    }
+}
+
+fn memory_module(s: &Structure) -> Option<MemoryModule> {
+    // 0 means an empty slot, 0xFFFF an unknown size
+    let size = match s.word(0x0c)? {
+        0 | 0xffff => return None,
+        0x7fff => u64::from(s.dword(0x1c)? & 0x7fff_ffff) << 20,
+        kb if kb & 0x8000 != 0 => u64::from(kb & 0x7fff) << 10,
+        mb => u64::from(mb) << 20,
+    };
+
+    // 0xFFFF points to the 32-bit fields added in SMBIOS 3.3
+    let speed = |offset: usize, extended: usize| match s.word(offset) {
+        Some(0) | None => None,
+        Some(0xffff) => s.dword(extended).map(|v| v & 0x7fff_ffff).filter(|&v| v > 0),
+        Some(v) => Some(u32::from(v)),
+    };
+
+    let slot = s.string(0x10);
+    let bank = s.string(0x11);
+    let part_number = s.string(0x1a);
+    Some(MemoryModule {
+        channel: channel(&slot, &bank),
+        slot,
+        bank,
+        size,
+        memory_type: memory_type(s.byte(0x12).unwrap_or(0)).to_string(),
+        speed: speed(0x15, 0x54),
+        configured_speed: speed(0x20, 0x58),
+        part_speed: part_number_speed(&part_number),
+        manufacturer: s.string(0x17),
+        part_number,
+    })
+}
+
+fn memory_type(code: u8) -> &'static str {
+    match code {
+        0x12 => "DDR",
+        0x13 => "DDR2",
+        0x18 => "DDR3",
+        0x1a => "DDR4",
+        0x1b => "LPDDR",
+        0x1c => "LPDDR2",
+        0x1d => "LPDDR3",
+        0x1e => "LPDDR4",
+        0x22 => "DDR5",
+        0x23 => "LPDDR5",
+        _ => "Unknown",
+    }
+}
+
+/// Guesses the memory channel from slot and bank labels such as
+/// `ChannelA-DIMM0`, `P0 CHANNEL A`, `Controller1-ChannelA-DIMM0` or
+/// `DIMM_B2`.
+pub fn channel(slot: &str, bank: &str) -> Option<String> {
+    for label in [bank, slot] {
+        let upper = label.to_uppercase();
+        let Some((before, after)) = upper.split_once("CHANNEL") else { continue };
+        let Some(id) = after.trim_start_matches([' ', '_', '-']).chars().next().filter(|c| c.is_ascii_alphanumeric()) else {
+            continue;
+        };
+        let controller = before
+            .split_once("CONTROLLER")
+            .and_then(|(_, c)| c.trim_start_matches([' ', '_', '-']).chars().next())
+            .filter(|c| c.is_ascii_digit());
+        return Some(match controller {
+            Some(controller) => format!("{}{}", controller, id),
+            None => id.to_string(),
+        });
+    }
+
+    let upper = slot.to_uppercase();
+    let rest = upper.strip_prefix("DIMM").unwrap_or(&upper).trim_start_matches([' ', '_', '-']);
+    let mut chars = rest.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c @ 'A'..='H'), Some('0'..='9')) => Some(c.to_string()),
+        _ => None,
+    }
+}
+
+/// Rated speed in MT/s from common enthusiast part numbers: G.Skill
+/// `F4-3600C16...`, Corsair `CMK16GX4M2B3200C16`, Kingston `KF436C16...`
+/// and `HX432...`, Crucial `BL8G36C16U4B` and `CP16G60C36U5B`.
+pub fn part_number_speed(part: &str) -> Option<u32> {
+    let part = part.trim().to_uppercase();
+    let digits = |s: &str, n: usize| -> Option<u32> {
+        let d = s.get(..n).filter(|d| d.bytes().all(|b| b.is_ascii_digit()))?;
+        d.parse().ok()
+    };
+
+    let speed = if ["F3-", "F4-", "F5-"].iter().any(|p| part.starts_with(p)) {
+        digits(&part[3..], 4)
+    } else if part.starts_with("CM") {
+        // ...X4M2B3200C16: kit size, one letter, then the speed
+        ["X4M", "X5M"].iter().find_map(|m| part.split_once(m)).and_then(|(_, rest)| rest.get(2..)).and_then(|s| digits(s, 4))
+    } else if part.starts_with("KF") || part.starts_with("HX") {
+        // KF436: DDR generation, then hundreds of MT/s
+        part.get(3..).and_then(|s| digits(s, 2)).map(|s| s * 100)
+    } else if part.starts_with("BL") || part.starts_with("CP") {
+        part.split_once('G').and_then(|(_, rest)| digits(rest, 2)).map(|s| s * 100)
+    } else {
+        None
+    };
+    speed.filter(|s| (1600..=12000).contains(s))
+}
+
+/// `MM/DD/YYYY` from the BIOS structure to `YYYY-MM-DD`; other formats are
+/// kept as they are.
+fn normalize_date(date: &str) -> String {
+    let parts: Vec<&str> = date.split('/').collect();
+    match parts.as_slice() {
+        [m, d, y] if y.len() == 4 => format!("{}-{:0>2}-{:0>2}", y, m, d),
+        [m, d, y] if y.len() == 2 => format!("20{}-{:0>2}-{:0>2}", y, m, d),
+        _ => date.to_string(),
+    }
+}
+
+/// The firmware's SMBIOS table, read once per process.
+pub fn table() -> Result<&'static Smbios> {
+    static TABLE: OnceLock<Result<Smbios>> = OnceLock::new();
+    TABLE.get_or_init(read_table).as_ref().map_err(Clone::clone)
+}
+
+#[cfg(target_os = "windows")]
+fn read_table() -> Result<Smbios> {
+    use windows::Win32::System::SystemInformation::{GetSystemFirmwareTable, RSMB};
+
+    const CONTEXT: &str = "SMBIOS firmware table";
+    let size = unsafe { GetSystemFirmwareTable(RSMB, 0, None) };
+    if size == 0 {
+        return Err(Error::io(std::io::Error::last_os_error(), CONTEXT));
+    }
+    let mut buffer = vec![0u8; size as usize];
+    let written = unsafe { GetSystemFirmwareTable(RSMB, 0, Some(&mut buffer)) } as usize;
+    if written == 0 {
+        return Err(Error::io(std::io::Error::last_os_error(), CONTEXT));
+    }
+
+    // RawSMBIOSData: calling method, version, DMI revision, u32 length
+    if written < 8 || written > buffer.len() {
+        return Err(Error::invalid(CONTEXT, "truncated header"));
+    }
+    let length = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+    let table = &buffer[8..(8 + length).min(written)];
+    Ok(Smbios::parse(table))
+}
+
+#[cfg(target_os = "linux")]
+fn read_table() -> Result<Smbios> {
+    const TABLE: &str = "/sys/firmware/dmi/tables/DMI";
+
+    let table = std::fs::read(TABLE).map_err(|e| Error::io(e, TABLE))?;
+    Ok(Smbios::parse(&table))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_table() -> Result<Smbios> {
+    Err(Error::unsupported("SMBIOS"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An AM5 board's table: BIOS, baseboard, a system boot structure
+    /// without strings, and three memory devices: a DDR5-6000 module at
+    /// its JEDEC 4800, an empty slot, and a module whose manufacturer
+    /// index points past its strings.
+    const TABLE: &str = "
+00 1a 00 00 01 02 00 f0 03 ff 00 00 00 00 00 00
+00 00 00 00 05 1b ff ff 20 00 41 6d 65 72 69 63
+61 6e 20 4d 65 67 61 74 72 65 6e 64 73 20 49 6e
+74 65 72 6e 61 74 69 6f 6e 61 6c 2c 20 4c 4c 43
+2e 00 31 2e 41 30 00 31 31 2f 32 38 2f 32 30 32
+33 00 00 02 0f 02 00 01 02 03 04 00 09 05 03 00
+0a 00 4d 69 63 72 6f 2d 53 74 61 72 20 49 6e 74
+65 72 6e 61 74 69 6f 6e 61 6c 20 43 6f 2e 2c 20
+4c 74 64 2e 00 4d 41 47 20 42 36 35 30 20 54 4f
+4d 41 48 41 57 4b 20 57 49 46 49 20 28 4d 53 2d
+37 44 37 35 29 00 31 2e 30 00 30 37 44 37 35 31
+31 5f 4e 37 31 45 31 32 33 34 35 36 00 44 65 66
+61 75 6c 74 20 73 74 72 69 6e 67 00 00 20 0b 20
+00 00 00 00 00 00 00 00 00 00 11 5c 11 00 00 10
+fe ff 40 00 40 00 00 40 09 00 01 02 22 00 00 c0
+12 03 00 00 04 00 00 00 00 00 c0 12 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 44 49 4d 4d 20 31 00 50 30 20
+43 48 41 4e 4e 45 4c 20 41 00 47 20 53 6b 69 6c
+6c 20 49 6e 74 6c 00 46 35 2d 36 30 30 30 4a 33
+30 33 38 46 31 36 47 00 00 11 5c 12 00 00 10 fe
+ff 40 00 40 00 00 00 09 00 01 02 02 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 44 49 4d 4d 20 30 00 50 30 20 43
+48 41 4e 4e 45 4c 20 42 00 00 11 5c 13 00 00 10
+fe ff 40 00 40 00 00 40 09 00 01 02 22 00 00 c0
+12 05 00 00 03 00 00 00 00 00 c0 12 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+00 00 00 00 00 00 44 49 4d 4d 20 31 00 50 30 20
+43 48 41 4e 4e 45 4c 20 42 00 43 4d 4b 33 32 47
+58 35 4d 32 42 36 30 30 30 43 33 30 00 00 7f 04
+ff fe 00 00
+";
+
+    fn bytes(dump: &str) -> Vec<u8> {
+        dump.split_whitespace().map(|b| u8::from_str_radix(b, 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn bios_and_board() {
+        let smbios = Smbios::parse(&bytes(TABLE));
+        let bios = smbios.bios().unwrap();
+        assert_eq!(bios.vendor, "American Megatrends International, LLC.");
+        assert_eq!(bios.version, "1.A0");
+        assert_eq!(bios.release_date, "2023-11-28");
+
+        let board = smbios.board().unwrap();
+        assert_eq!(board.manufacturer, "Micro-Star International Co., Ltd.");
+        assert_eq!(board.product, "MAG B650 TOMAHAWK WIFI (MS-7D75)");
+        assert_eq!(board.version, "1.0");
+    }
+
+    #[test]
+    fn structure_without_strings() {
+        // The type 32 structure ends in a double NUL; the memory devices
+        // after it must still be found
+        let smbios = Smbios::parse(&bytes(TABLE));
+        assert_eq!(smbios.memory_slots(), 3);
+    }
+
+    #[test]
+    fn memory_modules() {
+        let modules = Smbios::parse(&bytes(TABLE)).memory_modules();
+        assert_eq!(modules.len(), 2, "the empty slot is skipped");
+
+        let xmp = &modules[0];
+        assert_eq!(xmp.slot, "DIMM 1");
+        assert_eq!(xmp.channel.as_deref(), Some("A"));
+        assert_eq!(xmp.size, 16 << 30);
+        assert_eq!(xmp.memory_type, "DDR5");
+        assert_eq!(xmp.speed, Some(4800));
+        assert_eq!(xmp.configured_speed, Some(4800));
+        assert_eq!(xmp.part_speed, Some(6000));
+        assert_eq!(xmp.rated_speed(), Some(6000));
+        assert_eq!(xmp.manufacturer, "G Skill Intl");
+
+        let unnamed = &modules[1];
+        assert_eq!(unnamed.channel.as_deref(), Some("B"));
+        assert_eq!(unnamed.manufacturer, "");
+        assert_eq!(unnamed.part_number, "CMK32GX5M2B6000C30");
+        assert_eq!(unnamed.rated_speed(), Some(6000));
+    }
+}
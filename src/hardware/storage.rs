@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sysinfo::{Disks};
 
+use super::disk;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageInfo {
     pub name: String,
@@ -8,6 +10,9 @@ pub struct StorageInfo {
     pub total: u64,
     pub available: u64,
     pub filesystem: String,
+    /// Physical disk number and partition the volume is on.
+    pub disk: Option<u32>,
+    pub partition: Option<u32>,
 }
 
 pub fn detect() -> Vec<StorageInfo> {
@@ -15,12 +20,19 @@ pub fn detect() -> Vec<StorageInfo> {
     
     disks
         .iter()
-        .map(|disk| StorageInfo {
-            name: disk.name().to_string_lossy().to_string(),
-            mount_point: disk.mount_point().to_string_lossy().to_string(),
-            total: disk.total_space(),
-            available: disk.available_space(),
-            filesystem: disk.file_system().to_string_lossy().to_string(),
+        .map(|volume| {
+            let name = volume.name().to_string_lossy().to_string();
+            let mount_point = volume.mount_point().to_string_lossy().to_string();
+            let location = disk::locate_volume(&name, &mount_point);
+            StorageInfo {
+                total: volume.total_space(),
+                available: volume.available_space(),
+                filesystem: volume.file_system().to_string_lossy().to_string(),
+                disk: location.map(|(disk, _)| disk),
+                partition: location.and_then(|(_, partition)| partition),
+                name,
+                mount_point,
+            }
         })
         .collect()
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
 
+use super::smbios::{self, BiosInfo, BoardInfo};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub os_name: String,
@@ -8,16 +10,20 @@ pub struct SystemInfo {
     pub kernel_version: String,
     pub hostname: String,
     pub uptime: u64,
+    pub bios: Option<BiosInfo>,
+    pub board: Option<BoardInfo>,
 }
 
 pub fn detect() -> SystemInfo {
-    let _sys = System::new_all();
-    
+    let table = smbios::table().ok();
+
     SystemInfo {
         os_name: System::name().unwrap_or_else(|| "Unknown".to_string()),
         os_version: System::os_version().unwrap_or_else(|| "Unknown".to_string()),
         kernel_version: System::kernel_version().unwrap_or_else(|| "Unknown".to_string()),
         hostname: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
         uptime: System::uptime(),
+        bios: table.and_then(|t| t.bios()),
+        board: table.and_then(|t| t.board()),
     }
 }
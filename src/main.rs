@@ -1,13 +1,18 @@
 mod config;
 mod types;
+mod error;
 mod hardware;
 mod checks;
 mod fixes;
 mod export;
 mod utils;
+mod session;
+mod experiment;
 
 use clap::{Parser, Subcommand};
 use colored::*;
+use hardware::network::AdapterType;
+use std::path::PathBuf;
 use std::time::Instant;
 
 #[derive(Parser)]
@@ -18,6 +23,10 @@ use std::time::Instant;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Name PCI devices from this pci.ids file instead of the system or bundled one
+    #[arg(long, global = true)]
+    pci_ids: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -35,17 +44,56 @@ enum Commands {
         /// Export results to CSV
         #[arg(long)]
         csv: Option<String>,
+        
+        /// Also check a saved `smartctl --json -x` report or raw NVMe health log (repeat for several)
+        #[arg(long)]
+        smart: Vec<PathBuf>,
     },
     
     /// Apply fixes for detected issues
     Fix {
-        /// Issue number to fix
+        /// Issue number to fix (pick interactively when neither this nor --all is given)
         #[arg(short, long)]
         issue: Option<u32>,
         
         /// Fix all issues
         #[arg(long)]
         all: bool,
+        
+        /// Write the selected fixes to a script (.ps1, .reg or .cmd) or a desired state file (.json) instead of applying them
+        #[arg(long)]
+        export: Option<String>,
+        
+        /// Also allow Moderate fixes
+        #[arg(long)]
+        allow_moderate: bool,
+        
+        /// Also allow Risky fixes (implies --allow-moderate)
+        #[arg(long)]
+        allow_risky: bool,
+    },
+    
+    /// Compare the machine against a desired state file and report drift
+    Enforce {
+        /// State file listing the wanted settings (write one with `fix --export state.json`)
+        #[arg(long)]
+        state: String,
+        
+        /// Re-apply drifted settings (with backups)
+        #[arg(long)]
+        apply: bool,
+    },
+    
+    /// Restore a check or a whole category to the Windows defaults
+    Reset {
+        /// Check id (e.g. hpet-status) or category (e.g. latency); lists them when omitted
+        target: Option<String>,
+    },
+    
+    /// Measure whether fixes improve timer latency, then keep or roll them back
+    Experiment {
+        #[command(subcommand)]
+        action: ExperimentAction,
     },
     
     /// Rollback previous fixes
@@ -65,17 +113,83 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum ExperimentAction {
+    /// Benchmark, apply the fixes and compare (after the reboot they need)
+    Start {
+        /// Issue numbers to try (repeat for several)
+        #[arg(short, long, required = true)]
+        issue: Vec<u32>,
+        
+        /// Statistic the decision is based on
+        #[arg(long, value_enum, default_value = "p99")]
+        metric: experiment::Metric,
+        
+        /// Minimum improvement of the metric, in percent, to keep the fixes
+        #[arg(long, default_value = "5")]
+        min_improvement: f64,
+        
+        /// Also require a statistically significant drop in mean latency
+        #[arg(long)]
+        require_significant: bool,
+        
+        /// Timer samples per benchmark run
+        #[arg(long, default_value = "1000")]
+        samples: usize,
+        
+        /// Also allow Moderate fixes
+        #[arg(long)]
+        allow_moderate: bool,
+        
+        /// Also allow Risky fixes (implies --allow-moderate)
+        #[arg(long)]
+        allow_risky: bool,
+    },
+    
+    /// Run the second benchmark after rebooting and decide
+    Resume,
+    
+    /// Show the experiment in progress
+    Status,
+    
+    /// Roll back the experiment's fixes without measuring
+    Abort,
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     
     print_banner();
     
+    if let Some(path) = &cli.pci_ids {
+        hardware::pci::load_ids(path)?;
+    }
+    
     match cli.command {
-        Commands::Audit { json, html, csv } => {
-            run_audit(json, html, csv)?;
+        Commands::Audit { json, html, csv, smart } => {
+            run_audit(json, html, csv, smart)?;
+        }
+        Commands::Fix { issue, all, export, allow_moderate, allow_risky } => {
+            let allowed = if allow_risky {
+                types::Safety::Risky
+            } else if allow_moderate {
+                types::Safety::Moderate
+            } else {
+                types::Safety::Safe
+            };
+            run_fix(issue, all, export, allowed)?;
+        }
+        Commands::Enforce { state, apply } => {
+            // A non-zero exit code lets schedulers notice remaining drift
+            if !run_enforce(&state, apply)? {
+                std::process::exit(1);
+            }
         }
-        Commands::Fix { issue, all } => {
-            run_fix(issue, all)?;
+        Commands::Reset { target } => {
+            run_reset(target.as_deref())?;
+        }
+        Commands::Experiment { action } => {
+            run_experiment(action)?;
         }
         Commands::Rollback { backup } => {
             run_rollback(&backup)?;
@@ -100,7 +214,12 @@ fn print_banner() {
     println!();
 }
 
-fn run_audit(json_path: Option<String>, html_path: Option<String>, csv_path: Option<String>) -> anyhow::Result<()> {
+fn run_audit(
+    json_path: Option<String>,
+    html_path: Option<String>,
+    csv_path: Option<String>,
+    smart_imports: Vec<PathBuf>,
+) -> anyhow::Result<()> {
     println!("{}", "🔍 Starting system audit...".bright_cyan());
     
     let start = Instant::now();
@@ -110,21 +229,167 @@ fn run_audit(json_path: Option<String>, html_path: Option<String>, csv_path: Opt
     let hardware = hardware::HardwareInfo::detect();
     
     println!("   CPU: {}", hardware.cpu.name.bright_yellow());
-    println!("   Cores: {} physical, {} logical", hardware.cpu.cores, hardware.cpu.threads);
+    let cpu = &hardware.cpu;
+    if let Some(microarch) = &cpu.microarch {
+        println!("   Microarchitecture: {}", microarch);
+    }
+    let hybrid = if cpu.hybrid {
+        format!(" ({} P-cores, {} E-cores)", cpu.performance_cores, cpu.efficiency_cores)
+    } else {
+        String::new()
+    };
+    println!("   Cores: {} physical, {} logical{}", cpu.cores, cpu.threads, hybrid);
+    let clocks: Vec<String> = [(cpu.base_frequency, "base"), (cpu.max_frequency, "max")]
+        .iter()
+        .filter_map(|(mhz, label)| mhz.map(|mhz| format!("{:.2} GHz {}", mhz as f64 / 1000.0, label)))
+        .collect();
+    if !clocks.is_empty() {
+        println!("   Clock: {}", clocks.join(", "));
+    }
+    if !cpu.caches.is_empty() {
+        let caches: Vec<String> = cpu.caches.iter().map(|c| c.to_string()).collect();
+        println!("   Caches: {}", caches.join(", "));
+    }
+    if cpu.numa_nodes > 1 || cpu.l3_domains > 1 {
+        println!("   NUMA nodes: {}, L3 domains: {}", cpu.numa_nodes, cpu.l3_domains);
+    }
+    if !cpu.features.is_empty() {
+        println!("   Features: {}", cpu.features.join(" "));
+    }
     println!("   Memory: {:.2} GB", hardware.memory.total as f64 / 1024.0 / 1024.0 / 1024.0);
+    for module in &hardware.memory.modules {
+        let speed = match (module.configured_speed, module.rated_speed()) {
+            (Some(configured), Some(rated)) if configured != rated => format!(" {}/{} MT/s", configured, rated),
+            (Some(speed), _) | (None, Some(speed)) => format!(" {} MT/s", speed),
+            (None, None) => String::new(),
+        };
+        println!(
+            "      - {}: {} GB {}{}, {} {}",
+            module.slot,
+            module.size >> 30,
+            module.memory_type,
+            speed,
+            module.manufacturer,
+            module.part_number,
+        );
+    }
+    if let Some(board) = &hardware.system.board {
+        let bios = hardware.system.bios.as_ref()
+            .map(|b| format!(" (BIOS {}, {})", b.version, b.release_date))
+            .unwrap_or_default();
+        println!("   Board: {} {}{}", board.manufacturer, board.product, bios);
+    }
+    println!("   Disks: {}", hardware.disks.len());
+    for disk in &hardware.disks {
+        let mut notes = vec![
+            format!("{} {}", disk.bus, disk.media),
+            format!("{:.1} GB", disk.size as f64 / 1024.0 / 1024.0 / 1024.0),
+        ];
+        if let Some(style) = disk.partition_style {
+            notes.push(style.to_string());
+        }
+        if !disk.firmware.is_empty() {
+            notes.push(format!("fw {}", disk.firmware));
+        }
+        println!("      - {} ({})", disk.model, notes.join(", "));
+    }
     println!("   GPUs: {}", hardware.gpu.len());
     for gpu in &hardware.gpu {
-        println!("      - {}", gpu.name.bright_yellow());
+        let mut notes = Vec::new();
+        if gpu.memory > 0 {
+            notes.push(format!("{:.1} GB", gpu.memory as f64 / 1024.0 / 1024.0 / 1024.0));
+        }
+        if let Some(pci) = &gpu.pci {
+            notes.push(format!("{:04x}:{:04x}", pci.vendor, pci.device));
+        }
+        if gpu.primary {
+            notes.push("primary".to_string());
+        }
+        let notes = if notes.is_empty() { String::new() } else { format!(" ({})", notes.join(", ")) };
+        println!("      - {}{}", gpu.name.bright_yellow(), notes);
+    }
+    println!("   Displays: {}", hardware.displays.len());
+    for display in &hardware.displays {
+        let mut notes = Vec::new();
+        if let Some(mode) = display.current_mode {
+            notes.push(mode.to_string());
+        }
+        if let Some(max) = display.max_refresh() {
+            notes.push(format!("max {} Hz", max));
+        }
+        if let Some(edid) = &display.edid {
+            if edid.hdr.as_ref().is_some_and(|h| h.pq) {
+                notes.push("HDR10".to_string());
+            }
+            if let Some(vrr) = edid.vrr {
+                notes.push(format!("{} {}-{} Hz", vrr.source, vrr.min, vrr.max));
+            }
+        }
+        if display.primary {
+            notes.push("primary".to_string());
+        }
+        let notes = if notes.is_empty() { String::new() } else { format!(" ({})", notes.join(", ")) };
+        println!("      - {}{}", display.name, notes);
+    }
+    let connected: Vec<_> = hardware.network.iter().filter(|a| a.up && a.adapter_type != AdapterType::Loopback).collect();
+    println!("   Network: {} connected", connected.len());
+    for adapter in connected {
+        let mut notes = vec![adapter.adapter_type.to_string()];
+        if let Some(speed) = adapter.speed {
+            notes.push(format!("{} Mbps", speed / 1_000_000));
+        }
+        notes.extend(adapter.ipv4.iter().map(|ip| ip.to_string()));
+        if adapter.default_gateway {
+            notes.push("default route".to_string());
+        }
+        let name = if adapter.description.is_empty() { &adapter.name } else { &adapter.description };
+        println!("      - {} ({})", name, notes.join(", "));
+    }
+    let endpoints: Vec<_> = hardware.pci.iter().filter(|d| !d.is_bridge()).collect();
+    println!("   PCI devices: {}", endpoints.len());
+    for device in &endpoints {
+        let Some(link) = device.link else { continue };
+        let max = device.max_link.filter(|_| device.link_degraded()).map(|max| format!(" of {}", max)).unwrap_or_default();
+        println!("      - {} {} ({}{})", device.address, device.name(), link, max);
     }
     println!();
     
     // Run checks
     println!("{}", "🔬 Running system checks...".bright_cyan());
-    let config = config::Config::new();
-    let results = checks::run_all_checks(&config);
+    let config = config::Config { smart_imports, ..config::Config::new() };
+    let mut results = checks::run_all_checks(&config);
+    results.hardware = Some(hardware);
     
     let duration = start.elapsed();
     
+    let reboot_status = fixes::reboot::check()?;
+    for fix in &reboot_status.pending {
+        results.add_warning(types::Warning {
+            category: fix.category.clone(),
+            message: format!("{}: fix pending reboot (applied {})", fix.check, fix.applied_at_display()),
+        });
+    }
+    for verified in reboot_status.verified.iter().filter(|v| !v.stuck()) {
+        results.add_warning(types::Warning {
+            category: verified.fix.category.clone(),
+            message: format!("{}: fix did not take effect after reboot", verified.fix.check),
+        });
+    }
+    
+    if let Ok(Some(experiment)) = experiment::load() {
+        let nums: Vec<String> = experiment.issues.iter().map(|i| format!("#{}", i.num)).collect();
+        results.add_warning(types::Warning {
+            category: "Experiment".to_string(),
+            message: format!("Experiment with {} in progress since {}; run `apex experiment resume`",
+                nums.join(" "), experiment.applied_at_display()),
+        });
+    }
+    
+    // Remember what the user is shown so `fix --issue N` means the same issue
+    if let Err(e) = session::save(&results) {
+        println!("{}", format!("⚠️  Could not save audit session: {}", e).bright_yellow());
+    }
+    
     println!();
     println!("{}", "═══════════════════════════════════════════════════════════════".bright_green());
     println!("{}", format!("✅ Audit Complete in {:.2}s", duration.as_secs_f64()).bright_green());
@@ -162,6 +427,22 @@ fn run_audit(json_path: Option<String>, html_path: Option<String>, csv_path: Opt
     println!("   {} {}", "ℹ".blue(), format!("{} Info", info_count).blue());
     println!();
     
+    print_reboot_status(&reboot_status);
+    
+    if !results.issues.is_empty() {
+        println!("Issues:");
+        for issue in &results.issues {
+            println!("   {} {} {} {}{}",
+                format!("#{}", issue.num).bright_white(),
+                format!("[{}]", issue.category).bright_cyan(),
+                issue.problem,
+                fixes::picker::safety_label(issue.safety),
+                if issue.reboot { " (reboot)".dimmed().to_string() } else { String::new() }
+            );
+        }
+        println!();
+    }
+    
     // Export results
     if let Some(path) = json_path {
         export::json::export(&results, &path)?;
@@ -181,15 +462,553 @@ fn run_audit(json_path: Option<String>, html_path: Option<String>, csv_path: Opt
     Ok(())
 }
 
-fn run_fix(_issue: Option<u32>, _all: bool) -> anyhow::Result<()> {
+fn print_reboot_status(status: &fixes::reboot::RebootStatus) {
+    if !status.pending.is_empty() {
+        println!("{}", "⏳ Fixes pending reboot:".bright_yellow());
+        for fix in &status.pending {
+            println!("   - {} [{}] applied {}", fix.check, fix.category, fix.applied_at_display());
+        }
+        println!();
+    }
+    
+    if !status.verified.is_empty() {
+        println!("{}", "🔁 Verifying fixes applied before the last reboot:".bright_cyan());
+        for verified in &status.verified {
+            if verified.stuck() {
+                println!("   {} {}", "✓".green(), verified.fix.check);
+                continue;
+            }
+            
+            println!("   {} {} did not take effect (overridden by policy or firmware?)", "✗".red(), verified.fix.check);
+            for change in &verified.reverted {
+                println!("      - expected {}", change.describe());
+            }
+            for change in &verified.unreadable {
+                println!("      - could not read {}", change.describe());
+            }
+        }
+        println!();
+    }
+}
+
+fn run_fix(issue: Option<u32>, all: bool, export_path: Option<String>, allowed: types::Safety) -> anyhow::Result<()> {
+    // Exporting only writes a script, applying needs admin rights
+    if export_path.is_none() {
+        utils::privileges::require_admin()?;
+    }
+    
+    let (results, replaced) = fix_session()?;
+    
+    // Follow the issue the user read across a re-audit
+    let issue = match issue.map(|num| follow_issue(num, &results, replaced.as_ref())) {
+        Some(Followed::Found(num)) => Some(num),
+        Some(Followed::Gone(old_issue)) => {
+            println!("{}", format!("✅ Issue #{} ({}) no longer needs fixing", old_issue.num, old_issue.check).bright_green());
+            return Ok(());
+        }
+        None => None,
+    };
+    
+    if results.issues.is_empty() {
+        println!("{}", "✅ No issues to fix".bright_green());
+        return Ok(());
+    }
+    
+    let picked = issue.is_none() && !all;
+    let mut nums: Vec<u32> = if all {
+        let skipped: Vec<&types::Issue> = results.issues.iter().filter(|i| i.safety > allowed).collect();
+        for issue in &skipped {
+            println!("{}", format!("⏭  Skipping #{} ({}): {}", issue.num, issue.safety.label(), issue.problem).bright_yellow());
+        }
+        if let Some(flag) = skipped.iter().map(|i| i.safety).max().and_then(|s| s.flag()) {
+            println!("{}", format!("   Rerun with {} to include them", flag).bright_white());
+        }
+        results.issues.iter().filter(|i| i.safety <= allowed).map(|i| i.num).collect()
+    } else if let Some(num) = issue {
+        if let Some(found) = results.issues.iter().find(|i| i.num == num && i.safety > allowed) {
+            println!("{}", format!("Issue #{} is {}; rerun with {} to apply it",
+                num, found.safety.label(), found.safety.flag().unwrap_or_default()).bright_yellow());
+            return Ok(());
+        }
+        vec![num]
+    } else if fixes::picker::is_interactive() {
+        match fixes::picker::pick(&results.issues, allowed)? {
+            Some(nums) => nums,
+            None => {
+                println!("{}", "No changes made".bright_white());
+                return Ok(());
+            }
+        }
+    } else {
+        fixes::picker::print_list(&results.issues, allowed);
+        return Ok(());
+    };
+    
+    let mut selected: Vec<&types::Issue> = results.issues.iter()
+        .filter(|i| nums.contains(&i.num))
+        .collect();
+    
+    if selected.is_empty() {
+        match nums.first() {
+            Some(num) => println!("{}", format!("Issue #{} not found", num).bright_yellow()),
+            None => println!("{}", "No fixes allowed at this safety level".bright_yellow()),
+        }
+        return Ok(());
+    }
+    
+    let mut plan = fixes::plan::Plan::new(&selected);
+    if !plan.conflicts.is_empty() {
+        plan.print_conflicts();
+        if !fixes::picker::is_interactive() {
+            println!("{}", "Refusing to apply fixes that change the same setting differently.".bright_red());
+            println!("{}", "   Select one fix of each pair with --issue <N>.".bright_white());
+            return Ok(());
+        }
+        if !fixes::picker::resolve_conflicts(&results.issues, &mut nums)? {
+            println!("{}", "No changes made".bright_white());
+            return Ok(());
+        }
+        selected.retain(|i| nums.contains(&i.num));
+        plan = fixes::plan::Plan::new(&selected);
+    }
+    
+    if let Some(path) = export_path {
+        return export_fixes(&selected, &path);
+    }
+    
+    // The picker already showed the plan before asking for confirmation
+    if !picked {
+        plan.print();
+    }
+    
+    println!("{}", format!("🔧 Applying {} change(s)...", plan.steps.len()).bright_cyan());
+    let mut reboot_needed = false;
+    for (issue, (_, result)) in selected.iter().zip(fixes::apply::apply_plan(&plan, &selected)) {
+        match result {
+            Ok(()) => {
+                println!("   {} #{} {}", "✓".green(), issue.num, issue.solution);
+                reboot_needed |= issue.reboot;
+            }
+            Err(e) => println!("   {} #{} {}", "✗".red(), issue.num, e),
+        }
+    }
+    
+    if reboot_needed {
+        println!();
+        println!("{}", "⏳ Some fixes take effect after a reboot.".bright_yellow());
+        println!("{}", "   The first audit after restarting will verify them.".bright_white());
+    }
+    
+    Ok(())
+}
+
+/// Audit results `fix` works from: the saved session when it is usable, or a
+/// fresh audit. When a stale session is replaced, it is returned as well so
+/// issue numbers from it can be mapped.
+fn fix_session() -> anyhow::Result<(types::AuditResults, Option<types::AuditResults>)> {
+    let saved = match session::load() {
+        Ok(Some(saved)) => saved,
+        Ok(None) => {
+            println!("{}", "No saved audit found".bright_white());
+            return Ok((fresh_audit()?, None));
+        }
+        Err(e) => {
+            println!("{}", format!("⚠️  The saved audit could not be read ({}); re-auditing", e).bright_yellow());
+            return Ok((fresh_audit()?, None));
+        }
+    };
+    
+    if !saved.same_machine() {
+        // Issue numbers from another machine mean nothing here
+        println!("{}", format!("⚠️  The saved audit was made on {}, not this machine", saved.fingerprint.hostname).bright_yellow());
+        return Ok((fresh_audit()?, None));
+    }
+    if saved.os_changed() {
+        println!("{}", format!("⚠️  Windows was updated since the audit ({} then)", saved.fingerprint.os).bright_yellow());
+    }
+    
+    if !saved.is_stale() {
+        println!("{}", format!("📋 Using audit from {}", saved.created_at_display()).bright_white());
+        return Ok((saved.results, None));
+    }
+    
+    println!("{}", format!("⚠️  The saved audit from {} is stale (older than a day or made before the last reboot)", saved.created_at_display()).bright_yellow());
+    if fixes::picker::is_interactive() && fixes::picker::confirm("Re-audit now? [Y/n] ", true)? {
+        return Ok((fresh_audit()?, Some(saved.results)));
+    }
+    
+    println!("{}", "   Using it anyway; run `apex audit` to refresh it".bright_white());
+    Ok((saved.results, None))
+}
+
+/// Where an issue number the user read ended up after a re-audit.
+enum Followed<'a> {
+    Found(u32),
+    /// The check it came from no longer raises an issue.
+    Gone(&'a types::Issue),
+}
+
+/// Follows issue `num` of the audit that `replaced` held to the issue the
+/// same check raised in `results`, since a re-audit can renumber them.
+fn follow_issue<'a>(num: u32, results: &types::AuditResults, replaced: Option<&'a types::AuditResults>) -> Followed<'a> {
+    let Some(old_issue) = replaced.and_then(|old| old.issues.iter().find(|i| i.num == num)) else {
+        return Followed::Found(num);
+    };
+    match results.issues.iter().find(|i| i.check == old_issue.check && i.category == old_issue.category) {
+        Some(new_issue) => {
+            if new_issue.num != num {
+                println!("{}", format!("Issue #{} ({}) is now #{}", num, old_issue.check, new_issue.num).bright_white());
+            }
+            Followed::Found(new_issue.num)
+        }
+        None => Followed::Gone(old_issue),
+    }
+}
+
+/// Runs the checks for `fix` and saves them as the new session.
+fn fresh_audit() -> anyhow::Result<types::AuditResults> {
+    println!("{}", "🔬 Running system checks...".bright_cyan());
+    let config = config::Config::new();
+    let results = checks::run_all_checks(&config);
+    session::save(&results)?;
+    Ok(results)
+}
+
+fn export_fixes(issues: &[&types::Issue], path: &str) -> anyhow::Result<()> {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    
+    match extension.as_str() {
+        "ps1" => export::powershell::export(issues, path)?,
+        "reg" => {
+            let skipped = export::regfile::export(issues, path)?;
+            if !skipped.is_empty() {
+                let names: Vec<String> = skipped.iter().map(|i| format!("#{} ({})", i.num, i.check)).collect();
+                println!("{}", format!("⚠️  Not fully covered by the .reg file, which only holds registry values: {}", names.join(", ")).bright_yellow());
+                println!("{}", "   Export to .ps1 or .cmd to include their service and power plan changes.".bright_yellow());
+            }
+        }
+        "cmd" | "bat" => export::batch::export(issues, path)?,
+        "json" => {
+            fixes::enforce::save_state(issues, path)?;
+            println!("{}", format!("💾 Desired state for {} issue(s) saved to: {}", issues.len(), path).bright_green());
+            return Ok(());
+        }
+        _ => return Err(anyhow::anyhow!("Unsupported script type '{}': use .ps1, .reg, .cmd or .json", path)),
+    }
+    
+    println!("{}", format!("💾 Remediation script for {} issue(s) saved to: {}", issues.len(), path).bright_green());
+    Ok(())
+}
+
+/// Returns whether the machine matches the state once done.
+fn run_enforce(state_path: &str, apply: bool) -> anyhow::Result<bool> {
+    use fixes::enforce::Drift;
+    
+    if apply {
+        utils::privileges::require_admin()?;
+    }
+    
+    let state = fixes::enforce::load_state(state_path)?;
+    let plan = fixes::plan::Plan::from_changes(&state.changes);
+    if !plan.conflicts.is_empty() {
+        plan.print_conflicts();
+        return Err(anyhow::anyhow!("State file {} sets the same setting to different values", state_path));
+    }
+    
+    println!("{}", format!("🔍 Checking {} setting(s) from {}...", state.changes.len(), state_path).bright_cyan());
+    let mut drifted = Vec::new();
+    let mut unreadable = 0;
+    let mut needs_admin = false;
+    for (change, drift) in fixes::enforce::check(&state) {
+        match drift {
+            Drift::InSync => println!("   {} {}", "✓".green(), change.describe()),
+            Drift::Drifted => {
+                println!("   {} {}", "✗".red(), change.describe());
+                drifted.push(change.clone());
+            }
+            Drift::Missing => println!("   {} {}: service not installed, skipped", "-".dimmed(), change.describe()),
+            Drift::Unreadable(e) => {
+                println!("   {} {}: {}", "?".yellow(), change.describe(), e);
+                needs_admin |= e.kind() == error::ErrorKind::AccessDenied;
+                unreadable += 1;
+            }
+        }
+    }
+    println!();
+    
+    if unreadable > 0 {
+        println!("{}", format!("⚠️  {} setting(s) could not be read", unreadable).bright_yellow());
+        if needs_admin {
+            println!("{}", "   Run as administrator to read all of them".bright_white());
+        }
+    }
+    if drifted.is_empty() {
+        if unreadable == 0 {
+            println!("{}", "✅ No drift detected".bright_green());
+        }
+        return Ok(unreadable == 0);
+    }
+    
+    println!("{}", format!("⚠️  {} setting(s) drifted from the desired state", drifted.len()).bright_yellow());
+    if !apply {
+        println!("{}", "   Re-apply them with --apply".bright_white());
+        return Ok(false);
+    }
+    
+    println!("{}", "🔧 Re-applying drifted settings...".bright_cyan());
+    let (restored, failed) = print_restored(&drifted);
+    
+    if failed == 0 {
+        println!("{}", format!("✅ Restored {} setting(s)", restored).bright_green());
+    }
+    Ok(failed == 0 && unreadable == 0)
+}
+
+/// Restores the changes and lists the outcome of each. Returns how many
+/// were written and how many failed.
+fn print_restored(changes: &[types::Change]) -> (usize, usize) {
+    use fixes::enforce::Restored;
+    
+    let (mut written, mut failed) = (0, 0);
+    for (change, restored) in fixes::enforce::restore(changes) {
+        match restored {
+            Restored::Applied => {
+                println!("   {} {}", "✓".green(), change.describe());
+                written += 1;
+            }
+            Restored::InSync => println!("   {} {}: already set", "✓".green(), change.describe()),
+            Restored::Missing => println!("   {} {}: service not installed, skipped", "-".dimmed(), change.describe()),
+            Restored::Failed(e) => {
+                println!("   {} {}: {}", "✗".red(), change.describe(), e);
+                failed += 1;
+            }
+        }
+    }
+    (written, failed)
+}
+
+fn run_reset(target: Option<&str>) -> anyhow::Result<()> {
+    use checks::defaults;
+    
+    let Some(target) = target else {
+        println!("{}", "📋 Settings with known Windows defaults:".bright_cyan());
+        let mut category = "";
+        for (cat, check) in defaults::resettable() {
+            if cat != category {
+                println!("\n   {}", cat.bold());
+                category = cat;
+            }
+            println!("   {:<44} {}", defaults::check_id(check), check.dimmed());
+        }
+        println!();
+        if let Some(build) = defaults::windows_build() {
+            println!("   Defaults shown for Windows build {}", build);
+        }
+        println!("{}", "   Reset one with: apex reset <check-id|category>".bright_white());
+        return Ok(());
+    };
+    
+    let (changes, reboot) = defaults::reset_changes(target);
+    if changes.is_empty() {
+        return Err(anyhow::anyhow!("No Windows defaults known for '{}'. Run `apex reset` to list check ids and categories", target));
+    }
+    
     utils::privileges::require_admin()?;
     
-    println!("{}", "🔧 Fix functionality requires detailed implementation".bright_yellow());
-    println!("{}", "   This will be available in a future update.".bright_white());
+    let pending: Vec<types::Change> = changes
+        .into_iter()
+        .filter(|c| !matches!(fixes::apply::is_applied(c), Ok(true)))
+        .collect();
+    if pending.is_empty() {
+        println!("{}", format!("✅ {} is already at the Windows defaults", target).bright_green());
+        return Ok(());
+    }
+    
+    println!("{}", format!("⏪ Restoring {} setting(s) to the Windows defaults...", pending.len()).bright_cyan());
+    let (_, failed) = print_restored(&pending);
+    println!();
     
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} setting(s) could not be reset", failed));
+    }
+    println!("{}", "✅ Defaults restored (backups in backups/)".bright_green());
+    if reboot {
+        println!("{}", "⏳ Some defaults take effect after a reboot.".bright_yellow());
+    }
+    Ok(())
+}
+
+fn run_experiment(action: ExperimentAction) -> anyhow::Result<()> {
+    match action {
+        ExperimentAction::Start { issue, metric, min_improvement, require_significant, samples, allow_moderate, allow_risky } => {
+            utils::privileges::require_admin()?;
+            if experiment::load()?.is_some() {
+                return Err(anyhow::anyhow!("An experiment is already in progress; resume or abort it first"));
+            }
+            
+            let allowed = if allow_risky {
+                types::Safety::Risky
+            } else if allow_moderate {
+                types::Safety::Moderate
+            } else {
+                types::Safety::Safe
+            };
+            
+            let (results, replaced) = fix_session()?;
+            let mut issues = Vec::new();
+            for num in issue {
+                let num = match follow_issue(num, &results, replaced.as_ref()) {
+                    Followed::Found(num) => num,
+                    Followed::Gone(old_issue) => {
+                        return Err(anyhow::anyhow!("Issue #{} ({}) no longer needs fixing after the re-audit; nothing to measure",
+                            old_issue.num, old_issue.check));
+                    }
+                };
+                let found = results.issues.iter().find(|i| i.num == num)
+                    .ok_or_else(|| anyhow::anyhow!("Issue #{} not found", num))?;
+                if found.safety > allowed {
+                    return Err(anyhow::anyhow!("Issue #{} is {}; rerun with {} to try it",
+                        num, found.safety.label(), found.safety.flag().unwrap_or_default()));
+                }
+                issues.push(found.clone());
+            }
+            
+            println!("{}", format!("⏱️  Measuring baseline ({} timer samples)...", samples).bright_cyan());
+            let baseline = experiment::measure(samples);
+            print_latency_stats("Baseline", &experiment::Stats::from_samples(&baseline));
+            
+            let mut trial = experiment::Experiment {
+                issues,
+                metric,
+                min_improvement,
+                require_significant,
+                samples,
+                baseline,
+                backups: Vec::new(),
+                applied_at: 0,
+            };
+            
+            println!("{}", "🔧 Applying fixes...".bright_cyan());
+            trial.apply()?;
+            for issue in &trial.issues {
+                println!("   {} #{} {}", "✓".green(), issue.num, issue.solution);
+            }
+            experiment::save(&trial)?;
+            
+            if trial.needs_reboot() {
+                println!();
+                println!("{}", "⏳ Reboot, then run `apex experiment resume` to measure again.".bright_yellow());
+                return Ok(());
+            }
+            finish_experiment(&trial)
+        }
+        ExperimentAction::Resume => {
+            utils::privileges::require_admin()?;
+            let Some(trial) = experiment::load()? else {
+                println!("{}", "No experiment in progress".bright_white());
+                return Ok(());
+            };
+            if trial.waiting_for_reboot() {
+                println!("{}", "⏳ The fixes take effect after a reboot; resume once the machine restarted.".bright_yellow());
+                return Ok(());
+            }
+            finish_experiment(&trial)
+        }
+        ExperimentAction::Status => {
+            let Some(trial) = experiment::load()? else {
+                println!("{}", "No experiment in progress".bright_white());
+                return Ok(());
+            };
+            println!("{}", format!("🧪 Experiment started {}", trial.applied_at_display()).bright_cyan());
+            for issue in &trial.issues {
+                println!("   #{} {}", issue.num, issue.problem);
+            }
+            println!("   Keep if {:?} improves by at least {}%{}",
+                trial.metric, trial.min_improvement,
+                if trial.require_significant { " and the mean drops significantly" } else { "" });
+            print_latency_stats("Baseline", &experiment::Stats::from_samples(&trial.baseline));
+            if trial.waiting_for_reboot() {
+                println!("{}", "   Waiting for a reboot".bright_yellow());
+            } else {
+                println!("{}", "   Ready: run `apex experiment resume`".bright_white());
+            }
+            Ok(())
+        }
+        ExperimentAction::Abort => {
+            utils::privileges::require_admin()?;
+            let Some(trial) = experiment::load()? else {
+                println!("{}", "No experiment in progress".bright_white());
+                return Ok(());
+            };
+            let failures = trial.rollback()?;
+            experiment::clear()?;
+            print_rollback_result("⏮️  Experiment aborted and its fixes rolled back", &failures);
+            if trial.needs_reboot() {
+                println!("{}", "   Reboot for the previous settings to take effect again.".bright_white());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Second measurement, comparison and the keep-or-rollback decision.
+fn finish_experiment(trial: &experiment::Experiment) -> anyhow::Result<()> {
+    println!("{}", format!("⏱️  Measuring with the fixes applied ({} timer samples)...", trial.samples).bright_cyan());
+    let after = experiment::measure(trial.samples);
+    let comparison = experiment::Comparison::new(&trial.baseline, &after);
+    
+    println!();
+    println!("{}", "📊 Timer latency (µs, lower is better):".bright_white());
+    println!("   {:<8} {:>10} {:>10} {:>9}", "", "before", "after", "change");
+    for (label, metric) in [("mean", experiment::Metric::Mean), ("median", experiment::Metric::Median),
+                            ("p99", experiment::Metric::P99), ("max", experiment::Metric::Max)] {
+        println!("   {:<8} {:>10.1} {:>10.1} {:>8.1}%",
+            label, comparison.before.metric(metric), comparison.after.metric(metric), -comparison.improvement(metric));
+    }
+    println!("   Welch t = {:.2} ({})", comparison.t,
+        if comparison.significant() { "means differ significantly" } else { "difference in means is within noise" });
+    println!();
+    
+    let improvement = comparison.improvement(trial.metric);
+    if trial.passes(&comparison) {
+        experiment::clear()?;
+        println!("{}", format!("✅ {:?} improved by {:.1}%: keeping the fixes", trial.metric, improvement).bright_green());
+        return Ok(());
+    }
+    
+    println!("{}", format!("❌ {:?} changed by {:.1}% (needed {:.1}% improvement): rolling back",
+        trial.metric, -improvement, trial.min_improvement).bright_yellow());
+    let failures = trial.rollback()?;
+    experiment::clear()?;
+    print_rollback_result("⏮️  Fixes rolled back", &failures);
+    if trial.needs_reboot() {
+        println!("{}", "   Reboot for the previous settings to take effect again.".bright_white());
+    }
     Ok(())
 }
 
+/// Reports the backups a rollback could not restore, which are kept so
+/// `apex rollback <file>` can retry them.
+fn print_rollback_result(done: &str, failures: &[String]) {
+    if failures.is_empty() {
+        println!("{}", done.bright_green());
+        return;
+    }
+    println!("{}", format!("⚠️  {} change(s) could not be restored:", failures.len()).bright_yellow());
+    for failure in failures {
+        println!("   - {}", failure);
+    }
+    println!("{}", "   Retry each with `apex rollback <file>` or restore it by hand.".bright_white());
+}
+
+fn print_latency_stats(label: &str, stats: &experiment::Stats) {
+    println!("   {}: mean {:.1} µs, median {:.1} µs, p99 {:.1} µs, max {:.1} µs (σ {:.1}, n = {})",
+        label, stats.mean, stats.median, stats.p99, stats.max, stats.stddev, stats.count);
+}
+
 fn run_rollback(backup: &str) -> anyhow::Result<()> {
     utils::privileges::require_admin()?;
     
@@ -0,0 +1,135 @@
+use crate::types::AuditResults;
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::System;
+
+/// Saved audits older than this are reported as stale.
+const MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// Identifies the machine an audit was made on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub hostname: String,
+    /// SMBIOS system UUID, which belongs to the hardware and survives
+    /// reinstalls and renames.
+    #[serde(default)]
+    pub system_uuid: Option<String>,
+    /// `HKLM\SOFTWARE\Microsoft\Cryptography\MachineGuid`, which survives
+    /// renames but differs between installations.
+    pub machine_guid: Option<String>,
+    /// Includes the build, so it changes with Windows updates; not part of
+    /// the identity.
+    pub os: String,
+}
+
+impl Fingerprint {
+    pub fn current() -> Self {
+        Fingerprint {
+            hostname: System::host_name().unwrap_or_default(),
+            system_uuid: crate::hardware::smbios::table().ok().and_then(|t| t.system_uuid()),
+            machine_guid: machine_guid(),
+            os: System::long_os_version().unwrap_or_default(),
+        }
+    }
+
+    /// Compares the strongest identity both sides know: the system UUID,
+    /// then the machine GUID, then the hostname.
+    pub fn same_machine(&self, other: &Fingerprint) -> bool {
+        if let (Some(a), Some(b)) = (&self.system_uuid, &other.system_uuid) {
+            return a.eq_ignore_ascii_case(b);
+        }
+        if let (Some(a), Some(b)) = (&self.machine_guid, &other.machine_guid) {
+            return a.eq_ignore_ascii_case(b);
+        }
+        self.hostname.eq_ignore_ascii_case(&other.hostname)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn machine_guid() -> Option<String> {
+    use crate::utils::registry::{self, RegPath};
+
+    registry::read_string(&RegPath::hklm("SOFTWARE\\Microsoft\\Cryptography"), "MachineGuid").ok()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn machine_guid() -> Option<String> {
+    None
+}
+
+/// The results of the last audit, so `fix` acts on the issue numbers the
+/// user was shown.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditSession {
+    /// Unix timestamp of the audit.
+    pub created_at: u64,
+    pub fingerprint: Fingerprint,
+    pub results: AuditResults,
+}
+
+impl AuditSession {
+    pub fn created_at_display(&self) -> String {
+        Local
+            .timestamp_opt(self.created_at as i64, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| self.created_at.to_string())
+    }
+
+    pub fn same_machine(&self) -> bool {
+        self.fingerprint.same_machine(&Fingerprint::current())
+    }
+
+    /// The OS was updated or replaced since the audit.
+    pub fn os_changed(&self) -> bool {
+        self.fingerprint.os != Fingerprint::current().os
+    }
+
+    /// Too old to trust, or made before the last reboot; settings may have
+    /// changed since.
+    pub fn is_stale(&self) -> bool {
+        now().saturating_sub(self.created_at) > MAX_AGE_SECS || self.created_at < System::boot_time()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn session_path() -> PathBuf {
+    PathBuf::from("backups").join("last_audit.json")
+}
+
+/// The saved session, `None` when there is none. A file that cannot be
+/// read or parsed, e.g. from an older version, is an error.
+pub fn load() -> Result<Option<AuditSession>> {
+    let path = session_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&json)?))
+}
+
+pub fn save(results: &AuditResults) -> Result<()> {
+    let path = session_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let session = AuditSession {
+        created_at: now(),
+        fingerprint: Fingerprint::current(),
+        results: results.clone(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&session)?)?;
+    Ok(())
+}
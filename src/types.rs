@@ -1,3 +1,7 @@
+use crate::error::{Error, ErrorKind};
+use crate::hardware::HardwareInfo;
+use crate::utils::registry::RegPath;
+use crate::utils::services::StartType;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,6 +23,36 @@ impl Severity {
     }
 }
 
+/// How much care a fix needs. Ordered from least to most dangerous.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Safety {
+    /// Can be applied without risk
+    Safe,
+    /// Test after applying
+    Moderate,
+    /// Trades security or stability for performance
+    Risky,
+}
+
+impl Safety {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Safety::Safe => "Safe",
+            Safety::Moderate => "Moderate",
+            Safety::Risky => "Risky",
+        }
+    }
+
+    /// `fix` flag that allows fixes of this tier, if one is needed.
+    pub fn flag(&self) -> Option<&'static str> {
+        match self {
+            Safety::Safe => None,
+            Safety::Moderate => Some("--allow-moderate"),
+            Safety::Risky => Some("--allow-risky"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CheckStatus {
     Ok,
@@ -27,17 +61,100 @@ pub enum CheckStatus {
     Info,
 }
 
+/// A single system change made by a fix. Changes are plain data so they can
+/// be backed up, recorded for post-reboot verification and re-checked later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Change {
+    RegistryDword {
+        #[serde(flatten)]
+        key: RegPath,
+        value: String,
+        data: u32,
+    },
+    /// Removes a value so Windows falls back to its built-in default.
+    RegistryDelete {
+        #[serde(flatten)]
+        key: RegPath,
+        value: String,
+    },
+    ServiceStartType {
+        name: String,
+        start_type: StartType,
+        delayed_auto: bool,
+    },
+    ServiceRunning {
+        name: String,
+        running: bool,
+    },
+    /// Creates `destination` as a copy of `source` unless it already exists.
+    PowerSchemeCopy {
+        source: String,
+        destination: String,
+    },
+    ActivePowerScheme {
+        scheme: String,
+    },
+    /// AC/DC value indices of a power setting; `None` leaves that side alone.
+    PowerSetting {
+        scheme: String,
+        subgroup: String,
+        setting: String,
+        ac: Option<u32>,
+        dc: Option<u32>,
+    },
+}
+
+impl Change {
+    pub fn describe(&self) -> String {
+        match self {
+            Change::RegistryDword { key, value, data } => format!("{} = {}", key.value_path(value), data),
+            Change::RegistryDelete { key, value } => format!("{} deleted", key.value_path(value)),
+            Change::ServiceStartType { name, start_type, delayed_auto } => {
+                format!("service {} start type = {:?}{}", name, start_type, if *delayed_auto { " (delayed)" } else { "" })
+            }
+            Change::ServiceRunning { name, running } => {
+                format!("service {} {}", name, if *running { "running" } else { "stopped" })
+            }
+            Change::PowerSchemeCopy { source, destination } => {
+                format!("power scheme {} created from {}", destination, source)
+            }
+            Change::ActivePowerScheme { scheme } => {
+                format!("active power scheme = {}", scheme)
+            }
+            Change::PowerSetting { scheme, subgroup, setting, ac, dc } => {
+                let side = |v: &Option<u32>| v.map_or("unchanged".to_string(), |v| v.to_string());
+                format!("power {}\\{}\\{} AC = {}, DC = {}", scheme, subgroup, setting, side(ac), side(dc))
+            }
+        }
+    }
+
+    /// The setting this change writes. Two changes with the same target
+    /// (compared case-insensitively) touch the same thing.
+    pub fn target(&self) -> String {
+        match self {
+            Change::RegistryDword { key, value, .. } | Change::RegistryDelete { key, value } => key.value_path(value),
+            Change::ServiceStartType { name, .. } => format!("service {} start type", name),
+            Change::ServiceRunning { name, .. } => format!("service {} state", name),
+            Change::PowerSchemeCopy { destination, .. } => format!("power scheme {}", destination),
+            Change::ActivePowerScheme { .. } => "active power scheme".to_string(),
+            Change::PowerSetting { scheme, subgroup, setting, .. } => {
+                format!("power {}\\{}\\{}", scheme, subgroup, setting)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
     pub num: u32,
+    pub check: String,
     pub category: String,
     pub problem: String,
     pub solution: String,
     pub severity: Severity,
-    pub safe: bool,
+    pub safety: Safety,
     pub reboot: bool,
-    #[serde(skip)]
-    pub fix_fn: Option<fn() -> anyhow::Result<()>>,
+    pub changes: Vec<Change>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,15 +169,41 @@ pub struct CheckResult {
     pub category: String,
     pub status: CheckStatus,
     pub detail: String,
+    /// Set when the check could not read what it looks at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorKind>,
+}
+
+impl CheckResult {
+    /// An Info result for a check whose setting could not be read.
+    pub fn unreadable(name: &str, category: &str, error: &Error) -> Self {
+        // Not an error as such: the check does not apply on this platform
+        let (detail, error) = match error.kind() {
+            #[cfg(not(target_os = "windows"))]
+            ErrorKind::Unsupported => ("Windows-only check".to_string(), None),
+            kind => (error.to_string(), Some(kind)),
+        };
+        CheckResult {
+            name: name.to_string(),
+            category: category.to_string(),
+            status: CheckStatus::Info,
+            detail,
+            error,
+        }
+    }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AuditResults {
     pub issues: Vec<Issue>,
     pub warnings: Vec<Warning>,
     pub checks: Vec<CheckResult>,
     pub check_count: usize,
     pub score: i32,
+    /// Inventory of the audited machine; absent from sessions saved before
+    /// it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardware: Option<HardwareInfo>,
 }
 
 impl AuditResults {
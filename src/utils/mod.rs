@@ -1,2 +1,4 @@
 pub mod registry;
 pub mod privileges;
+pub mod services;
+pub mod powercfg;
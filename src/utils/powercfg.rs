@@ -0,0 +1,436 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+pub const SCHEME_HIGH_PERFORMANCE: &str = "8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c";
+pub const SCHEME_ULTIMATE_PERFORMANCE: &str = "e9a42b02-d5df-448d-aa00-03f14749eb61";
+
+/// GUID APEX gives its copy of the Ultimate Performance scheme, so the copy
+/// can be found and verified again later.
+pub const SCHEME_APEX_ULTIMATE: &str = "a9e5c0de-11a7-4e0b-9f3c-5dbe7a9c0011";
+
+pub const SUB_PROCESSOR: &str = "54533251-82be-4824-96c1-47b60b740d00";
+pub const SETTING_CORE_PARKING_MIN: &str = "0cc5b647-c1df-4637-891a-dec35c318583";
+pub const SETTING_BOOST_MODE: &str = "be337238-0d82-4146-a960-4f3749d470c7";
+pub const SETTING_THROTTLE_MIN: &str = "893dee8e-2bef-41e0-89c6-b55d0929964c";
+pub const SETTING_THROTTLE_MAX: &str = "bc5038f7-23e0-4960-96da-33abaf5935ec";
+pub const SETTING_IDLE_DISABLE: &str = "5d76a2ca-e8c0-402f-a133-2158492d58ad";
+
+/// A power scheme as listed by `powercfg /list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerScheme {
+    pub guid: String,
+    pub name: String,
+    pub active: bool,
+}
+
+/// One possible value of an enumerated setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PossibleValue {
+    pub index: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerSetting {
+    pub guid: String,
+    pub name: String,
+    pub alias: Option<String>,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+    pub increment: Option<u32>,
+    pub units: Option<String>,
+    pub values: Vec<PossibleValue>,
+    pub ac: Option<u32>,
+    pub dc: Option<u32>,
+}
+
+impl PowerSetting {
+    /// Friendly name of an index for enumerated settings, or the raw value
+    /// with its units for ranged ones.
+    pub fn display_value(&self, index: u32) -> String {
+        if let Some(value) = self.values.iter().find(|v| v.index == index) {
+            return value.name.clone();
+        }
+        match &self.units {
+            Some(units) => format!("{} {}", index, units),
+            None => index.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerSubgroup {
+    pub guid: String,
+    pub name: String,
+    pub alias: Option<String>,
+    pub settings: Vec<PowerSetting>,
+}
+
+/// Full contents of a scheme as printed by `powercfg /qh`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemeDetails {
+    pub guid: String,
+    pub name: String,
+    pub alias: Option<String>,
+    pub subgroups: Vec<PowerSubgroup>,
+}
+
+impl SchemeDetails {
+    pub fn setting(&self, subgroup: &str, setting: &str) -> Option<&PowerSetting> {
+        self.subgroups
+            .iter()
+            .find(|s| s.guid.eq_ignore_ascii_case(subgroup))?
+            .settings
+            .iter()
+            .find(|s| s.guid.eq_ignore_ascii_case(setting))
+    }
+}
+
+fn is_guid(s: &str) -> bool {
+    s.len() == 36
+        && s.chars().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// Splits `Label: 0123...-guid  (Friendly name) *` into GUID, name and the
+/// trailing active marker.
+fn parse_guid_line(line: &str) -> Option<(String, String, bool)> {
+    let (_, rest) = line.split_once(':')?;
+    let rest = rest.trim();
+    let guid = rest.get(..36)?;
+    if !is_guid(guid) {
+        return None;
+    }
+
+    let tail = rest[36..].trim();
+    let active = tail.ends_with('*');
+    let tail = tail.trim_end_matches('*').trim();
+    let name = tail
+        .strip_prefix('(')
+        .and_then(|t| t.strip_suffix(')'))
+        .unwrap_or(tail)
+        .to_string();
+
+    Some((guid.to_lowercase(), name, active))
+}
+
+fn label_value(line: &str) -> Option<(&str, &str)> {
+    let (label, value) = line.split_once(':')?;
+    Some((label.trim(), value.trim()))
+}
+
+fn parse_hex(value: &str) -> Option<u32> {
+    u32::from_str_radix(value.strip_prefix("0x").or_else(|| value.strip_prefix("0X"))?, 16).ok()
+}
+
+/// Parses `powercfg /list`.
+pub fn parse_list(output: &str) -> Vec<PowerScheme> {
+    output
+        .lines()
+        .filter_map(parse_guid_line)
+        .map(|(guid, name, active)| PowerScheme { guid, name, active })
+        .collect()
+}
+
+/// Parses `powercfg /q` or `/qh` output for a single scheme.
+///
+/// powercfg localizes its labels, so the structure is read from the
+/// indentation instead: schemes start at column 0, subgroups at 2, settings
+/// at 4 and setting attributes at 6. The two hex values at column 4 that
+/// close a setting are its AC and DC indices, in that order.
+pub fn parse_query(output: &str) -> Result<SchemeDetails> {
+    let mut details: Option<SchemeDetails> = None;
+    let mut pending_index: Option<u32> = None;
+    let mut range_values = 0;
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let text = line.trim();
+
+        if let Some((guid, name, _)) = parse_guid_line(text) {
+            match indent {
+                0 => {
+                    if details.is_some() {
+                        break;
+                    }
+                    details = Some(SchemeDetails { guid, name, ..Default::default() });
+                }
+                2 => {
+                    let scheme = details.as_mut().ok_or_else(|| Error::invalid("powercfg output", "subgroup before scheme"))?;
+                    scheme.subgroups.push(PowerSubgroup { guid, name, ..Default::default() });
+                }
+                4 => {
+                    let subgroup = details
+                        .as_mut()
+                        .and_then(|d| d.subgroups.last_mut())
+                        .ok_or_else(|| Error::invalid("powercfg output", "setting outside a subgroup"))?;
+                    subgroup.settings.push(PowerSetting { guid, name, ..Default::default() });
+                    range_values = 0;
+                    pending_index = None;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let Some(scheme) = details.as_mut() else { continue };
+        let Some((label, value)) = label_value(text) else { continue };
+        let is_alias = label.to_lowercase().contains("alias");
+
+        match indent {
+            2 if is_alias => scheme.alias = Some(value.to_string()),
+            4 if is_alias => {
+                if let Some(subgroup) = scheme.subgroups.last_mut() {
+                    subgroup.alias = Some(value.to_string());
+                }
+            }
+            4 => {
+                let Some(setting) = scheme.subgroups.last_mut().and_then(|s| s.settings.last_mut()) else { continue };
+                let Some(index) = parse_hex(value) else { continue };
+                if setting.ac.is_none() {
+                    setting.ac = Some(index);
+                } else {
+                    setting.dc = Some(index);
+                }
+            }
+            6 => {
+                let Some(setting) = scheme.subgroups.last_mut().and_then(|s| s.settings.last_mut()) else { continue };
+                if is_alias {
+                    setting.alias = Some(value.to_string());
+                } else if let Some(number) = parse_hex(value) {
+                    // Minimum, maximum and increment, always in that order
+                    match range_values {
+                        0 => setting.min = Some(number),
+                        1 => setting.max = Some(number),
+                        _ => setting.increment = Some(number),
+                    }
+                    range_values += 1;
+                } else if let Some(index) = pending_index.take() {
+                    setting.values.push(PossibleValue { index, name: value.to_string() });
+                } else if value.len() == 3 && value.chars().all(|c| c.is_ascii_digit()) {
+                    pending_index = value.parse().ok();
+                } else if range_values > 0 {
+                    setting.units = Some(value.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    details.ok_or_else(|| Error::invalid("powercfg output", "no power scheme found"))
+}
+
+/// Runs powercfg and returns its standard output, failing on a non-zero exit
+/// status with powercfg's own message.
+fn powercfg(args: &[&str]) -> Result<String> {
+    let output = Command::new("powercfg")
+        .args(args)
+        .output()
+        .map_err(|e| Error::io(e, "powercfg"))?;
+
+    let stdout = decode(&output.stdout);
+    if !output.status.success() {
+        let stderr = decode(&output.stderr);
+        let message = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+        return Err(Error::CommandFailed { command: format!("powercfg {}", args.join(" ")), message: message.to_string() });
+    }
+
+    Ok(stdout)
+}
+
+/// powercfg writes in the console code page, the OEM code page when there
+/// is no console, so localized names are not UTF-8.
+#[cfg(target_os = "windows")]
+fn decode(bytes: &[u8]) -> String {
+    use windows::Win32::Globalization::{MultiByteToWideChar, CP_OEMCP, MULTI_BYTE_TO_WIDE_CHAR_FLAGS};
+    use windows::Win32::System::Console::GetConsoleOutputCP;
+
+    if bytes.is_empty() {
+        return String::new();
+    }
+    let code_page = match unsafe { GetConsoleOutputCP() } {
+        0 => CP_OEMCP,
+        code_page => code_page,
+    };
+    let flags = MULTI_BYTE_TO_WIDE_CHAR_FLAGS(0);
+    let len = unsafe { MultiByteToWideChar(code_page, flags, bytes, None) };
+    if len <= 0 {
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
+    let mut wide = vec![0u16; len as usize];
+    let written = unsafe { MultiByteToWideChar(code_page, flags, bytes, Some(&mut wide)) };
+    String::from_utf16_lossy(&wide[..written.max(0) as usize])
+}
+
+#[cfg(not(target_os = "windows"))]
+fn decode(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+pub fn list_schemes() -> Result<Vec<PowerScheme>> {
+    Ok(parse_list(&powercfg(&["/list"])?))
+}
+
+pub fn active_scheme() -> Result<PowerScheme> {
+    list_schemes()?
+        .into_iter()
+        .find(|s| s.active)
+        .ok_or_else(|| Error::not_found("active power scheme"))
+}
+
+/// Queries all settings of a scheme, including hidden ones. `scheme` can be a
+/// GUID or an alias such as `SCHEME_CURRENT`.
+pub fn query_scheme(scheme: &str) -> Result<SchemeDetails> {
+    parse_query(&powercfg(&["/qh", scheme])?)
+}
+
+pub fn duplicate_scheme(source: &str, destination: &str) -> Result<()> {
+    powercfg(&["/duplicatescheme", source, destination]).map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+pub fn delete_scheme(scheme: &str) -> Result<()> {
+    powercfg(&["/delete", scheme]).map(|_| ())
+}
+
+pub fn set_active(scheme: &str) -> Result<()> {
+    powercfg(&["/setactive", scheme]).map(|_| ())
+}
+
+pub fn set_value(scheme: &str, subgroup: &str, setting: &str, ac: u32, dc: u32) -> Result<()> {
+    powercfg(&["/setacvalueindex", scheme, subgroup, setting, &ac.to_string()])?;
+    powercfg(&["/setdcvalueindex", scheme, subgroup, setting, &dc.to_string()])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEME_BALANCED: &str = "381b4222-f694-41f0-9685-ff5bb260df2e";
+
+    const LIST: &str = "
+Existing Power Schemes (* Active)
+-----------------------------------
+Power Scheme GUID: 381b4222-f694-41f0-9685-ff5bb260df2e  (Balanced)
+Power Scheme GUID: 8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c  (High performance) *
+Power Scheme GUID: a1841308-3541-4fab-bc81-f71556f20b4a  (Power saver)
+";
+
+    const LIST_DE: &str = "
+Vorhandene Energieschemas (* Aktiv)
+-----------------------------------
+GUID des Energieschemas: 381B4222-F694-41F0-9685-FF5BB260DF2E  (Ausbalanciert) *
+GUID des Energieschemas: a9e5c0de-11a7-4e0b-9f3c-5dbe7a9c0011  (Ultimative Leistung (APEX))
+";
+
+    const QUERY: &str = "Power Scheme GUID: 381b4222-f694-41f0-9685-ff5bb260df2e  (Balanced)
+  GUID Alias: SCHEME_BALANCED
+  Subgroup GUID: 54533251-82be-4824-96c1-47b60b740d00  (Processor power management)
+    GUID Alias: SUB_PROCESSOR
+    Power Setting GUID: 893dee8e-2bef-41e0-89c6-b55d0929964c  (Minimum processor state)
+      GUID Alias: PROCTHROTTLEMIN
+      Minimum Possible Setting: 0x00000000
+      Maximum Possible Setting: 0x00000064
+      Possible Settings increment: 0x00000001
+      Possible Settings units: %
+    Current AC Power Setting Index: 0x00000064
+    Current DC Power Setting Index: 0x00000005
+    Power Setting GUID: be337238-0d82-4146-a960-4f3749d470c7  (Processor performance boost mode)
+      GUID Alias: PERFBOOSTMODE
+      Possible Setting Index: 000
+      Possible Setting Friendly Name: Disabled
+      Possible Setting Index: 001
+      Possible Setting Friendly Name: Enabled
+      Possible Setting Index: 002
+      Possible Setting Friendly Name: Aggressive
+    Current AC Power Setting Index: 0x00000002
+    Current DC Power Setting Index: 0x00000001
+";
+
+    // A scheme other than the active one, queried with `/qh <guid>` on a
+    // German system
+    const QUERY_DE: &str = "GUID des Energieschemas: 8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c  (Höchstleistung)
+  GUID-Alias: SCHEME_MIN
+  GUID der Untergruppe: 54533251-82be-4824-96c1-47b60b740d00  (Prozessorenergieverwaltung)
+    GUID-Alias: SUB_PROCESSOR
+    GUID der Energieeinstellung: 0cc5b647-c1df-4637-891a-dec35c318583  (Minimale Anzahl der Prozessorkerne im Parkzustand)
+      GUID-Alias: CPMINCORES
+      Minimale mögliche Einstellung: 0x00000000
+      Maximale mögliche Einstellung: 0x00000064
+      Mögliche Einstellungen (Inkrement): 0x00000001
+      Mögliche Einstellungen (Einheiten): %
+    Aktueller Wechselstrom-Einstellungsindex: 0x00000064
+    Aktueller Gleichstrom-Einstellungsindex: 0x0000000a
+";
+
+    #[test]
+    fn list_reads_schemes_and_active_marker() {
+        let schemes = parse_list(LIST);
+        let summary: Vec<(&str, &str, bool)> =
+            schemes.iter().map(|s| (s.guid.as_str(), s.name.as_str(), s.active)).collect();
+        assert_eq!(summary, [
+            (SCHEME_BALANCED, "Balanced", false),
+            (SCHEME_HIGH_PERFORMANCE, "High performance", true),
+            ("a1841308-3541-4fab-bc81-f71556f20b4a", "Power saver", false),
+        ]);
+    }
+
+    #[test]
+    fn list_reads_localized_output() {
+        let schemes = parse_list(LIST_DE);
+        assert_eq!(schemes.len(), 2);
+        assert_eq!(schemes[0].guid, SCHEME_BALANCED);
+        assert_eq!(schemes[0].name, "Ausbalanciert");
+        assert!(schemes[0].active);
+        assert_eq!(schemes[1].guid, SCHEME_APEX_ULTIMATE);
+        assert_eq!(schemes[1].name, "Ultimative Leistung (APEX)");
+        assert!(!schemes[1].active);
+    }
+
+    #[test]
+    fn query_reads_ranged_and_enumerated_settings() {
+        let details = parse_query(QUERY).unwrap();
+        assert_eq!(details.guid, SCHEME_BALANCED);
+        assert_eq!(details.alias.as_deref(), Some("SCHEME_BALANCED"));
+        assert_eq!(details.subgroups.len(), 1);
+        assert_eq!(details.subgroups[0].alias.as_deref(), Some("SUB_PROCESSOR"));
+
+        let throttle = details.setting(SUB_PROCESSOR, SETTING_THROTTLE_MIN).unwrap();
+        assert_eq!(throttle.name, "Minimum processor state");
+        assert_eq!(throttle.alias.as_deref(), Some("PROCTHROTTLEMIN"));
+        assert_eq!((throttle.min, throttle.max, throttle.increment), (Some(0), Some(100), Some(1)));
+        assert_eq!(throttle.units.as_deref(), Some("%"));
+        assert_eq!((throttle.ac, throttle.dc), (Some(100), Some(5)));
+
+        let boost = details.setting(SUB_PROCESSOR, SETTING_BOOST_MODE).unwrap();
+        let values: Vec<(u32, &str)> = boost.values.iter().map(|v| (v.index, v.name.as_str())).collect();
+        assert_eq!(values, [(0, "Disabled"), (1, "Enabled"), (2, "Aggressive")]);
+        assert_eq!((boost.ac, boost.dc), (Some(2), Some(1)));
+        assert_eq!(boost.display_value(2), "Aggressive");
+    }
+
+    #[test]
+    fn query_reads_localized_output() {
+        let details = parse_query(QUERY_DE).unwrap();
+        assert_eq!(details.guid, SCHEME_HIGH_PERFORMANCE);
+        assert_eq!(details.name, "Höchstleistung");
+
+        let parking = details.setting(SUB_PROCESSOR, SETTING_CORE_PARKING_MIN).unwrap();
+        assert_eq!(parking.alias.as_deref(), Some("CPMINCORES"));
+        assert_eq!((parking.min, parking.max, parking.increment), (Some(0), Some(100), Some(1)));
+        assert_eq!((parking.ac, parking.dc), (Some(100), Some(10)));
+        assert_eq!(parking.display_value(10), "10 %");
+    }
+
+    #[test]
+    fn query_without_scheme_is_an_error() {
+        assert!(parse_query("").is_err());
+    }
+}
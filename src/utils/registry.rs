@@ -1,163 +1,578 @@
-use anyhow::Result;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
 
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Registry::*;
 #[cfg(target_os = "windows")]
-use windows::core::PCWSTR;
+use windows::core::{PCWSTR, PWSTR};
 #[cfg(target_os = "windows")]
-use std::ptr;
+use windows::Win32::Foundation::{WIN32_ERROR, ERROR_FILE_NOT_FOUND, ERROR_MORE_DATA, ERROR_NO_MORE_ITEMS};
 
-#[cfg(target_os = "windows")]
-pub fn read_dword(key: HKEY, subkey: &str, value: &str) -> Result<u32> {
-    unsafe {
-        let mut hkey = HKEY::default();
-        let subkey_wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
-        
-        let result = RegOpenKeyExW(
-            key,
-            PCWSTR(subkey_wide.as_ptr()),
-            0,
-            KEY_READ,
-            &mut hkey,
-        );
-        
-        if result.is_err() {
-            return Err(anyhow::anyhow!("Failed to open registry key: {}", subkey));
-        }
-
-        let value_wide: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
-        let mut data: u32 = 0;
-        let mut data_size = std::mem::size_of::<u32>() as u32;
-        let mut data_type: u32 = 0;
-
-        let result = RegQueryValueExW(
-            hkey,
-            PCWSTR(value_wide.as_ptr()),
-            Some(ptr::null_mut()),
-            Some(&mut data_type),
-            Some(&mut data as *mut u32 as *mut u8),
-            Some(&mut data_size),
-        );
-
-        let _ = RegCloseKey(hkey);
+/// Registry root a value lives under.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Hive {
+    #[default]
+    LocalMachine,
+    CurrentUser,
+    /// `HKEY_USERS`; the first subkey component is a SID or `.DEFAULT`.
+    Users,
+}
 
-        if result.is_ok() {
-            Ok(data)
+impl Hive {
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Hive::LocalMachine => "HKLM",
+            Hive::CurrentUser => "HKCU",
+            Hive::Users => "HKU",
+        }
+    }
+
+    pub fn full_name(&self) -> &'static str {
+        match self {
+            Hive::LocalMachine => "HKEY_LOCAL_MACHINE",
+            Hive::CurrentUser => "HKEY_CURRENT_USER",
+            Hive::Users => "HKEY_USERS",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Hive::LocalMachine, Hive::CurrentUser, Hive::Users]
+            .into_iter()
+            .find(|h| name.eq_ignore_ascii_case(h.short_name()) || name.eq_ignore_ascii_case(h.full_name()))
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn hkey(&self) -> HKEY {
+        match self {
+            Hive::LocalMachine => HKEY_LOCAL_MACHINE,
+            Hive::CurrentUser => HKEY_CURRENT_USER,
+            Hive::Users => HKEY_USERS,
+        }
+    }
+}
+
+/// Which registry a 32-bit/64-bit redirected key is opened in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum View {
+    /// Whatever view the running process gets.
+    #[default]
+    Native,
+    Bit64,
+    Bit32,
+}
+
+impl View {
+    pub fn is_native(&self) -> bool {
+        *self == View::Native
+    }
+
+    #[cfg(target_os = "windows")]
+    fn flag(&self) -> REG_SAM_FLAGS {
+        match self {
+            View::Native => REG_SAM_FLAGS(0),
+            View::Bit64 => KEY_WOW64_64KEY,
+            View::Bit32 => KEY_WOW64_32KEY,
+        }
+    }
+}
+
+/// A key, e.g. `HKLM\SYSTEM\CurrentControlSet\Control\PriorityControl`.
+/// Parses from the short and long hive names and prints with the short one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "RegPathRepr")]
+pub struct RegPath {
+    pub hive: Hive,
+    pub subkey: Cow<'static, str>,
+    pub view: View,
+}
+
+/// Saved as `"key": "HKLM\\..."`. Files written before that use separate
+/// `hive` and `subkey` fields, and backups older still only a `subkey`
+/// under HKLM.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RegPathRepr {
+    Path {
+        key: String,
+        #[serde(default)]
+        view: View,
+    },
+    Parts {
+        #[serde(default)]
+        hive: Hive,
+        subkey: String,
+        #[serde(default)]
+        view: View,
+    },
+}
+
+impl TryFrom<RegPathRepr> for RegPath {
+    type Error = Error;
+
+    fn try_from(repr: RegPathRepr) -> Result<Self> {
+        match repr {
+            RegPathRepr::Path { key, view } => Ok(key.parse::<RegPath>()?.with_view(view)),
+            RegPathRepr::Parts { hive, subkey, view } => Ok(RegPath::new(hive, &subkey).with_view(view)),
+        }
+    }
+}
+
+impl Serialize for RegPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Out {
+            key: String,
+            #[serde(skip_serializing_if = "View::is_native")]
+            view: View,
+        }
+
+        Out { key: format!("{}\\{}", self.hive.short_name(), self.subkey), view: self.view }.serialize(serializer)
+    }
+}
+
+impl RegPath {
+    pub const fn hklm(subkey: &'static str) -> Self {
+        RegPath { hive: Hive::LocalMachine, subkey: Cow::Borrowed(subkey), view: View::Native }
+    }
+
+    pub const fn hkcu(subkey: &'static str) -> Self {
+        RegPath { hive: Hive::CurrentUser, subkey: Cow::Borrowed(subkey), view: View::Native }
+    }
+
+    pub fn new(hive: Hive, subkey: &str) -> Self {
+        RegPath { hive, subkey: Cow::Owned(subkey.trim_matches('\\').to_string()), view: View::Native }
+    }
+
+    pub fn with_view(mut self, view: View) -> Self {
+        self.view = view;
+        self
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn join(&self, child: &str) -> Self {
+        let child = child.trim_matches('\\');
+        let subkey = if self.subkey.is_empty() {
+            child.to_string()
         } else {
-            Err(anyhow::anyhow!("Failed to read registry value: {}", value))
+            format!("{}\\{}", self.subkey, child)
+        };
+        RegPath { hive: self.hive, subkey: Cow::Owned(subkey), view: self.view }
+    }
+
+    /// The subkey as seen from the 64-bit registry, where the 32-bit view
+    /// of `HKLM\SOFTWARE` lives under `WOW6432Node`. For exports, which
+    /// cannot select a view.
+    pub fn physical_subkey(&self) -> String {
+        if self.view == View::Bit32 && self.hive == Hive::LocalMachine {
+            let rest = self.subkey.get(8..).filter(|_| self.subkey[..8].eq_ignore_ascii_case("SOFTWARE"));
+            if let Some(rest) = rest.filter(|r| r.is_empty() || r.starts_with('\\')) {
+                return format!("SOFTWARE\\WOW6432Node{}", rest);
+            }
+        }
+        self.subkey.to_string()
+    }
+
+    /// Path for the PowerShell registry provider, which only has drives for
+    /// HKLM and HKCU.
+    pub fn provider_path(&self) -> String {
+        match self.hive {
+            Hive::Users => format!("Registry::{}\\{}", self.hive.full_name(), self.physical_subkey()),
+            _ => format!("{}:\\{}", self.hive.short_name(), self.physical_subkey()),
+        }
+    }
+
+    /// A value under this key, with the view last so the path stays intact.
+    pub fn value_path(&self, value: &str) -> String {
+        let path = format!("{}\\{}\\{}", self.hive.short_name(), self.subkey, value);
+        match self.view {
+            View::Native => path,
+            View::Bit64 => format!("{} (64-bit)", path),
+            View::Bit32 => format!("{} (32-bit)", path),
+        }
+    }
+
+    /// Path with the long hive name, as used by `.reg` files.
+    pub fn full_path(&self) -> String {
+        format!("{}\\{}", self.hive.full_name(), self.physical_subkey())
+    }
+}
+
+impl fmt::Display for RegPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.hive.short_name())?;
+        if !self.subkey.is_empty() {
+            write!(f, "\\{}", self.subkey)?;
+        }
+        match self.view {
+            View::Native => Ok(()),
+            View::Bit64 => write!(f, " (64-bit)"),
+            View::Bit32 => write!(f, " (32-bit)"),
         }
     }
 }
 
-#[cfg(not(target_os = "windows"))]
-pub fn read_dword(_key: usize, _subkey: &str, _value: &str) -> Result<u32> {
-    Err(anyhow::anyhow!("Registry operations only supported on Windows"))
+impl FromStr for RegPath {
+    type Err = Error;
+
+    /// Accepts `HKLM\...`, `HKEY_LOCAL_MACHINE\...`, `HKLM:\...` and
+    /// `HKU\<SID>\...`, with or without a `Registry::` prefix.
+    fn from_str(path: &str) -> Result<Self> {
+        let trimmed = path.trim().trim_end_matches('\\');
+        let trimmed = trimmed
+            .get(..10)
+            .filter(|p| p.eq_ignore_ascii_case("Registry::"))
+            .map_or(trimmed, |_| &trimmed[10..]);
+        let (root, subkey) = trimmed.split_once('\\').unwrap_or((trimmed, ""));
+
+        let hive = Hive::from_name(root.trim_end_matches(':'))
+            .ok_or_else(|| Error::invalid(path, "unknown registry hive, expected HKLM, HKCU or HKU"))?;
+        if subkey.split('\\').any(|part| part.is_empty()) && !subkey.is_empty() {
+            return Err(Error::invalid(path, "empty key name in registry path"));
+        }
+        Ok(RegPath::new(hive, subkey))
+    }
 }
 
+/// A registry value with its type.
 #[cfg(target_os = "windows")]
-pub fn write_dword(key: HKEY, subkey: &str, value: &str, data: u32) -> Result<()> {
-    unsafe {
-        let mut hkey = HKEY::default();
-        let subkey_wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
-        
-        let result = RegOpenKeyExW(
-            key,
-            PCWSTR(subkey_wide.as_ptr()),
-            0,
-            KEY_WRITE,
-            &mut hkey,
-        );
-        
-        if result.is_err() {
-            return Err(anyhow::anyhow!("Failed to open registry key for writing: {}", subkey));
-        }
-
-        let value_wide: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
-        
-        let result = RegSetValueExW(
-            hkey,
-            PCWSTR(value_wide.as_ptr()),
-            0,
-            REG_DWORD,
-            Some(&data as *const u32 as *const u8),
-            std::mem::size_of::<u32>() as u32,
-        );
-
-        let _ = RegCloseKey(hkey);
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegValue {
+    None,
+    Sz(String),
+    /// A string with unexpanded `%VARIABLE%` references.
+    ExpandSz(String),
+    MultiSz(Vec<String>),
+    Dword(u32),
+    Qword(u64),
+    Binary(Vec<u8>),
+}
 
-        if result.is_ok() {
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Failed to write registry value: {}", value))
+#[cfg(target_os = "windows")]
+impl RegValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            RegValue::None => "REG_NONE",
+            RegValue::Sz(_) => "REG_SZ",
+            RegValue::ExpandSz(_) => "REG_EXPAND_SZ",
+            RegValue::MultiSz(_) => "REG_MULTI_SZ",
+            RegValue::Dword(_) => "REG_DWORD",
+            RegValue::Qword(_) => "REG_QWORD",
+            RegValue::Binary(_) => "REG_BINARY",
         }
     }
+
+    /// The `REG_*` type constant.
+    pub fn raw_type(&self) -> u32 {
+        match self {
+            RegValue::None => 0,
+            RegValue::Sz(_) => 1,
+            RegValue::ExpandSz(_) => 2,
+            RegValue::Binary(_) => 3,
+            RegValue::Dword(_) => 4,
+            RegValue::MultiSz(_) => 7,
+            RegValue::Qword(_) => 11,
+        }
+    }
+
+    /// Decodes value data as returned by the registry. Types without a
+    /// variant of their own (links, resource lists) and integers of the
+    /// wrong size are kept as raw bytes.
+    pub fn from_raw(raw_type: u32, data: &[u8]) -> Self {
+        match raw_type {
+            0 => RegValue::None,
+            1 => RegValue::Sz(first_string(data)),
+            2 => RegValue::ExpandSz(first_string(data)),
+            4 if data.len() >= 4 => RegValue::Dword(u32::from_le_bytes([data[0], data[1], data[2], data[3]])),
+            7 => RegValue::MultiSz(
+                utf16(data)
+                    .split(|&c| c == 0)
+                    .take_while(|s| !s.is_empty())
+                    .map(String::from_utf16_lossy)
+                    .collect(),
+            ),
+            11 if data.len() >= 8 => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&data[..8]);
+                RegValue::Qword(u64::from_le_bytes(bytes))
+            }
+            _ => RegValue::Binary(data.to_vec()),
+        }
+    }
+
+    /// Encodes the value as registry data, with the terminating nulls
+    /// string types need.
+    pub fn to_raw(&self) -> Vec<u8> {
+        let encode = |s: &str| s.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+        let wide: Vec<u16> = match self {
+            RegValue::None => return Vec::new(),
+            RegValue::Dword(v) => return v.to_le_bytes().to_vec(),
+            RegValue::Qword(v) => return v.to_le_bytes().to_vec(),
+            RegValue::Binary(bytes) => return bytes.clone(),
+            RegValue::Sz(s) | RegValue::ExpandSz(s) => encode(s),
+            RegValue::MultiSz(strings) => strings
+                .iter()
+                .flat_map(|s| encode(s))
+                .chain(std::iter::once(0))
+                .collect(),
+        };
+        wide.iter().flat_map(|c| c.to_le_bytes()).collect()
+    }
 }
 
-#[cfg(not(target_os = "windows"))]
-pub fn write_dword(_key: usize, _subkey: &str, _value: &str, _data: u32) -> Result<()> {
-    Err(anyhow::anyhow!("Registry operations only supported on Windows"))
+#[cfg(target_os = "windows")]
+impl std::fmt::Display for RegValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegValue::None => write!(f, "(none)"),
+            RegValue::Sz(s) | RegValue::ExpandSz(s) => write!(f, "{}", s),
+            RegValue::MultiSz(strings) => write!(f, "{}", strings.join("; ")),
+            RegValue::Dword(v) => write!(f, "{}", v),
+            RegValue::Qword(v) => write!(f, "{}", v),
+            RegValue::Binary(bytes) => {
+                let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                write!(f, "{}", hex.join(" "))
+            }
+        }
+    }
 }
 
 #[cfg(target_os = "windows")]
-pub fn read_string(key: HKEY, subkey: &str, value: &str) -> Result<String> {
-    unsafe {
-        let mut hkey = HKEY::default();
-        let subkey_wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
-        
-        let result = RegOpenKeyExW(
-            key,
-            PCWSTR(subkey_wide.as_ptr()),
-            0,
-            KEY_READ,
-            &mut hkey,
-        );
-        
-        if result.is_err() {
-            return Err(anyhow::anyhow!("Failed to open registry key: {}", subkey));
-        }
-
-        let value_wide: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
-        let mut data_size: u32 = 0;
-        let mut data_type: u32 = 0;
-
-        // First call to get size
-        let result = RegQueryValueExW(
-            hkey,
-            PCWSTR(value_wide.as_ptr()),
-            Some(ptr::null_mut()),
-            Some(&mut data_type),
-            None,
-            Some(&mut data_size),
-        );
-
-        if result.is_err() {
-            let _ = RegCloseKey(hkey);
-            return Err(anyhow::anyhow!("Failed to query registry value size: {}", value));
-        }
-
-        let mut buffer: Vec<u16> = vec![0; (data_size / 2) as usize];
-        
-        let result = RegQueryValueExW(
-            hkey,
-            PCWSTR(value_wide.as_ptr()),
-            Some(ptr::null_mut()),
-            Some(&mut data_type),
-            Some(buffer.as_mut_ptr() as *mut u8),
-            Some(&mut data_size),
-        );
-
-        let _ = RegCloseKey(hkey);
+fn utf16(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect()
+}
+
+/// Registry strings are not always null-terminated, and may carry garbage
+/// after the first null.
+#[cfg(target_os = "windows")]
+fn first_string(data: &[u8]) -> String {
+    let wide = utf16(data);
+    let end = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..end])
+}
+
+#[cfg(target_os = "windows")]
+fn type_mismatch(key: &RegPath, value: &str, expected: &'static str, found: &RegValue) -> Error {
+    Error::WrongType { context: key.value_path(value), expected, found: found.type_name() }
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_dword(key: &RegPath, value: &str) -> Result<u32> {
+    match read_value(key, value)? {
+        RegValue::Dword(v) => Ok(v),
+        other => Err(type_mismatch(key, value, "REG_DWORD", &other)),
+    }
+}
+
+/// Reads a REG_SZ, or a REG_EXPAND_SZ without expanding it.
+#[cfg(target_os = "windows")]
+pub fn read_string(key: &RegPath, value: &str) -> Result<String> {
+    match read_value(key, value)? {
+        RegValue::Sz(s) | RegValue::ExpandSz(s) => Ok(s),
+        other => Err(type_mismatch(key, value, "REG_SZ", &other)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_multi_string(key: &RegPath, value: &str) -> Result<Vec<String>> {
+    match read_value(key, value)? {
+        RegValue::MultiSz(strings) => Ok(strings),
+        other => Err(type_mismatch(key, value, "REG_MULTI_SZ", &other)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_binary(key: &RegPath, value: &str) -> Result<Vec<u8>> {
+    match read_value(key, value)? {
+        RegValue::Binary(bytes) => Ok(bytes),
+        other => Err(type_mismatch(key, value, "REG_BINARY", &other)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn write_dword(key: &RegPath, value: &str, data: u32) -> Result<()> {
+    write_value(key, value, &RegValue::Dword(data))
+}
+
+#[cfg(target_os = "windows")]
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
 
+/// An open key, closed when dropped.
+#[cfg(target_os = "windows")]
+pub struct RegKey {
+    hkey: HKEY,
+    path: RegPath,
+}
+
+#[cfg(target_os = "windows")]
+impl RegKey {
+    unsafe fn open_with(path: &RegPath, access: REG_SAM_FLAGS) -> std::result::Result<Self, WIN32_ERROR> {
+        let mut hkey = HKEY::default();
+        let subkey_wide = wide(&path.subkey);
+        let result = RegOpenKeyExW(path.hive.hkey(), PCWSTR(subkey_wide.as_ptr()), 0, access | path.view.flag(), &mut hkey);
         if result.is_ok() {
-            let s = String::from_utf16_lossy(&buffer);
-            Ok(s.trim_end_matches('\0').to_string())
+            Ok(RegKey { hkey, path: path.clone() })
         } else {
-            Err(anyhow::anyhow!("Failed to read registry string value: {}", value))
+            Err(result)
+        }
+    }
+
+    pub fn open(path: &RegPath) -> Result<Self> {
+        unsafe { Self::open_with(path, KEY_READ) }.map_err(|e| Error::os(e.0, path.to_string()))
+    }
+
+    /// Opens a key for writing, creating it and any missing parents.
+    pub fn create(path: &RegPath) -> Result<Self> {
+        unsafe {
+            let mut hkey = HKEY::default();
+            let subkey_wide = wide(&path.subkey);
+
+            let result = RegCreateKeyExW(
+                path.hive.hkey(),
+                PCWSTR(subkey_wide.as_ptr()),
+                0,
+                PCWSTR::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_READ | KEY_WRITE | path.view.flag(),
+                None,
+                &mut hkey,
+                None,
+            );
+
+            if result.is_ok() {
+                Ok(RegKey { hkey, path: path.clone() })
+            } else {
+                Err(Error::os(result.0, path.to_string()))
+            }
+        }
+    }
+
+    pub fn read_value(&self, value: &str) -> Result<RegValue> {
+        unsafe {
+            let value_wide = wide(value);
+            let mut data_type = REG_VALUE_TYPE::default();
+            let mut size: u32 = 0;
+
+            // First call to get size
+            let result = RegQueryValueExW(self.hkey, PCWSTR(value_wide.as_ptr()), None, Some(&mut data_type), None, Some(&mut size));
+            if result.is_err() {
+                return Err(Error::os(result.0, self.path.value_path(value)));
+            }
+
+            loop {
+                let mut buffer = vec![0u8; size as usize];
+                let result = RegQueryValueExW(
+                    self.hkey,
+                    PCWSTR(value_wide.as_ptr()),
+                    None,
+                    Some(&mut data_type),
+                    Some(buffer.as_mut_ptr()),
+                    Some(&mut size),
+                );
+
+                // The value grew between the calls; `size` holds the new length
+                if result == ERROR_MORE_DATA {
+                    continue;
+                }
+                if result.is_err() {
+                    return Err(Error::os(result.0, self.path.value_path(value)));
+                }
+                buffer.truncate(size as usize);
+                return Ok(RegValue::from_raw(data_type.0, &buffer));
+            }
+        }
+    }
+
+    pub fn write_value(&self, value: &str, data: &RegValue) -> Result<()> {
+        unsafe {
+            let value_wide = wide(value);
+            let bytes = data.to_raw();
+            let result = RegSetValueExW(self.hkey, PCWSTR(value_wide.as_ptr()), 0, REG_VALUE_TYPE(data.raw_type()), Some(bytes.as_slice()));
+
+            if result.is_ok() {
+                Ok(())
+            } else {
+                Err(Error::os(result.0, self.path.value_path(value)))
+            }
+        }
+    }
+
+    /// A value that does not exist counts as deleted.
+    pub fn delete_value(&self, value: &str) -> Result<()> {
+        unsafe {
+            let value_wide = wide(value);
+            let result = RegDeleteValueW(self.hkey, PCWSTR(value_wide.as_ptr()));
+
+            if result.is_ok() || result == ERROR_FILE_NOT_FOUND {
+                Ok(())
+            } else {
+                Err(Error::os(result.0, self.path.value_path(value)))
+            }
+        }
+    }
+
+    /// Names of the direct subkeys.
+    pub fn subkeys(&self) -> Result<Vec<String>> {
+        unsafe {
+            let mut max_name: u32 = 0;
+            let _ = RegQueryInfoKeyW(self.hkey, PWSTR::null(), None, None, None, Some(&mut max_name), None, None, None, None, None, None);
+
+            let mut names = Vec::new();
+            let mut buffer = vec![0u16; max_name as usize + 1];
+            let mut index = 0;
+            loop {
+                let mut len = buffer.len() as u32;
+                let result = RegEnumKeyExW(self.hkey, index, PWSTR(buffer.as_mut_ptr()), &mut len, None, PWSTR::null(), None, None);
+                if result == ERROR_NO_MORE_ITEMS {
+                    return Ok(names);
+                }
+                if result.is_err() {
+                    return Err(Error::os(result.0, self.path.to_string()));
+                }
+                names.push(String::from_utf16_lossy(&buffer[..len as usize]));
+                index += 1;
+            }
+        }
+    }
+
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for RegKey {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = RegCloseKey(self.hkey);
         }
     }
 }
 
-#[cfg(not(target_os = "windows"))]
-pub fn read_string(_key: usize, _subkey: &str, _value: &str) -> Result<String> {
-    Err(anyhow::anyhow!("Registry operations only supported on Windows"))
+#[cfg(target_os = "windows")]
+pub fn read_value(key: &RegPath, value: &str) -> Result<RegValue> {
+    RegKey::open(key)?.read_value(value)
 }
+
+/// Writes a value, creating the key if it does not exist.
+#[cfg(target_os = "windows")]
+pub fn write_value(key: &RegPath, value: &str, data: &RegValue) -> Result<()> {
+    RegKey::create(key)?.write_value(value, data)
+}
+
+/// Deletes a value. A value or key that does not exist counts as deleted.
+#[cfg(target_os = "windows")]
+pub fn delete_value(key: &RegPath, value: &str) -> Result<()> {
+    match unsafe { RegKey::open_with(key, KEY_SET_VALUE) } {
+        Ok(handle) => handle.delete_value(value),
+        Err(ERROR_FILE_NOT_FOUND) => Ok(()),
+        Err(e) => Err(Error::os(e.0, key.to_string())),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn enum_subkeys(key: &RegPath) -> Result<Vec<String>> {
+    RegKey::open(key)?.subkeys()
+}
+
@@ -0,0 +1,289 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Services::*;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::*;
+#[cfg(target_os = "windows")]
+use windows::core::PCWSTR;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartType {
+    Boot,
+    System,
+    Automatic,
+    Manual,
+    Disabled,
+}
+
+impl StartType {
+    #[cfg(target_os = "windows")]
+    pub fn from_raw(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(StartType::Boot),
+            1 => Some(StartType::System),
+            2 => Some(StartType::Automatic),
+            3 => Some(StartType::Manual),
+            4 => Some(StartType::Disabled),
+            _ => None,
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn raw(&self) -> u32 {
+        match self {
+            StartType::Boot => 0,
+            StartType::System => 1,
+            StartType::Automatic => 2,
+            StartType::Manual => 3,
+            StartType::Disabled => 4,
+        }
+    }
+
+    /// Keyword accepted by `sc.exe config <name> start= <keyword>`.
+    pub fn sc_keyword(&self, delayed_auto: bool) -> &'static str {
+        match self {
+            StartType::Boot => "boot",
+            StartType::System => "system",
+            StartType::Automatic if delayed_auto => "delayed-auto",
+            StartType::Automatic => "auto",
+            StartType::Manual => "demand",
+            StartType::Disabled => "disabled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceState {
+    Stopped,
+    StartPending,
+    StopPending,
+    Running,
+    Paused,
+}
+
+impl ServiceState {
+    pub fn is_running(&self) -> bool {
+        matches!(self, ServiceState::Running | ServiceState::StartPending)
+    }
+}
+
+/// Start configuration and current state of an installed service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceConfig {
+    pub start_type: StartType,
+    pub delayed_auto: bool,
+    pub state: ServiceState,
+}
+
+impl ServiceConfig {
+    #[cfg(target_os = "windows")]
+    pub fn describe(&self) -> String {
+        format!("{:?}{}, {:?}",
+            self.start_type,
+            if self.delayed_auto { " (delayed)" } else { "" },
+            self.state
+        )
+    }
+}
+
+#[cfg(target_os = "windows")]
+struct ScHandle(SC_HANDLE);
+
+#[cfg(target_os = "windows")]
+impl Drop for ScHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseServiceHandle(self.0);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn open_service(name: &str, access: u32) -> windows::core::Result<ScHandle> {
+    unsafe {
+        let manager = ScHandle(OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), SC_MANAGER_CONNECT)?);
+
+        let name_wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let service = OpenServiceW(manager.0, PCWSTR(name_wide.as_ptr()), access)?;
+
+        Ok(ScHandle(service))
+    }
+}
+
+/// Keeps the Win32 code of a service API failure; anything else becomes a
+/// generic OS error.
+#[cfg(target_os = "windows")]
+fn service_error(error: windows::core::Error, context: String) -> Error {
+    match WIN32_ERROR::from_error(&error) {
+        Some(code) => Error::os(code.0, context),
+        None => Error::Os { context, code: error.code().0 as u32, message: error.message() },
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn open_service_for(name: &str, access: u32) -> Result<ScHandle> {
+    open_service(name, access).map_err(|e| service_error(e, format!("service {}", name)))
+}
+
+#[cfg(target_os = "windows")]
+fn query_state(service: &ScHandle, name: &str) -> Result<ServiceState> {
+    unsafe {
+        let mut status = SERVICE_STATUS::default();
+        QueryServiceStatus(service.0, &mut status)
+            .map_err(|e| service_error(e, format!("status of service {}", name)))?;
+
+        Ok(match status.dwCurrentState {
+            SERVICE_RUNNING => ServiceState::Running,
+            SERVICE_START_PENDING => ServiceState::StartPending,
+            SERVICE_STOP_PENDING => ServiceState::StopPending,
+            SERVICE_STOPPED => ServiceState::Stopped,
+            _ => ServiceState::Paused,
+        })
+    }
+}
+
+/// Returns `None` when the service is not installed.
+#[cfg(target_os = "windows")]
+pub fn query(name: &str) -> Result<Option<ServiceConfig>> {
+    let service = match open_service(name, SERVICE_QUERY_CONFIG | SERVICE_QUERY_STATUS) {
+        Ok(service) => service,
+        Err(e) if WIN32_ERROR::from_error(&e) == Some(ERROR_SERVICE_DOES_NOT_EXIST) => return Ok(None),
+        Err(e) => return Err(service_error(e, format!("service {}", name))),
+    };
+
+    unsafe {
+        // First call to get size
+        let mut needed: u32 = 0;
+        let _ = QueryServiceConfigW(service.0, None, 0, &mut needed);
+
+        // u64 storage keeps the pointers inside the struct aligned
+        let mut buffer: Vec<u64> = vec![0; (needed as usize).div_ceil(8)];
+        QueryServiceConfigW(
+            service.0,
+            Some(buffer.as_mut_ptr() as *mut QUERY_SERVICE_CONFIGW),
+            needed,
+            &mut needed,
+        ).map_err(|e| service_error(e, format!("configuration of service {}", name)))?;
+
+        let config = &*(buffer.as_ptr() as *const QUERY_SERVICE_CONFIGW);
+        let start_type = StartType::from_raw(config.dwStartType.0)
+            .ok_or_else(|| Error::invalid(format!("service {}", name), format!("unknown start type {}", config.dwStartType.0)))?;
+
+        let mut delayed = SERVICE_DELAYED_AUTO_START_INFO::default();
+        let delayed_bytes = std::slice::from_raw_parts_mut(
+            &mut delayed as *mut SERVICE_DELAYED_AUTO_START_INFO as *mut u8,
+            std::mem::size_of::<SERVICE_DELAYED_AUTO_START_INFO>(),
+        );
+        let delayed_auto = QueryServiceConfig2W(
+            service.0,
+            SERVICE_CONFIG_DELAYED_AUTO_START_INFO,
+            Some(delayed_bytes),
+            &mut needed,
+        ).is_ok() && delayed.fDelayedAutostart.as_bool();
+
+        Ok(Some(ServiceConfig {
+            start_type,
+            delayed_auto,
+            state: query_state(&service, name)?,
+        }))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn query(_name: &str) -> Result<Option<ServiceConfig>> {
+    Err(Error::unsupported("Service management"))
+}
+
+#[cfg(target_os = "windows")]
+fn wait_for_state(service: &ScHandle, name: &str, target: ServiceState) -> Result<()> {
+    for _ in 0..60 {
+        if query_state(service, name)? == target {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    }
+    Err(Error::os(ERROR_SERVICE_REQUEST_TIMEOUT.0, format!("service {} did not become {:?}", name, target)))
+}
+
+#[cfg(target_os = "windows")]
+pub fn start(name: &str) -> Result<()> {
+    let service = open_service_for(name, SERVICE_START | SERVICE_QUERY_STATUS)?;
+
+    unsafe {
+        if let Err(e) = StartServiceW(service.0, None) {
+            if WIN32_ERROR::from_error(&e) != Some(ERROR_SERVICE_ALREADY_RUNNING) {
+                return Err(service_error(e, format!("starting service {}", name)));
+            }
+        }
+    }
+
+    wait_for_state(&service, name, ServiceState::Running)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn start(_name: &str) -> Result<()> {
+    Err(Error::unsupported("Service management"))
+}
+
+#[cfg(target_os = "windows")]
+pub fn stop(name: &str) -> Result<()> {
+    let service = open_service_for(name, SERVICE_STOP | SERVICE_QUERY_STATUS)?;
+
+    unsafe {
+        let mut status = SERVICE_STATUS::default();
+        if let Err(e) = ControlService(service.0, SERVICE_CONTROL_STOP, &mut status) {
+            if WIN32_ERROR::from_error(&e) != Some(ERROR_SERVICE_NOT_ACTIVE) {
+                return Err(service_error(e, format!("stopping service {}", name)));
+            }
+        }
+    }
+
+    wait_for_state(&service, name, ServiceState::Stopped)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn stop(_name: &str) -> Result<()> {
+    Err(Error::unsupported("Service management"))
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_start_type(name: &str, start_type: StartType, delayed_auto: bool) -> Result<()> {
+    let service = open_service_for(name, SERVICE_CHANGE_CONFIG)?;
+
+    unsafe {
+        ChangeServiceConfigW(
+            service.0,
+            ENUM_SERVICE_TYPE(SERVICE_NO_CHANGE),
+            SERVICE_START_TYPE(start_type.raw()),
+            SERVICE_ERROR(SERVICE_NO_CHANGE),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            None,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            PCWSTR::null(),
+        ).map_err(|e| service_error(e, format!("start type of service {}", name)))?;
+
+        // The delayed flag only means something for automatic services
+        if start_type == StartType::Automatic {
+            let info = SERVICE_DELAYED_AUTO_START_INFO {
+                fDelayedAutostart: BOOL::from(delayed_auto),
+            };
+            ChangeServiceConfig2W(
+                service.0,
+                SERVICE_CONFIG_DELAYED_AUTO_START_INFO,
+                Some(&info as *const SERVICE_DELAYED_AUTO_START_INFO as *const core::ffi::c_void),
+            ).map_err(|e| service_error(e, format!("delayed start of service {}", name)))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_start_type(_name: &str, _start_type: StartType, _delayed_auto: bool) -> Result<()> {
+    Err(Error::unsupported("Service management"))
+}